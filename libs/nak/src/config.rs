@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
+use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 fn get_home() -> String {
     std::env::var("HOME").unwrap_or_default()
@@ -29,6 +33,9 @@ fn default_data_path() -> String {
 // Main App Config - stored in ~/.config/nak/config.json
 // ============================================================================
 
+/// Numbers the temp files of saves made by this process
+static SAVE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct AppConfig {
     pub selected_proton: Option<String>,
@@ -49,6 +56,10 @@ pub struct AppConfig {
     /// If empty/not set, uses the most recently active account
     #[serde(default)]
     pub selected_steam_account: String,
+    /// Extra folders holding GOG games installed outside Heroic
+    /// (e.g. a mounted Windows "GOG Games" folder)
+    #[serde(default)]
+    pub gog_library_roots: Vec<String>,
 }
 
 impl Default for AppConfig {
@@ -60,6 +71,7 @@ impl Default for AppConfig {
             steam_migration_shown: false,
             cache_location: String::new(),
             selected_steam_account: String::new(),
+            gog_library_roots: Vec::new(),
         }
     }
 }
@@ -114,7 +126,14 @@ impl AppConfig {
             let _ = fs::create_dir_all(parent);
         }
         if let Ok(json) = serde_json::to_string_pretty(self) {
-            let _ = fs::write(path, json);
+            // Each save writes its own temp file and renames it into place,
+            // so concurrent saves (in this or another NaK process) never leave
+            // a torn file; the last rename wins
+            let n = SAVE_COUNTER.fetch_add(1, Ordering::Relaxed);
+            let tmp = path.with_extension(format!("json.{}.{}.tmp", std::process::id(), n));
+            if fs::write(&tmp, json).is_err() || fs::rename(&tmp, &path).is_err() {
+                let _ = fs::remove_file(&tmp);
+            }
         }
     }
 
@@ -142,13 +161,115 @@ impl AppConfig {
         }
     }
 
+    /// Get the cache directory for one game's install (<cache dir>/<app_id>/),
+    /// so parallel installs into different prefixes keep their files apart
+    pub fn get_cache_dir_for_app(app_id: u32) -> PathBuf {
+        Self::load().get_cache_dir().join(app_id.to_string())
+    }
+
     /// Get path to tmp directory (~/.cache/nak/tmp/)
     pub fn get_tmp_path() -> PathBuf {
         Self::get_default_cache_dir().join("tmp")
     }
 
+    /// Get path to one game's tmp directory (~/.cache/nak/tmp/<app_id>/)
+    pub fn get_tmp_path_for_app(app_id: u32) -> PathBuf {
+        Self::get_tmp_path().join(app_id.to_string())
+    }
+
     /// Get path to Prefixes directory (legacy - for migration detection only)
     pub fn get_prefixes_path(&self) -> PathBuf {
         self.get_data_path().join("Prefixes")
     }
 }
+
+// ============================================================================
+// Instance / Prefix Locks - advisory flock()s under ~/.config/nak/
+// ============================================================================
+
+/// An exclusive advisory lock, released when dropped (or when the process exits)
+pub struct NakLock {
+    _file: fs::File,
+    path: PathBuf,
+}
+
+impl NakLock {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Take the app-wide lock (~/.config/nak/lock).
+///
+/// Fails immediately if another NaK instance holds it.
+pub fn acquire_lock() -> Result<NakLock, Box<dyn Error>> {
+    lock_file(&AppConfig::get_config_dir().join("lock"))
+        .map_err(|_| "Another NaK instance is already running".into())
+}
+
+/// Take the lock for one prefix, so two installs never touch it at once.
+///
+/// Fails immediately if another operation on the prefix holds it.
+pub fn acquire_prefix_lock(prefix_path: &Path) -> Result<NakLock, Box<dyn Error>> {
+    let canonical = prefix_path
+        .canonicalize()
+        .unwrap_or_else(|_| prefix_path.to_path_buf());
+    let name = format!("{:016x}.lock", fnv1a(canonical.to_string_lossy().as_bytes()));
+    let path = AppConfig::get_config_dir().join("locks").join(name);
+
+    lock_file(&path).map_err(|_| {
+        format!(
+            "Another operation is already running on prefix {}",
+            prefix_path.display()
+        )
+        .into()
+    })
+}
+
+fn lock_file(path: &Path) -> Result<NakLock, Box<dyn Error>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)?;
+
+    // SAFETY: flock on a file descriptor we own
+    let rc = unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) };
+    if rc != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+
+    // Record the holder for anyone debugging a stuck lock
+    let _ = file.set_len(0);
+    let _ = writeln!(file, "{}", std::process::id());
+
+    Ok(NakLock {
+        _file: file,
+        path: path.to_path_buf(),
+    })
+}
+
+/// Stable 64-bit FNV-1a hash (lock file names must match across builds)
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_is_exclusive() {
+        let path = std::env::temp_dir().join(format!("nak-test-lock-{}", std::process::id()));
+        let first = lock_file(&path).unwrap();
+        assert!(lock_file(&path).is_err());
+        drop(first);
+        assert!(lock_file(&path).is_ok());
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -4,12 +4,17 @@
 
 pub mod tools;
 
+use std::collections::VecDeque;
 use std::error::Error;
+use std::io::{BufRead, BufReader, Read};
 use std::path::Path;
+use std::process::Stdio;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
 
 use crate::config::AppConfig;
+use crate::installers::{InstallError, InstallErrorKind};
 use crate::logging::{log_error, log_install};
 use crate::runtime_wrap;
 use crate::steam::SteamProton;
@@ -32,6 +37,46 @@ pub const STANDARD_VERBS: &[&str] = &[
     "xact_x64",       // XACT Audio (64-bit)
 ];
 
+/// A verb that can be installed on its own, with rounded sizes of the
+/// current redistributable for disk space estimates
+#[derive(Debug, Clone, Copy)]
+pub struct SupportedVerb {
+    pub name: &'static str,
+    /// Winetricks cache folder the download lands in. Verbs sharing one (the
+    /// DirectX June 2010 redistributable) only download it once.
+    pub cache: &'static str,
+    pub download_mib: u64,
+    pub installed_mib: u64,
+}
+
+/// Verbs that can be installed on their own: the standard set plus the
+/// game-specific runtimes NaK installs for known games
+pub const SUPPORTED_VERBS: &[SupportedVerb] = &[
+    SupportedVerb { name: "vcrun2022", cache: "vcrun2022", download_mib: 25, installed_mib: 60 },
+    SupportedVerb { name: "vcrun2013", cache: "vcrun2013", download_mib: 13, installed_mib: 30 },
+    SupportedVerb { name: "vcrun2012", cache: "vcrun2012", download_mib: 13, installed_mib: 30 },
+    SupportedVerb { name: "vcrun2010", cache: "vcrun2010", download_mib: 15, installed_mib: 30 },
+    SupportedVerb { name: "vcrun2008", cache: "vcrun2008", download_mib: 7, installed_mib: 20 },
+    SupportedVerb { name: "dotnet6", cache: "dotnet6", download_mib: 55, installed_mib: 70 },
+    SupportedVerb { name: "dotnet7", cache: "dotnet7", download_mib: 55, installed_mib: 75 },
+    SupportedVerb { name: "dotnet8", cache: "dotnet8", download_mib: 55, installed_mib: 75 },
+    SupportedVerb { name: "dotnetdesktop6", cache: "dotnetdesktop6", download_mib: 55, installed_mib: 150 },
+    SupportedVerb { name: "dotnet48", cache: "dotnet48", download_mib: 120, installed_mib: 300 },
+    SupportedVerb { name: "d3dcompiler_47", cache: "d3dcompiler_47", download_mib: 50, installed_mib: 10 },
+    SupportedVerb { name: "d3dcompiler_43", cache: "directx9", download_mib: 96, installed_mib: 5 },
+    SupportedVerb { name: "d3dx9", cache: "directx9", download_mib: 96, installed_mib: 60 },
+    SupportedVerb { name: "d3dx11_43", cache: "directx9", download_mib: 96, installed_mib: 5 },
+    SupportedVerb { name: "xact", cache: "directx9", download_mib: 96, installed_mib: 15 },
+    SupportedVerb { name: "xact_x64", cache: "directx9", download_mib: 96, installed_mib: 15 },
+    SupportedVerb { name: "mf", cache: "win7sp1", download_mib: 900, installed_mib: 60 },
+    SupportedVerb { name: "wmp11", cache: "wmp11", download_mib: 25, installed_mib: 40 },
+];
+
+/// Look up a verb in [`SUPPORTED_VERBS`]
+pub fn supported_verb(name: &str) -> Option<&'static SupportedVerb> {
+    SUPPORTED_VERBS.iter().find(|v| v.name == name)
+}
+
 
 /// Run winetricks to install dependencies
 pub fn run_winetricks(
@@ -48,11 +93,11 @@ pub fn run_winetricks(
     ensure_cabextract()?;
 
     let Some(wine_bin) = proton.wine_binary() else {
-        return Err("Wine binary not found in Proton".into());
+        return Err(InstallError::new(InstallErrorKind::ProtonNotFound, "Wine binary not found in Proton").into());
     };
 
     let Some(wineserver_bin) = proton.wineserver_binary() else {
-        return Err("Wineserver binary not found in Proton".into());
+        return Err(InstallError::new(InstallErrorKind::ProtonNotFound, "Wineserver binary not found in Proton").into());
     };
 
     let cache_dir = AppConfig::get_default_cache_dir();
@@ -81,7 +126,7 @@ pub fn run_winetricks(
     if !status.success() {
         let err_msg = format!("Winetricks failed with exit code: {:?}", status.code());
         log_error(&err_msg);
-        return Err(err_msg.into());
+        return Err(InstallError::new(InstallErrorKind::Other, err_msg).into());
     }
 
     log_install("Winetricks completed successfully");
@@ -104,6 +149,29 @@ pub fn run_winetricks_cancellable(
     verbs: &[&str],
     log_callback: impl Fn(String),
     cancel_flag: &Arc<AtomicBool>,
+) -> Result<(), Box<dyn Error>> {
+    run_winetricks_with_progress(prefix_path, proton, verbs, log_callback, |_| {}, cancel_flag)
+}
+
+/// Lines of winetricks output kept for the log when it fails
+const OUTPUT_TAIL_LINES: usize = 20;
+
+/// How long to keep reading output after winetricks exits
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Run winetricks with cancellation support, following its output.
+///
+/// `progress_callback` gets 0.0 to 1.0 as winetricks works through `verbs`
+/// (see [`WinetricksProgress`]), and `log_callback` an "Installing <verb>"
+/// line as each one starts. The last lines of output are logged if
+/// winetricks fails.
+pub fn run_winetricks_with_progress(
+    prefix_path: &Path,
+    proton: &SteamProton,
+    verbs: &[&str],
+    log_callback: impl Fn(String),
+    progress_callback: impl Fn(f32),
+    cancel_flag: &Arc<AtomicBool>,
 ) -> Result<(), Box<dyn Error>> {
     if verbs.is_empty() {
         return Ok(());
@@ -113,11 +181,11 @@ pub fn run_winetricks_cancellable(
     ensure_cabextract()?;
 
     let Some(wine_bin) = proton.wine_binary() else {
-        return Err("Wine binary not found in Proton".into());
+        return Err(InstallError::new(InstallErrorKind::ProtonNotFound, "Wine binary not found in Proton").into());
     };
 
     let Some(wineserver_bin) = proton.wineserver_binary() else {
-        return Err("Wineserver binary not found in Proton".into());
+        return Err(InstallError::new(InstallErrorKind::ProtonNotFound, "Wineserver binary not found in Proton").into());
     };
 
     let cache_dir = AppConfig::get_default_cache_dir();
@@ -141,28 +209,170 @@ pub fn run_winetricks_cancellable(
     let mut child = runtime_wrap::build_command(&winetricks_path, &envs)
         .arg("-q")
         .args(verbs)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
         .spawn()?;
 
-    loop {
-        match child.try_wait()? {
-            Some(status) => {
-                if !status.success() {
-                    let err_msg = format!("Winetricks failed with exit code: {:?}", status.code());
-                    log_error(&err_msg);
-                    return Err(err_msg.into());
+    // winetricks writes to both streams; merge them line by line
+    // The reader threads are never joined: they end once every process
+    // holding the pipes has exited, or at their next line after rx is dropped
+    let (tx, rx) = mpsc::channel::<String>();
+    for stream in [
+        child.stdout.take().map(|s| Box::new(s) as Box<dyn Read + Send>),
+        child.stderr.take().map(|s| Box::new(s) as Box<dyn Read + Send>),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        let tx = tx.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                if tx.send(line).is_err() {
+                    break;
                 }
-                log_install("Winetricks completed successfully");
-                return Ok(());
             }
-            None => {
-                if cancel_flag.load(Ordering::Relaxed) {
-                    let _ = child.kill();
-                    let _ = child.wait();
-                    return Err("Cancelled".into());
-                }
-                std::thread::sleep(std::time::Duration::from_millis(250));
+        });
+    }
+    drop(tx);
+
+    let mut tracker = WinetricksProgress::new(verbs);
+    let mut tail: VecDeque<String> = VecDeque::with_capacity(OUTPUT_TAIL_LINES);
+    let mut handle_line = |line: String| {
+        if let Some(verb) = tracker.feed(&line) {
+            log_callback(format!("Installing {}...", verb));
+        }
+        progress_callback(tracker.progress());
+        if tail.len() == OUTPUT_TAIL_LINES {
+            tail.pop_front();
+        }
+        tail.push_back(line);
+    };
+
+    let status = loop {
+        while let Ok(line) = rx.try_recv() {
+            handle_line(line);
+        }
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if cancel_flag.load(Ordering::Relaxed) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(InstallError::cancelled().into());
+        }
+        std::thread::sleep(Duration::from_millis(250));
+    };
+
+    // Output still buffered in the pipes after exit. Wine processes started
+    // by winetricks (wineserver) inherit the pipes and can hold them open
+    // long after, so only wait a short while for the rest.
+    let deadline = Instant::now() + OUTPUT_DRAIN_TIMEOUT;
+    while !cancel_flag.load(Ordering::Relaxed) {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        match rx.recv_timeout(remaining.min(Duration::from_millis(250))) {
+            Ok(line) => handle_line(line),
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    if !status.success() {
+        let err_msg = format!("Winetricks failed with exit code: {:?}", status.code());
+        log_error(&err_msg);
+        for line in &tail {
+            log_error(&format!("  winetricks: {}", line));
+        }
+        return Err(InstallError::new(winetricks_failure_kind(&tail), err_msg).into());
+    }
+    progress_callback(1.0);
+    log_install("Winetricks completed successfully");
+    Ok(())
+}
+
+/// Why a winetricks run failed, judging by the last lines it printed.
+///
+/// A verb whose installer couldn't be fetched is a network failure;
+/// anything else is reported as [`InstallErrorKind::Other`].
+fn winetricks_failure_kind(tail: &VecDeque<String>) -> InstallErrorKind {
+    const DOWNLOAD_FAILURES: &[&str] = &[
+        "curl: (",
+        "wget: unable to resolve",
+        "unable to establish ssl connection",
+        "failed to download",
+        "sha256sum mismatch",
+    ];
+    let network = tail.iter().any(|line| {
+        let line = line.to_lowercase();
+        DOWNLOAD_FAILURES.iter().any(|pattern| line.contains(pattern))
+    });
+    if network {
+        InstallErrorKind::Network
+    } else {
+        InstallErrorKind::Other
+    }
+}
+
+/// Estimates how far a winetricks run is from its output.
+///
+/// winetricks prints `Executing load_<verb>` as it starts a verb and
+/// `<verb> already installed, skipping` for ones it skips. Each requested
+/// verb gets an equal share of the bar; within a verb, every further
+/// `Executing` line (downloads, installers, wine calls) moves the bar part of
+/// the way towards the next verb without reaching it.
+pub struct WinetricksProgress<'a> {
+    verbs: &'a [&'a str],
+    /// Verbs finished or skipped before the current one
+    done: usize,
+    /// Commands run since the current verb started
+    steps: u32,
+}
+
+impl<'a> WinetricksProgress<'a> {
+    pub fn new(verbs: &'a [&'a str]) -> Self {
+        Self { verbs, done: 0, steps: 0 }
+    }
+
+    /// Take one line of output; returns the verb if this line started one
+    pub fn feed(&mut self, line: &str) -> Option<&'a str> {
+        let line = line.trim();
+        if let Some(rest) = line.strip_suffix(" already installed, skipping") {
+            if let Some(i) = self.index_of(rest) {
+                self.done = self.done.max(i + 1);
+                self.steps = 0;
             }
+            return None;
         }
+
+        let command = line.strip_prefix("Executing ")?;
+        let started = command
+            .strip_prefix("load_")
+            .and_then(|rest| rest.split_whitespace().next())
+            .and_then(|verb| self.index_of(verb));
+        match started {
+            Some(i) if i >= self.done => {
+                self.done = i;
+                self.steps = 0;
+                Some(self.verbs[i])
+            }
+            // A verb's own helpers (and dependencies it loads) are sub-steps
+            _ => {
+                self.steps += 1;
+                None
+            }
+        }
+    }
+
+    /// Fraction of the run done, 0.0 to 1.0
+    pub fn progress(&self) -> f32 {
+        let within = self.steps as f32 / (self.steps as f32 + 4.0);
+        ((self.done as f32 + within) / self.verbs.len().max(1) as f32).min(1.0)
+    }
+
+    fn index_of(&self, verb: &str) -> Option<usize> {
+        self.verbs.iter().position(|v| *v == verb)
     }
 }
 
@@ -175,3 +385,45 @@ pub fn install_standard_deps_cancellable(
 ) -> Result<(), Box<dyn Error>> {
     run_winetricks_cancellable(prefix_path, proton, STANDARD_VERBS, log_callback, cancel_flag)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{winetricks_failure_kind, WinetricksProgress};
+    use crate::installers::InstallErrorKind;
+    use std::collections::VecDeque;
+
+    #[test]
+    fn test_winetricks_progress() {
+        let verbs = ["vcrun2022", "d3dx9", "xact"];
+        let mut tracker = WinetricksProgress::new(&verbs);
+        assert_eq!(tracker.progress(), 0.0);
+
+        assert_eq!(tracker.feed("Executing w_do_call vcrun2022"), None);
+        assert_eq!(tracker.feed("Executing load_vcrun2022 "), Some("vcrun2022"));
+        assert_eq!(tracker.progress(), 0.0);
+        tracker.feed("Executing cabextract -q --directory=/tmp vc_redist.x64.exe");
+        tracker.feed("Executing wine vc_redist.x64.exe /q");
+        let within = tracker.progress();
+        assert!(within > 0.0 && within < 1.0 / 3.0);
+
+        assert_eq!(tracker.feed("d3dx9 already installed, skipping"), None);
+        assert!((tracker.progress() - 2.0 / 3.0).abs() < 1e-6);
+        assert_eq!(tracker.feed("Executing load_xact"), Some("xact"));
+        // Unrelated output and earlier verbs don't move it backwards
+        tracker.feed("warning: some warning");
+        tracker.feed("Executing load_vcrun2022");
+        assert!(tracker.progress() >= 2.0 / 3.0);
+    }
+
+    #[test]
+    fn test_winetricks_failure_kind() {
+        let tail = |lines: &[&str]| lines.iter().map(|l| l.to_string()).collect::<VecDeque<_>>();
+        let offline = tail(&[
+            "Downloading https://aka.ms/vs/17/release/vc_redist.x64.exe to /cache/vcrun2022",
+            "curl: (6) Could not resolve host: aka.ms",
+        ]);
+        assert_eq!(winetricks_failure_kind(&offline), InstallErrorKind::Network);
+        let crashed = tail(&["Executing wine vc_redist.x64.exe /q", "wine: Unhandled page fault"]);
+        assert_eq!(winetricks_failure_kind(&crashed), InstallErrorKind::Other);
+    }
+}
@@ -10,6 +10,7 @@ use std::os::unix::fs::PermissionsExt;
 use std::path::PathBuf;
 use std::process::Command;
 
+use crate::installers::{InstallError, InstallErrorKind};
 use crate::logging::{log_error, log_info, log_warning};
 
 // ============================================================================
@@ -90,7 +91,7 @@ pub fn ensure_winetricks() -> Result<PathBuf, Box<dyn Error>> {
             if winetricks_path.exists() {
                 log_warning(&format!("Failed to check winetricks updates: {}", e));
             } else {
-                return Err(format!("Failed to download winetricks: {}", e).into());
+                return Err(InstallError::new(InstallErrorKind::Network, format!("Failed to download winetricks: {}", e)).into());
             }
         }
     }
@@ -130,9 +131,9 @@ pub fn ensure_cabextract() -> Result<PathBuf, Box<dyn Error>> {
     fs::create_dir_all(&bin_dir)?;
 
     let response = ureq::get(CABEXTRACT_URL).call().map_err(|e| {
-        format!(
-            "Failed to download cabextract: {}. Please install cabextract manually.",
-            e
+        InstallError::new(
+            InstallErrorKind::Network,
+            format!("Failed to download cabextract: {}. Please install cabextract manually.", e),
         )
     })?;
 
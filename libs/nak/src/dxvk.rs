@@ -1,16 +1,27 @@
 //! DXVK configuration management for Fluorine Manager.
 //!
 //! Downloads dxvk.conf from upstream, appends Fluorine-specific settings,
-//! and stores at `~/.local/share/fluorine/config/dxvk.conf`.
+//! and stores at `~/.local/share/fluorine/config/dxvk.conf`. The upstream
+//! file is pinned to a DXVK release, recorded in the first line of the
+//! stored copy so an outdated copy can be refreshed.
+//! Also pushes a config to several games at once and handles cleanup of
+//! per-game DXVK state caches.
 
 use std::error::Error;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::game_finder::{Game, Launcher};
 use crate::logging::{log_info, log_warning};
+use crate::steam::{
+    parse_env_assignment, read_all_launch_options, set_env_assignment, write_launch_options,
+};
 
-const DXVK_CONF_URL: &str =
-    "https://raw.githubusercontent.com/doitsujin/dxvk/master/dxvk.conf";
+/// DXVK release whose dxvk.conf is bundled
+pub const DXVK_VERSION: &str = "2.5.3";
+
+/// First line of a stored config, followed by the DXVK version
+const VERSION_STAMP: &str = "# Fluorine dxvk.conf from DXVK v";
 
 const DXVK_CUSTOM_SETTINGS: &str = r#"
 # Fluorine Custom Settings
@@ -37,33 +48,320 @@ pub fn ensure_dxvk_conf() -> Result<PathBuf, Box<dyn Error>> {
     download_and_create_dxvk_conf(&conf_path)
 }
 
+/// DXVK version the stored dxvk.conf was taken from.
+///
+/// None if there is no config yet, or it predates version stamps or was
+/// written without the upstream part after a failed download.
+pub fn dxvk_conf_version() -> Option<String> {
+    let content = fs::read_to_string(get_dxvk_conf_path()).ok()?;
+    let version = content.lines().next()?.strip_prefix(VERSION_STAMP)?.trim();
+    (!version.is_empty()).then(|| version.to_string())
+}
+
+/// Re-download dxvk.conf if `force` is set or the stored copy is older than
+/// [`DXVK_VERSION`] (or missing, or unstamped).
+///
+/// Unlike [`ensure_dxvk_conf`], a failed download is an error and leaves the
+/// existing file alone. Returns whether the config was rewritten.
+pub fn update_dxvk_conf(force: bool) -> Result<bool, Box<dyn Error>> {
+    let conf_path = get_dxvk_conf_path();
+    let outdated = dxvk_conf_version()
+        .is_none_or(|current| version_parts(&current) < version_parts(DXVK_VERSION));
+    if !force && !outdated {
+        return Ok(false);
+    }
+
+    let upstream = fetch_upstream_conf()?;
+    write_conf(&conf_path, Some(&upstream))?;
+    log_info(&format!("Updated dxvk.conf to DXVK v{}", DXVK_VERSION));
+    Ok(true)
+}
+
 /// Download the upstream dxvk.conf, append custom settings, and write to `dest`.
 pub fn download_and_create_dxvk_conf(dest: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let upstream = match fetch_upstream_conf() {
+        Ok(body) => Some(body),
+        Err(e) => {
+            log_warning(&format!("Failed to download dxvk.conf: {}", e));
+            // Create with just custom settings if download fails
+            None
+        }
+    };
+
+    write_conf(dest, upstream.as_deref())?;
+    log_info(&format!("Created dxvk.conf at {:?}", dest));
+    Ok(dest.to_path_buf())
+}
+
+fn fetch_upstream_conf() -> Result<String, Box<dyn Error>> {
+    log_info(&format!("Downloading dxvk.conf for DXVK v{}...", DXVK_VERSION));
+    let url = format!(
+        "https://raw.githubusercontent.com/doitsujin/dxvk/v{}/dxvk.conf",
+        DXVK_VERSION
+    );
+    let mut body = String::new();
+    ureq::get(&url).call()?.into_reader().read_to_string(&mut body)?;
+    Ok(body)
+}
+
+/// Write the stamped upstream config (if any) plus custom settings to `dest`
+fn write_conf(dest: &Path, upstream: Option<&str>) -> Result<(), Box<dyn Error>> {
     // Ensure parent directory exists
     if let Some(parent) = dest.parent() {
         fs::create_dir_all(parent)?;
     }
 
-    log_info("Downloading dxvk.conf from upstream...");
+    let full_content = match upstream {
+        Some(upstream) => format!(
+            "{}{}\n{}\n{}",
+            VERSION_STAMP, DXVK_VERSION, upstream, DXVK_CUSTOM_SETTINGS
+        ),
+        None => format!("\n{}", DXVK_CUSTOM_SETTINGS),
+    };
+
+    // Games may read the file at any time; never leave it half written
+    let tmp = dest.with_extension("conf.tmp");
+    fs::write(&tmp, &full_content)?;
+    fs::rename(&tmp, dest)?;
+    Ok(())
+}
+
+/// "2.5.3" -> [2, 5, 3] for ordering versions
+fn version_parts(version: &str) -> Vec<u32> {
+    version
+        .split('.')
+        .map(|part| part.trim().parse().unwrap_or(0))
+        .collect()
+}
+
+// ============================================================================
+// Batch Apply
+// ============================================================================
+
+/// How a DXVK config was applied to a game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfApplyMethod {
+    /// `DXVK_CONFIG_FILE` set in the game's Steam launch options
+    LaunchOptions,
+    /// Copied to `dxvk.conf` in the game's install directory
+    InstallDir,
+}
 
-    let upstream_content = match ureq::get(DXVK_CONF_URL).call() {
-        Ok(response) => {
-            let mut body = String::new();
-            response.into_reader().read_to_string(&mut body)?;
-            body
+/// Outcome of applying a DXVK config to one game
+#[derive(Debug, Clone)]
+pub struct ConfApplyResult {
+    pub app_id: String,
+    pub name: String,
+    pub result: Result<ConfApplyMethod, String>,
+}
+
+/// Point several games at the same DXVK config.
+///
+/// Steam games get `DXVK_CONFIG_FILE` in their launch options (one write to
+/// localconfig.vdf, so Steam must be closed). Other games get a copy as
+/// `dxvk.conf` in their install directory, which DXVK reads when it is the
+/// game's working directory; a differing existing file is kept as
+/// `dxvk.conf.nak-bak`.
+pub fn apply_conf_to_prefixes(conf_path: &Path, games: &[Game]) -> Vec<ConfApplyResult> {
+    let mut results = Vec::new();
+    if !conf_path.is_file() {
+        let error = format!("DXVK config not found: {}", conf_path.display());
+        return games
+            .iter()
+            .map(|g| ConfApplyResult {
+                app_id: g.app_id.clone(),
+                name: g.name.clone(),
+                result: Err(error.clone()),
+            })
+            .collect();
+    }
+
+    let conf_value = crate::config::normalize_path_for_steam(&conf_path.to_string_lossy());
+    let current = read_all_launch_options();
+    let mut updates: Vec<(u32, String)> = Vec::new();
+    let mut pending: Vec<usize> = Vec::new();
+
+    for game in games {
+        let steam_id = match game.launcher {
+            Launcher::Steam { .. } => game.app_id.parse::<u32>().ok(),
+            _ => None,
+        };
+        let result = match steam_id {
+            Some(app_id) => {
+                let existing = current.get(&game.app_id).map(String::as_str).unwrap_or("");
+                let updated = set_env_assignment(existing, "DXVK_CONFIG_FILE", Some(&conf_value));
+                if updated != existing {
+                    updates.push((app_id, updated));
+                }
+                pending.push(results.len());
+                Ok(ConfApplyMethod::LaunchOptions)
+            }
+            None => copy_conf_to_install(conf_path, &game.install_path)
+                .map(|_| ConfApplyMethod::InstallDir)
+                .map_err(|e| e.to_string()),
+        };
+        results.push(ConfApplyResult {
+            app_id: game.app_id.clone(),
+            name: game.name.clone(),
+            result,
+        });
+    }
+
+    if !updates.is_empty() {
+        if let Err(e) = write_launch_options(&updates) {
+            let error = format!("Failed to update launch options: {}", e);
+            for i in pending {
+                results[i].result = Err(error.clone());
+            }
         }
-        Err(e) => {
-            log_warning(&format!("Failed to download dxvk.conf: {}", e));
-            // Create with just custom settings if download fails
-            String::new()
+    }
+
+    let applied = results.iter().filter(|r| r.result.is_ok()).count();
+    log_info(&format!(
+        "Applied DXVK config {} to {}/{} game(s)",
+        conf_path.display(),
+        applied,
+        results.len()
+    ));
+    results
+}
+
+/// Copy a DXVK config to `<install>/dxvk.conf`, backing up a differing one
+fn copy_conf_to_install(conf_path: &Path, install_path: &Path) -> Result<(), Box<dyn Error>> {
+    if !install_path.is_dir() {
+        return Err(format!("Install directory not found: {}", install_path.display()).into());
+    }
+
+    let content = fs::read(conf_path)?;
+    let dest = install_path.join("dxvk.conf");
+    if let Ok(existing) = fs::read(&dest) {
+        if existing == content {
+            return Ok(());
         }
-    };
+        let backup = install_path.join("dxvk.conf.nak-bak");
+        if !backup.exists() {
+            fs::copy(&dest, &backup)?;
+        }
+    }
 
-    let full_content = format!("{}\n{}", upstream_content, DXVK_CUSTOM_SETTINGS);
-    fs::write(dest, &full_content)?;
+    let tmp = install_path.join("dxvk.conf.nak-tmp");
+    fs::write(&tmp, &content)?;
+    fs::rename(&tmp, &dest)?;
+    Ok(())
+}
 
-    log_info(&format!("Created dxvk.conf at {:?}", dest));
-    Ok(dest.to_path_buf())
+// ============================================================================
+// State Cache Cleanup
+// ============================================================================
+
+/// File extension DXVK uses for its pipeline state cache
+const STATE_CACHE_EXTENSION: &str = "dxvk-cache";
+
+/// Remove a game's DXVK state cache files.
+///
+/// Looks in the game's install directory, its prefix and Steam's per-game
+/// shadercache (where Proton points `DXVK_STATE_CACHE_PATH`), removing every
+/// `*.dxvk-cache` file there. A `DXVK_STATE_CACHE_PATH` set in the game's
+/// launch options may be shared with other games, so only the caches named
+/// after the game's own executables are removed from it.
+///
+/// Returns the number of bytes freed.
+pub fn clear_state_cache(game: &Game) -> Result<u64, Box<dyn Error>> {
+    let mut freed = 0u64;
+    let mut removed = 0usize;
+
+    for dir in state_cache_dirs(game) {
+        for entry in walkdir::WalkDir::new(&dir).into_iter().flatten() {
+            let path = entry.path();
+            if !entry.file_type().is_file()
+                || path.extension().and_then(|e| e.to_str()) != Some(STATE_CACHE_EXTENSION)
+            {
+                continue;
+            }
+
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            fs::remove_file(path)?;
+            freed += size;
+            removed += 1;
+        }
+    }
+
+    if let Some(shared) = launch_options_state_cache_dir(game) {
+        for name in exe_state_cache_names(&game.install_path) {
+            let path = shared.join(name);
+            let Ok(meta) = fs::symlink_metadata(&path) else {
+                continue;
+            };
+            if !meta.is_file() {
+                continue;
+            }
+            fs::remove_file(&path)?;
+            freed += meta.len();
+            removed += 1;
+        }
+    }
+
+    log_info(&format!(
+        "Cleared {} DXVK state cache file(s) for {} ({} bytes freed)",
+        removed, game.name, freed
+    ));
+    Ok(freed)
+}
+
+/// Directories that may contain a game's DXVK state cache
+fn state_cache_dirs(game: &Game) -> Vec<PathBuf> {
+    let mut dirs = vec![game.install_path.clone()];
+
+    if let Some(prefix) = &game.prefix_path {
+        dirs.push(prefix.clone());
+
+        // Steam prefixes live at steamapps/compatdata/<appid>/pfx
+        if let Some(steamapps) = prefix.ancestors().nth(3) {
+            dirs.push(
+                steamapps
+                    .join("shadercache")
+                    .join(&game.app_id)
+                    .join("DXVK_state_cache"),
+            );
+        }
+    }
+
+    dirs.retain(|d| d.is_dir());
+    dirs.dedup();
+    dirs
+}
+
+/// `DXVK_STATE_CACHE_PATH` from a Steam game's launch options, if it names
+/// an existing directory
+fn launch_options_state_cache_dir(game: &Game) -> Option<PathBuf> {
+    if !matches!(game.launcher, Launcher::Steam { .. }) {
+        return None;
+    }
+    let options = read_all_launch_options().remove(&game.app_id)?;
+    let dir = PathBuf::from(parse_env_assignment(&options, "DXVK_STATE_CACHE_PATH")?);
+    dir.is_dir().then_some(dir)
+}
+
+/// State cache file names DXVK uses for the executables in an install dir
+/// (`Game.exe` -> `Game.dxvk-cache`)
+fn exe_state_cache_names(install_path: &Path) -> Vec<String> {
+    let mut names: Vec<String> = walkdir::WalkDir::new(install_path)
+        .into_iter()
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            let path = e.path();
+            let is_exe = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("exe"));
+            let stem = path.file_stem()?.to_str()?;
+            is_exe.then(|| format!("{}.{}", stem, STATE_CACHE_EXTENSION))
+        })
+        .collect();
+    names.sort();
+    names.dedup();
+    names
 }
 
 use std::io::Read as _;
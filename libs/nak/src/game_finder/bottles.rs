@@ -8,7 +8,7 @@ use std::path::{Path, PathBuf};
 
 use super::known_games::KNOWN_GAMES;
 use super::registry::{read_registry_value, wine_path_to_linux};
-use super::{Game, Launcher};
+use super::{AppType, Game, InstallState, Launcher};
 use crate::logging::log_info;
 
 /// Possible Bottles data paths
@@ -109,6 +109,13 @@ fn scan_bottle(bottle_path: &Path) -> Vec<Game> {
                 appdata_roaming_folder: known_game.appdata_roaming_folder.map(String::from),
                 registry_path: Some(known_game.registry_path.to_string()),
                 registry_value: Some(known_game.registry_value.to_string()),
+                prefix_shared: false,
+                prefix_redirected: false,
+                bundled_runtime: None,
+                family_shared: false,
+                install_size: None,
+                app_type: AppType::Game,
+                install_state: InstallState::FullyInstalled,
             });
         }
     }
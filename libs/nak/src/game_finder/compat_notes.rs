@@ -0,0 +1,52 @@
+//! Local per-game compatibility notes
+//!
+//! A small JSON store (`~/.cache/nak/compat_notes.json`) mapping app ids to
+//! free-form tips, either written by the user or cached after a frontend
+//! fetched them. Reading never touches the network.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use super::Game;
+use crate::config::AppConfig;
+
+fn notes_path() -> PathBuf {
+    AppConfig::get_default_cache_dir().join("compat_notes.json")
+}
+
+fn load_notes() -> BTreeMap<String, String> {
+    fs::read_to_string(notes_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Stored compatibility note for a game, if any
+pub fn compat_note_for(game: &Game) -> Option<String> {
+    compat_note_for_app_id(&game.app_id)
+}
+
+/// Stored compatibility note for an app id, if any
+pub fn compat_note_for_app_id(app_id: &str) -> Option<String> {
+    load_notes().remove(app_id)
+}
+
+/// Store a note for an app id. `None` or an empty note removes it.
+pub fn set_compat_note(app_id: &str, note: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let mut notes = load_notes();
+    match note.map(str::trim).filter(|n| !n.is_empty()) {
+        Some(note) => notes.insert(app_id.to_string(), note.to_string()),
+        None => notes.remove(app_id),
+    };
+
+    let path = notes_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(&notes)?)?;
+    fs::rename(&tmp, &path)?;
+    Ok(())
+}
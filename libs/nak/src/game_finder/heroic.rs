@@ -1,6 +1,7 @@
 //! Heroic Games Launcher detection
 //!
-//! Detects games installed via Heroic (GOG and Epic Games).
+//! Detects games installed via Heroic (GOG and Epic Games), native, Flatpak
+//! or Snap.
 //! Parses installed.json and GamesConfig/*.json for game and prefix info.
 
 use std::fs;
@@ -9,38 +10,71 @@ use std::path::{Path, PathBuf};
 use serde::Deserialize;
 
 use super::known_games::find_by_gog_id;
-use super::{Game, HeroicStore, Launcher};
+use super::{AppType, Game, HeroicStore, InstallState, Launcher};
 use crate::logging::{log_info, log_warning};
 
 /// Possible Heroic configuration paths
 const HEROIC_PATHS: &[&str] = &[
     ".config/heroic",                                          // Native
     ".var/app/com.heroicgameslauncher.hgl/config/heroic",      // Flatpak
+    "snap/heroic/current/.config/heroic",                      // Snap
 ];
 
-/// Detect all Heroic games
-pub fn detect_heroic_games() -> Vec<Game> {
-    let mut games = Vec::new();
-    let home = match std::env::var("HOME") {
-        Ok(h) => h,
-        Err(_) => return games,
+/// Heroic configuration directories that exist, native first.
+///
+/// Paths are canonical, so a root reachable through more than one candidate
+/// (Snap's `current` symlink, a config dir symlinked into the Flatpak
+/// sandbox) is only listed once.
+pub fn heroic_config_roots() -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = Vec::new();
+    let Ok(home) = std::env::var("HOME") else {
+        return roots;
     };
 
     for relative_path in HEROIC_PATHS {
-        let heroic_path = PathBuf::from(&home).join(relative_path);
-        if !heroic_path.exists() {
+        let Ok(root) = fs::canonicalize(PathBuf::from(&home).join(relative_path)) else {
             continue;
+        };
+        if root.is_dir() && !roots.contains(&root) {
+            roots.push(root);
         }
+    }
+    roots
+}
 
+/// Detect all Heroic games
+pub fn detect_heroic_games() -> Vec<Game> {
+    detect_heroic_games_with_roots(&[])
+}
+
+/// Detect Heroic games, plus GOG games under extra library roots.
+///
+/// Each root (e.g. a mounted Windows "GOG Games" folder) is searched for
+/// `goggame-*.info` files directly inside it or one folder down. Games
+/// Heroic already lists are not reported twice.
+pub fn detect_heroic_games_with_roots(extra_roots: &[PathBuf]) -> Vec<Game> {
+    let mut games: Vec<Game> = Vec::new();
+
+    for heroic_path in heroic_config_roots() {
         log_info(&format!("Found Heroic installation: {}", heroic_path.display()));
 
-        // Detect GOG games
-        let gog_games = detect_gog_games(&heroic_path);
-        games.extend(gog_games);
+        // A game can be listed by both a native and a Flatpak Heroic
+        let found = detect_gog_games(&heroic_path)
+            .into_iter()
+            .chain(detect_epic_games(&heroic_path));
+        for game in found {
+            if !games.iter().any(|g| g.install_path == game.install_path) {
+                games.push(game);
+            }
+        }
+    }
 
-        // Detect Epic games
-        let epic_games = detect_epic_games(&heroic_path);
-        games.extend(epic_games);
+    for root in extra_roots {
+        for game in detect_gog_root_games(root) {
+            if !games.iter().any(|g| g.app_id == game.app_id) {
+                games.push(game);
+            }
+        }
     }
 
     log_info(&format!("Heroic: Found {} installed games", games.len()));
@@ -60,6 +94,8 @@ struct GogInstalledGame {
     #[serde(rename = "install_path")]
     install_path: Option<String>,
     platform: Option<String>,
+    /// Human-readable, e.g. "38.72 GiB"
+    install_size: Option<String>,
 }
 
 /// Wrapper for Heroic's GOG installed.json format: {"installed": [...]}
@@ -126,12 +162,104 @@ fn detect_gog_games(heroic_path: &Path) -> Vec<Game> {
             appdata_roaming_folder: known_game.and_then(|g| g.appdata_roaming_folder.map(String::from)),
             registry_path: known_game.map(|g| g.registry_path.to_string()),
             registry_value: known_game.map(|g| g.registry_value.to_string()),
+            prefix_shared: false,
+            prefix_redirected: false,
+            bundled_runtime: None,
+            family_shared: false,
+            install_size: gog_game.install_size.as_deref().and_then(parse_size),
+            app_type: AppType::Game,
+            install_state: InstallState::FullyInstalled,
+        });
+    }
+
+    games
+}
+
+/// GOG Galaxy's per-game `goggame-<id>.info` manifest
+#[derive(Debug, Deserialize)]
+struct GogInfoFile {
+    #[serde(rename = "gameId")]
+    game_id: String,
+    name: Option<String>,
+}
+
+/// Detect GOG games installed outside Heroic under `root`
+fn detect_gog_root_games(root: &Path) -> Vec<Game> {
+    let mut games = Vec::new();
+    let Ok(entries) = fs::read_dir(root) else {
+        log_warning(&format!("GOG library root not readable: {}", root.display()));
+        return games;
+    };
+
+    let mut dirs = vec![root.to_path_buf()];
+    dirs.extend(entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()));
+
+    for dir in dirs {
+        let Some(info) = read_gog_info(&dir) else {
+            continue;
+        };
+        let known_game = find_by_gog_id(&info.game_id);
+        let name = info.name.unwrap_or_else(|| info.game_id.clone());
+
+        games.push(Game {
+            name,
+            app_id: info.game_id,
+            install_path: dir,
+            prefix_path: None,
+            launcher: Launcher::Heroic {
+                store: HeroicStore::GOG,
+            },
+            my_games_folder: known_game.and_then(|g| g.my_games_folder.map(String::from)),
+            appdata_local_folder: known_game.and_then(|g| g.appdata_local_folder.map(String::from)),
+            appdata_roaming_folder: known_game.and_then(|g| g.appdata_roaming_folder.map(String::from)),
+            registry_path: known_game.map(|g| g.registry_path.to_string()),
+            registry_value: known_game.map(|g| g.registry_value.to_string()),
+            prefix_shared: false,
+            prefix_redirected: false,
+            bundled_runtime: None,
+            family_shared: false,
+            install_size: None,
+            app_type: AppType::Game,
+            install_state: InstallState::FullyInstalled,
         });
     }
 
+    log_info(&format!("GOG root {}: Found {} games", root.display(), games.len()));
     games
 }
 
+/// The base game's `goggame-*.info` in a directory.
+///
+/// DLC ship their own info files next to the game's, so a file whose
+/// `rootGameId` names another game is only used when nothing else is there.
+fn read_gog_info(dir: &Path) -> Option<GogInfoFile> {
+    let mut infos: Vec<(GogInfoFile, bool)> = Vec::new();
+    for entry in fs::read_dir(dir).ok()?.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let Some(id) = file_name
+            .strip_prefix("goggame-")
+            .and_then(|rest| rest.strip_suffix(".info"))
+        else {
+            continue;
+        };
+        let Ok(content) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(&content) else {
+            continue;
+        };
+        let is_base = value
+            .get("rootGameId")
+            .and_then(|v| v.as_str())
+            .is_none_or(|root| root == id);
+        if let Ok(info) = serde_json::from_value::<GogInfoFile>(value) {
+            infos.push((info, is_base));
+        }
+    }
+    infos.sort_by_key(|(_, is_base)| !is_base);
+    infos.into_iter().next().map(|(info, _)| info)
+}
+
 // ============================================================================
 // Epic Detection
 // ============================================================================
@@ -211,6 +339,13 @@ fn detect_epic_games(heroic_path: &Path) -> Vec<Game> {
                 // Get Wine prefix
                 let prefix_path = get_heroic_game_prefix(heroic_path, app_name);
 
+                // legendary records bytes, Heroic's library cache nests it under "install"
+                let install_size = game_obj
+                    .get("install_size")
+                    .or_else(|| game_obj.get("install").and_then(|i| i.get("install_size")))
+                    .and_then(|v| v.as_u64())
+                    .filter(|&size| size != 0);
+
                 games.push(Game {
                     name,
                     app_id: app_name.clone(),
@@ -224,6 +359,13 @@ fn detect_epic_games(heroic_path: &Path) -> Vec<Game> {
                     appdata_roaming_folder: None,
                     registry_path: None,
                     registry_value: None,
+                    prefix_shared: false,
+                    prefix_redirected: false,
+                    bundled_runtime: None,
+                    family_shared: false,
+                    install_size,
+                    app_type: AppType::Game,
+                    install_state: InstallState::FullyInstalled,
                 });
             }
         }
@@ -232,6 +374,106 @@ fn detect_epic_games(heroic_path: &Path) -> Vec<Game> {
     games
 }
 
+// ============================================================================
+// Runner Detection
+// ============================================================================
+
+/// A Wine/Proton build downloaded by Heroic's wine manager
+#[derive(Debug, Clone)]
+pub struct HeroicRunner {
+    /// Display name (e.g. "GE-Proton9-20")
+    pub name: String,
+    /// Runner type as Heroic reports it (e.g. "Proton-GE", "Wine-GE"), or
+    /// the tools subfolder it was found in
+    pub runner_type: String,
+    /// Install directory of the runner
+    pub path: PathBuf,
+}
+
+/// Subfolders of `tools/` that hold runners, with the type reported for them
+const RUNNER_DIRS: &[(&str, &str)] = &[
+    ("tools/proton", "Proton"),
+    ("tools/wine", "Wine"),
+    ("tools/runtimes", "Runtime"),
+];
+
+/// List the Wine/Proton runners Heroic has downloaded, across all Heroic installations.
+pub fn list_heroic_runners() -> Vec<HeroicRunner> {
+    let mut runners: Vec<HeroicRunner> = Vec::new();
+
+    for heroic_path in heroic_config_roots() {
+        // The wine manager knows the proper type of each download
+        for runner in read_wine_manager_runners(&heroic_path) {
+            if !runners.iter().any(|r| r.path == runner.path) {
+                runners.push(runner);
+            }
+        }
+
+        // Pick up anything extracted by hand into the tools folders
+        for (subdir, runner_type) in RUNNER_DIRS {
+            let Ok(entries) = fs::read_dir(heroic_path.join(subdir)) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if !path.is_dir() || runners.iter().any(|r| r.path == path) {
+                    continue;
+                }
+                runners.push(HeroicRunner {
+                    name: entry.file_name().to_string_lossy().into_owned(),
+                    runner_type: runner_type.to_string(),
+                    path,
+                });
+            }
+        }
+    }
+
+    runners.sort_by(|a, b| a.runner_type.cmp(&b.runner_type).then(b.name.cmp(&a.name)));
+    runners
+}
+
+/// Release entry from Heroic's store/wine-downloader-info.json
+#[derive(Debug, Deserialize)]
+struct WineManagerRelease {
+    version: String,
+    #[serde(rename = "type")]
+    release_type: Option<String>,
+    #[serde(rename = "installDir")]
+    install_dir: Option<String>,
+    #[serde(rename = "isInstalled")]
+    is_installed: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WineManagerInfo {
+    #[serde(rename = "wine-releases", default)]
+    releases: Vec<WineManagerRelease>,
+}
+
+/// Installed runners recorded by Heroic's wine manager
+fn read_wine_manager_runners(heroic_path: &Path) -> Vec<HeroicRunner> {
+    let Ok(content) = fs::read_to_string(heroic_path.join("store/wine-downloader-info.json")) else {
+        return Vec::new();
+    };
+    let Ok(info) = serde_json::from_str::<WineManagerInfo>(&content) else {
+        log_warning("Failed to parse Heroic wine-downloader-info.json");
+        return Vec::new();
+    };
+
+    info.releases
+        .into_iter()
+        .filter(|r| r.is_installed == Some(true))
+        .filter_map(|r| {
+            let path = PathBuf::from(r.install_dir?);
+            path.is_dir().then(|| HeroicRunner {
+                name: r.version,
+                runner_type: r.release_type.unwrap_or_else(|| "Wine".to_string()),
+                path,
+            })
+        })
+        .collect()
+}
+
 // ============================================================================
 // Shared Utilities
 // ============================================================================
@@ -275,3 +517,33 @@ fn get_heroic_game_prefix(heroic_path: &Path, app_name: &str) -> Option<PathBuf>
         None
     }
 }
+
+/// Parse a human-readable size from Heroic ("38.72 GiB", "512 MB") into bytes
+fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let split = text.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let multiplier: f64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "b" | "bytes" => 1.0,
+        "kb" | "kib" => 1024.0,
+        "mb" | "mib" => 1024.0 * 1024.0,
+        "gb" | "gib" => 1024.0 * 1024.0 * 1024.0,
+        "tb" | "tib" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((number * multiplier) as u64).filter(|&size| size != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("1.5 GiB"), Some(1_610_612_736));
+        assert_eq!(parse_size("512 MB"), Some(536_870_912));
+        assert_eq!(parse_size("0 B"), None);
+        assert_eq!(parse_size("unknown"), None);
+    }
+}
@@ -17,9 +17,9 @@ pub struct KnownGame {
     pub gog_app_id: Option<&'static str>,
     /// Folder name in Documents/My Games (if applicable)
     pub my_games_folder: Option<&'static str>,
-    /// Folder name in AppData/Local (if applicable)
+    /// Folder in AppData/Local (if applicable); may be nested as `Publisher/Game`
     pub appdata_local_folder: Option<&'static str>,
-    /// Folder name in AppData/Roaming (if applicable)
+    /// Folder in AppData/Roaming (if applicable); may be nested as `Publisher/Game`
     pub appdata_roaming_folder: Option<&'static str>,
     /// Registry path under HKLM\Software\ (for game detection)
     pub registry_path: &'static str,
@@ -27,6 +27,10 @@ pub struct KnownGame {
     pub registry_value: &'static str,
     /// Expected folder name in steamapps/common/
     pub steam_folder: &'static str,
+    /// Winetricks VC++ redistributable verbs the game (or its script extender) needs
+    pub needs_vcredist: &'static [&'static str],
+    /// Controller-related env vars the community recommends under Proton
+    pub controller_env: &'static [(&'static str, &'static str)],
 }
 
 /// All known games that NaK supports
@@ -42,6 +46,8 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\SureAI\Enderal",
         registry_value: "Install_Path",
         steam_folder: "Enderal",
+        needs_vcredist: &["vcrun2010"],
+        controller_env: &[],
     },
     KnownGame {
         name: "Enderal Special Edition",
@@ -53,6 +59,8 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\SureAI\Enderal SE",
         registry_value: "installed path",
         steam_folder: "Enderal Special Edition",
+        needs_vcredist: &["vcrun2022"],
+        controller_env: &[],
     },
     KnownGame {
         name: "Fallout 3",
@@ -64,6 +72,8 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\Bethesda Softworks\Fallout3",
         registry_value: "Installed Path",
         steam_folder: "Fallout 3",
+        needs_vcredist: &["vcrun2008"],
+        controller_env: &[],
     },
     KnownGame {
         name: "Fallout 4",
@@ -75,6 +85,8 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\Bethesda Softworks\Fallout4",
         registry_value: "Installed Path",
         steam_folder: "Fallout 4",
+        needs_vcredist: &["vcrun2022"],
+        controller_env: &[],
     },
     KnownGame {
         name: "Fallout 4 VR",
@@ -86,6 +98,8 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\Bethesda Softworks\Fallout 4 VR",
         registry_value: "Installed Path",
         steam_folder: "Fallout 4 VR",
+        needs_vcredist: &["vcrun2022"],
+        controller_env: &[],
     },
     KnownGame {
         name: "Fallout New Vegas",
@@ -97,6 +111,8 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\Bethesda Softworks\FalloutNV",
         registry_value: "Installed Path",
         steam_folder: "Fallout New Vegas",
+        needs_vcredist: &["vcrun2008"],
+        controller_env: &[],
     },
     KnownGame {
         name: "Morrowind",
@@ -108,6 +124,8 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\Bethesda Softworks\Morrowind",
         registry_value: "Installed Path",
         steam_folder: "Morrowind",
+        needs_vcredist: &[],
+        controller_env: &[],
     },
     KnownGame {
         name: "Oblivion",
@@ -119,6 +137,8 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\Bethesda Softworks\Oblivion",
         registry_value: "Installed Path",
         steam_folder: "Oblivion",
+        needs_vcredist: &["vcrun2008"],
+        controller_env: &[],
     },
     KnownGame {
         name: "Skyrim",
@@ -130,6 +150,8 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\Bethesda Softworks\Skyrim",
         registry_value: "Installed Path",
         steam_folder: "Skyrim",
+        needs_vcredist: &["vcrun2010"],
+        controller_env: &[],
     },
     KnownGame {
         name: "Skyrim Special Edition",
@@ -141,6 +163,8 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\Bethesda Softworks\Skyrim Special Edition",
         registry_value: "Installed Path",
         steam_folder: "Skyrim Special Edition",
+        needs_vcredist: &["vcrun2022"],
+        controller_env: &[],
     },
     KnownGame {
         name: "Skyrim VR",
@@ -152,6 +176,8 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\Bethesda Softworks\Skyrim VR",
         registry_value: "Installed Path",
         steam_folder: "Skyrim VR",
+        needs_vcredist: &["vcrun2022"],
+        controller_env: &[],
     },
     KnownGame {
         name: "Starfield",
@@ -163,6 +189,8 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\Bethesda Softworks\Starfield",
         registry_value: "Installed Path",
         steam_folder: "Starfield",
+        needs_vcredist: &["vcrun2022"],
+        controller_env: &[],
     },
     // CD Projekt RED Games
     KnownGame {
@@ -175,6 +203,8 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\CD Projekt Red\The Witcher 3",
         registry_value: "InstallFolder",
         steam_folder: "The Witcher 3 Wild Hunt",
+        needs_vcredist: &["vcrun2012", "vcrun2022"],
+        controller_env: &[],
     },
     KnownGame {
         name: "Cyberpunk 2077",
@@ -186,6 +216,8 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\CD Projekt Red\Cyberpunk 2077",
         registry_value: "InstallFolder",
         steam_folder: "Cyberpunk 2077",
+        needs_vcredist: &["vcrun2022"],
+        controller_env: &[],
     },
     // Other popular moddable games
     KnownGame {
@@ -198,6 +230,9 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\Larian Studios\Baldur's Gate 3",
         registry_value: "InstallDir",
         steam_folder: "Baldurs Gate 3",
+        needs_vcredist: &["vcrun2022"],
+        // Defaults the game to its controller UI instead of mouse/keyboard
+        controller_env: &[("SteamDeck", "1")],
     },
 ];
 
@@ -214,12 +249,53 @@ pub fn find_by_gog_id(app_id: &str) -> Option<&'static KnownGame> {
         .find(|g| g.gog_app_id == Some(app_id))
 }
 
-/// Find a known game by name (case-insensitive)
+/// Find a known game by name.
+///
+/// Matching ignores case, punctuation, extra whitespace and a leading "The",
+/// and expands common abbreviations, so "skyrim se" and "Fallout: New Vegas"
+/// both match.
 pub fn find_by_name(name: &str) -> Option<&'static KnownGame> {
-    let name_lower = name.to_lowercase();
-    KNOWN_GAMES
+    let wanted = normalize_name(name);
+    if wanted.is_empty() {
+        return None;
+    }
+    KNOWN_GAMES.iter().find(|g| normalize_name(g.name) == wanted)
+}
+
+/// Abbreviations users type for words in known game names
+const NAME_ABBREVIATIONS: &[(&str, &str)] = &[
+    ("se", "special edition"),
+    ("nv", "new vegas"),
+    ("fo3", "fallout 3"),
+    ("fo4", "fallout 4"),
+    ("bg3", "baldurs gate 3"),
+    ("cp2077", "cyberpunk 2077"),
+];
+
+/// Lowercase, drop apostrophes, turn other punctuation into spaces, expand
+/// abbreviations and collapse whitespace
+fn normalize_name(name: &str) -> String {
+    let cleaned: String = name
+        .to_lowercase()
+        .chars()
+        .filter(|c| !matches!(c, '\'' | '’'))
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+
+    let mut words: Vec<&str> = cleaned.split_whitespace().collect();
+    if words.len() > 1 && words[0] == "the" {
+        words.remove(0);
+    }
+    words
         .iter()
-        .find(|g| g.name.to_lowercase() == name_lower)
+        .map(|w| {
+            NAME_ABBREVIATIONS
+                .iter()
+                .find(|(short, _)| short == w)
+                .map_or(*w, |(_, long)| long)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 /// Normalize Steam App IDs that have equivalent variants.
@@ -234,7 +310,7 @@ fn normalize_steam_id(app_id: &str) -> &str {
 
 #[cfg(test)]
 mod tests {
-    use super::find_by_steam_id;
+    use super::{find_by_name, find_by_steam_id};
 
     #[test]
     fn fallout_3_goty_alias_maps_to_fallout_3() {
@@ -242,4 +318,18 @@ mod tests {
         assert_eq!(game.name, "Fallout 3");
         assert_eq!(game.steam_app_id, "22300");
     }
+
+    #[test]
+    fn find_by_name_normalizes() {
+        let name = |query: &str| find_by_name(query).map(|g| g.name);
+        assert_eq!(name("Skyrim Special Edition"), Some("Skyrim Special Edition"));
+        assert_eq!(name("  skyrim   SE "), Some("Skyrim Special Edition"));
+        assert_eq!(name("Fallout: New Vegas"), Some("Fallout New Vegas"));
+        assert_eq!(name("Fallout NV"), Some("Fallout New Vegas"));
+        assert_eq!(name("baldurs gate 3"), Some("Baldur's Gate 3"));
+        assert_eq!(name("Witcher 3"), Some("The Witcher 3"));
+        assert_eq!(name("Skyrim"), Some("Skyrim"));
+        assert_eq!(name("Skyrim Legendary"), None);
+        assert_eq!(name(""), None);
+    }
 }
@@ -0,0 +1,196 @@
+//! Lutris game detection
+//!
+//! Lutris keeps its library in `pga.db` (SQLite) and each game's runner
+//! settings in a YAML file under `games/`. Only Wine-runner games are
+//! reported, since those are the ones with a prefix to manage.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, OpenFlags};
+
+use super::known_games::find_by_name;
+use super::{AppType, Game, InstallState, Launcher};
+use crate::logging::{log_info, log_warning};
+
+/// Lutris installs as (config dir, data dir) relative to $HOME
+const LUTRIS_PATHS: &[(&str, &str)] = &[
+    (".config/lutris", ".local/share/lutris"),
+    (".var/app/net.lutris.Lutris/config/lutris", ".var/app/net.lutris.Lutris/data/lutris"),
+];
+
+/// A Wine game row from pga.db
+struct LutrisEntry {
+    name: String,
+    slug: String,
+    directory: Option<String>,
+    configpath: Option<String>,
+}
+
+/// Detect all Wine games installed through Lutris
+pub fn detect_lutris_games() -> Vec<Game> {
+    let mut games: Vec<Game> = Vec::new();
+    let Ok(home) = std::env::var("HOME") else {
+        return games;
+    };
+
+    for (config_rel, data_rel) in LUTRIS_PATHS {
+        let config_dir = PathBuf::from(&home).join(config_rel);
+        let data_dir = PathBuf::from(&home).join(data_rel);
+        // Older releases kept the game configs next to pga.db
+        let games_dir = [config_dir.join("games"), data_dir.join("games")]
+            .into_iter()
+            .find(|d| d.is_dir());
+        let db_path = data_dir.join("pga.db");
+        if games_dir.is_none() && !db_path.exists() {
+            continue;
+        }
+
+        log_info(&format!("Found Lutris installation: {}", data_dir.display()));
+
+        let entries = match read_pga_db(&db_path) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log_warning(&format!("Could not read {}: {}", db_path.display(), e));
+                Vec::new()
+            }
+        };
+
+        for entry in entries {
+            let config = games_dir.as_ref().and_then(|dir| {
+                let file = entry.configpath.as_deref().unwrap_or(&entry.slug);
+                fs::read_to_string(dir.join(format!("{}.yml", file))).ok()
+            });
+            if let Some(game) = lutris_game(entry, config.as_deref()) {
+                if !games.iter().any(|g| g.app_id == game.app_id) {
+                    games.push(game);
+                }
+            }
+        }
+    }
+
+    log_info(&format!("Lutris: Found {} installed games", games.len()));
+    games
+}
+
+/// Installed Wine-runner games listed in pga.db
+fn read_pga_db(db_path: &Path) -> rusqlite::Result<Vec<LutrisEntry>> {
+    // Read-only so a running Lutris never sees a lock from us
+    let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let mut stmt = conn.prepare(
+        "SELECT name, slug, directory, configpath FROM games \
+         WHERE installed = 1 AND runner = 'wine'",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(LutrisEntry {
+            name: row.get(0)?,
+            slug: row.get(1)?,
+            directory: row.get(2)?,
+            configpath: row.get(3)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Build a Game from a pga.db row and its YAML config
+fn lutris_game(entry: LutrisEntry, config: Option<&str>) -> Option<Game> {
+    let prefix = config
+        .and_then(|c| yml_value(c, "game", "prefix"))
+        .map(PathBuf::from)
+        .filter(|p| p.join("drive_c").is_dir());
+    let exe = config
+        .and_then(|c| yml_value(c, "game", "exe"))
+        .map(PathBuf::from);
+
+    // The install directory column is often the prefix for installer-based
+    // games, so prefer the executable's own folder when it is absolute
+    let install_path = exe
+        .as_ref()
+        .filter(|e| e.is_absolute())
+        .and_then(|e| e.parent().map(Path::to_path_buf))
+        .or_else(|| entry.directory.as_deref().filter(|d| !d.is_empty()).map(PathBuf::from))
+        .or_else(|| prefix.clone())?;
+    if !install_path.exists() {
+        return None;
+    }
+
+    let known = find_by_name(&entry.name);
+    Some(Game {
+        name: entry.name,
+        app_id: format!("lutris-{}", entry.slug),
+        install_path,
+        prefix_path: prefix,
+        launcher: Launcher::Lutris,
+        my_games_folder: known.and_then(|k| k.my_games_folder.map(String::from)),
+        appdata_local_folder: known.and_then(|k| k.appdata_local_folder.map(String::from)),
+        appdata_roaming_folder: known.and_then(|k| k.appdata_roaming_folder.map(String::from)),
+        registry_path: known.map(|k| k.registry_path.to_string()),
+        registry_value: known.map(|k| k.registry_value.to_string()),
+        prefix_shared: false,
+        prefix_redirected: false,
+        bundled_runtime: None,
+        family_shared: false,
+        install_size: None,
+        app_type: AppType::Game,
+        install_state: InstallState::FullyInstalled,
+    })
+}
+
+/// Value of `key` inside a top-level `section:` of a Lutris game YAML file.
+///
+/// Lutris writes flat two-level mappings, so this avoids a YAML dependency.
+fn yml_value(content: &str, section: &str, key: &str) -> Option<String> {
+    let mut in_section = false;
+    for line in content.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        if !line.starts_with([' ', '\t']) {
+            in_section = line.trim_end().strip_suffix(':') == Some(section);
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        let Some((k, v)) = line.trim().split_once(':') else {
+            continue;
+        };
+        if k.trim() == key {
+            let v = v.trim();
+            let v = v
+                .strip_prefix('\'')
+                .and_then(|v| v.strip_suffix('\''))
+                .or_else(|| v.strip_prefix('"').and_then(|v| v.strip_suffix('"')))
+                .unwrap_or(v);
+            return Some(v.to_string()).filter(|v| !v.is_empty());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yml_value() {
+        let content = "\
+game:
+  exe: /home/u/Games/skyrim/drive_c/Program Files/Skyrim/SkyrimSELauncher.exe
+  prefix: '/home/u/Games/skyrim'
+system:
+  prefix: /wrong
+wine:
+  version: lutris-GE-Proton8-26-x86_64
+";
+        assert_eq!(
+            yml_value(content, "game", "prefix").as_deref(),
+            Some("/home/u/Games/skyrim")
+        );
+        assert_eq!(
+            yml_value(content, "wine", "version").as_deref(),
+            Some("lutris-GE-Proton8-26-x86_64")
+        );
+        assert_eq!(yml_value(content, "game", "args"), None);
+    }
+}
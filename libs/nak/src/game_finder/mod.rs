@@ -4,36 +4,57 @@
 //! - Steam (native, Flatpak, Snap)
 //! - Heroic (GOG, Epic)
 //! - Bottles
+//! - Lutris (Wine runner)
 
 // Allow unused items - this is a public API module
 #![allow(dead_code)]
 #![allow(unused_imports)]
 
 mod bottles;
+mod compat_notes;
 mod heroic;
+mod lutris;
 pub mod known_games;
+mod prefix_version;
 mod registry;
 mod steam;
-mod vdf;
+pub(crate) mod vdf;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use serde::{Serialize, Serializer};
+
+use crate::logging::log_warning;
+use crate::steam::SteamProton;
 
 pub use bottles::detect_bottles_games;
-pub use heroic::detect_heroic_games;
+pub use compat_notes::{compat_note_for, compat_note_for_app_id, set_compat_note};
+pub use heroic::{
+    detect_heroic_games, detect_heroic_games_with_roots, heroic_config_roots, list_heroic_runners, HeroicRunner,
+};
+pub use lutris::detect_lutris_games;
 pub use known_games::{find_by_gog_id, find_by_name, find_by_steam_id, KnownGame, KNOWN_GAMES};
-pub use registry::{read_registry_value, wine_path_to_linux};
-pub use steam::{detect_steam_games, find_game_install_path, find_game_prefix_path, get_known_game};
+pub use prefix_version::{detect_prefix_proton_version, prefixes_needing_upgrade, PrefixUpgrade};
+pub use registry::{prefix_arch, read_registry_value, wine_path_to_linux, PrefixArch};
+pub(crate) use registry::{read_value_from_reg_content, read_value_from_reg_file, reg_key_header, set_reg_values};
+pub use steam::{
+    detect_steam_games, find_duplicate_library_entries, find_game_by_app_id, find_game_install_path,
+    find_game_prefix_path, find_library_folders, get_known_game, resolve_game_prefix,
+    DuplicateLibrary, ResolvedPrefix,
+};
 
 // ============================================================================
 // Core Types
 // ============================================================================
 
 /// The launcher/store a game was installed from
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
 pub enum Launcher {
     Steam { is_flatpak: bool, is_snap: bool },
     Heroic { store: HeroicStore },
     Bottles,
+    Lutris,
 }
 
 impl Launcher {
@@ -45,22 +66,72 @@ impl Launcher {
             Launcher::Heroic { store: HeroicStore::GOG } => "Heroic (GOG)",
             Launcher::Heroic { store: HeroicStore::Epic } => "Heroic (Epic)",
             Launcher::Bottles => "Bottles",
+            Launcher::Lutris => "Lutris",
+        }
+    }
+
+    /// Stable lowercase key for filtering: "steam", "heroic", "bottles" or "lutris"
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Launcher::Steam { .. } => "steam",
+            Launcher::Heroic { .. } => "heroic",
+            Launcher::Bottles => "bottles",
+            Launcher::Lutris => "lutris",
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum HeroicStore {
     GOG,
     Epic,
 }
 
+/// What kind of app a detected entry is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AppType {
+    Game,
+    /// Proton, Steam Linux Runtime, redistributables and other tools
+    Tool,
+    /// Non-game software
+    Application,
+    Demo,
+}
+
+impl AppType {
+    /// Stable lowercase key: "game", "tool", "application" or "demo"
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AppType::Game => "game",
+            AppType::Tool => "tool",
+            AppType::Application => "application",
+            AppType::Demo => "demo",
+        }
+    }
+}
+
+/// Where a game's install stands, from Steam's appmanifest `StateFlags`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallState {
+    FullyInstalled,
+    /// Installed, but Steam has an update queued that must finish first
+    UpdateRequired,
+    /// Downloading, validating or otherwise mid-update
+    Downloading,
+    NotInstalled,
+}
+
 /// A detected game installation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Game {
     pub name: String,
     pub app_id: String,
+    #[serde(serialize_with = "serialize_path")]
     pub install_path: PathBuf,
+    #[serde(serialize_with = "serialize_opt_path")]
     pub prefix_path: Option<PathBuf>,
     pub launcher: Launcher,
     pub my_games_folder: Option<String>,
@@ -68,6 +139,24 @@ pub struct Game {
     pub appdata_roaming_folder: Option<String>,
     pub registry_path: Option<String>,
     pub registry_value: Option<String>,
+    /// Whether the prefix is shared with other games or redirected away from
+    /// the game's own compatdata (e.g. via STEAM_COMPAT_DATA_PATH)
+    pub prefix_shared: bool,
+    /// Whether the prefix comes from a STEAM_COMPAT_DATA_PATH launch option
+    pub prefix_redirected: bool,
+    /// Proton/Wine runtime shipped inside the install directory and selected
+    /// as the game's compatibility tool
+    #[serde(serialize_with = "serialize_opt_path")]
+    pub bundled_runtime: Option<PathBuf>,
+    /// Installed by another account and borrowed through Steam Family Sharing
+    pub family_shared: bool,
+    /// Size on disk in bytes as recorded by the launcher, None if it keeps none
+    pub install_size: Option<u64>,
+    /// Game, tool, application or demo (only Steam reports anything but Game)
+    pub app_type: AppType,
+    /// Whether the game can be played now (only Steam reports anything but
+    /// FullyInstalled)
+    pub install_state: InstallState,
 }
 
 impl Game {
@@ -75,42 +164,186 @@ impl Game {
         self.prefix_path.is_some()
     }
 
-    pub fn get_prefix_user_path(&self) -> Option<PathBuf> {
+    /// Whether the game is installed and not mid-download or waiting on an
+    /// update, so its files and prefix can be relied on
+    pub fn is_fully_installed(&self) -> bool {
+        self.install_state == InstallState::FullyInstalled
+    }
+
+    /// The bundled runtime as a [`SteamProton`], so dependency operations can
+    /// target it instead of a global Proton.
+    pub fn bundled_proton(&self) -> Option<SteamProton> {
+        let path = self.bundled_runtime.clone()?;
+        let name = path.file_name()?.to_string_lossy().into_owned();
+        Some(SteamProton {
+            config_name: name.clone(),
+            name,
+            path,
+            is_steam_proton: false,
+            is_experimental: false,
+        })
+    }
+
+    /// Free space on the filesystem holding the prefix, or None if there is no prefix.
+    pub fn prefix_free_space(&self) -> Option<u64> {
         let prefix = self.prefix_path.as_ref()?;
-        let users_dir = prefix.join("drive_c/users");
+        crate::utils::free_space(prefix)
+    }
 
-        if let Ok(entries) = std::fs::read_dir(&users_dir) {
-            for entry in entries.flatten() {
-                let name = entry.file_name().to_string_lossy().to_string();
-                if name != "Public" && name != "root" {
-                    return Some(users_dir.join(name));
-                }
-            }
-        }
+    /// When the prefix was created: the birth time of the prefix (or its
+    /// compatdata) directory where the filesystem records one, otherwise the
+    /// prefix directory's modification time. None if there is no prefix.
+    pub fn prefix_created_at(&self) -> Option<std::time::SystemTime> {
+        let prefix = self.prefix_path.as_ref()?;
+        let meta = std::fs::metadata(prefix).ok()?;
+        meta.created()
+            .ok()
+            .or_else(|| {
+                let compat = prefix.parent()?;
+                std::fs::metadata(compat).and_then(|m| m.created()).ok()
+            })
+            .or_else(|| meta.modified().ok())
+    }
 
-        Some(users_dir.join("steamuser"))
+    pub fn get_prefix_user_path(&self) -> Option<PathBuf> {
+        self.prefix_path.as_deref().map(prefix_user_path)
     }
 
     pub fn get_prefix_documents_path(&self) -> Option<PathBuf> {
-        self.get_prefix_user_path().map(|p| p.join("Documents"))
+        self.prefix_path.as_deref().map(prefix_documents_path)
     }
 
     pub fn get_prefix_my_games_path(&self) -> Option<PathBuf> {
         let docs = self.get_prefix_documents_path()?;
         let folder = self.my_games_folder.as_ref()?;
-        Some(docs.join("My Games").join(folder))
+        Some(join_folder(&docs.join("My Games"), folder))
     }
 
     pub fn get_prefix_appdata_local_path(&self) -> Option<PathBuf> {
         let user = self.get_prefix_user_path()?;
         let folder = self.appdata_local_folder.as_ref()?;
-        Some(user.join("AppData/Local").join(folder))
+        Some(join_folder(&user.join("AppData/Local"), folder))
     }
 
     pub fn get_prefix_appdata_roaming_path(&self) -> Option<PathBuf> {
         let user = self.get_prefix_user_path()?;
         let folder = self.appdata_roaming_folder.as_ref()?;
-        Some(user.join("AppData/Roaming").join(folder))
+        Some(join_folder(&user.join("AppData/Roaming"), folder))
+    }
+}
+
+/// The directory holding a prefix's drive_c, for paths that point at the
+/// prefix root, at a compatdata-style parent (`<path>/pfx/drive_c`) or at
+/// drive_c itself. None if no drive_c is found.
+pub fn resolve_prefix_root(path: &Path) -> Option<PathBuf> {
+    if path.file_name().is_some_and(|n| n == "drive_c") && path.is_dir() {
+        return path.parent().map(Path::to_path_buf);
+    }
+    [path.to_path_buf(), path.join("pfx")]
+        .into_iter()
+        .find(|root| root.join("drive_c").is_dir())
+}
+
+/// Point each game's prefix at the directory that actually holds drive_c
+fn normalize_prefix_layouts(games: &mut [Game]) {
+    for game in games.iter_mut() {
+        let Some(prefix) = &game.prefix_path else {
+            continue;
+        };
+        if let Some(root) = resolve_prefix_root(prefix).filter(|root| root != prefix) {
+            game.prefix_path = Some(root);
+        }
+    }
+}
+
+/// The Wine user's profile directory in a prefix (the first user that isn't
+/// Public or root, defaulting to steamuser)
+pub fn prefix_user_path(prefix: &Path) -> PathBuf {
+    let prefix = resolve_prefix_root(prefix).unwrap_or_else(|| prefix.to_path_buf());
+    let users_dir = prefix.join("drive_c/users");
+
+    if let Ok(entries) = std::fs::read_dir(&users_dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name != "Public" && name != "root" {
+                return users_dir.join(name);
+            }
+        }
+    }
+
+    users_dir.join("steamuser")
+}
+
+/// user.reg keys that can redirect the Documents ("Personal") shell folder
+const SHELL_FOLDER_KEYS: &[&str] = &[
+    "Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\User Shell Folders",
+    "Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\Shell Folders",
+];
+
+/// The prefix's Documents folder, following a shell-folder redirection
+/// when it points at an existing directory on C:.
+///
+/// Prefixes copied from a Windows install often carry a OneDrive
+/// redirection that can't resolve under Wine; that is ignored with a
+/// warning and the local `Documents` folder is used instead.
+pub fn prefix_documents_path(prefix: &Path) -> PathBuf {
+    let prefix = &resolve_prefix_root(prefix).unwrap_or_else(|| prefix.to_path_buf());
+    let user_dir = prefix_user_path(prefix);
+    let local = user_dir.join("Documents");
+
+    let user_reg = prefix.join("user.reg");
+    let Some(raw) = SHELL_FOLDER_KEYS
+        .iter()
+        .find_map(|key| read_value_from_reg_file(&user_reg, key, "Personal"))
+    else {
+        return local;
+    };
+
+    // REG_EXPAND_SZ values come back unparsed as str(2):"..."
+    let value = raw.strip_prefix("str(2):").unwrap_or(&raw).trim_matches('"');
+    if value.to_lowercase().contains("onedrive") {
+        log_warning(&format!(
+            "Ignoring OneDrive Documents redirection '{}' in {}, using {}",
+            value,
+            prefix.display(),
+            local.display()
+        ));
+        return local;
+    }
+
+    let redirected = if let Some(rest) = value.strip_prefix("%USERPROFILE%") {
+        join_folder(&user_dir, rest)
+    } else if value.get(..2).is_some_and(|d| d.eq_ignore_ascii_case("c:")) {
+        join_folder(&prefix.join("drive_c"), &value[2..])
+    } else {
+        return local;
+    };
+
+    if redirected.is_dir() {
+        redirected
+    } else {
+        local
+    }
+}
+
+/// Join a known-game folder that may be nested (`Publisher/Game` or
+/// `Publisher\Game`) onto a base path, one segment at a time
+fn join_folder(base: &Path, folder: &str) -> PathBuf {
+    folder
+        .split(['/', '\\'])
+        .filter(|s| !s.is_empty() && *s != "." && *s != "..")
+        .fold(base.to_path_buf(), |path, segment| path.join(segment))
+}
+
+/// Paths go out as strings even when they aren't valid UTF-8
+fn serialize_path<S: Serializer>(path: &Path, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&path.to_string_lossy())
+}
+
+fn serialize_opt_path<S: Serializer>(path: &Option<PathBuf>, serializer: S) -> Result<S::Ok, S::Error> {
+    match path {
+        Some(path) => serialize_path(path, serializer),
+        None => serializer.serialize_none(),
     }
 }
 
@@ -118,30 +351,73 @@ impl Game {
 // Scan Results
 // ============================================================================
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize)]
 pub struct GameScanResult {
     pub games: Vec<Game>,
     pub steam_count: usize,
     pub heroic_count: usize,
     pub bottles_count: usize,
+    pub lutris_count: usize,
+    /// Problems found during the scan that the user may want to fix
+    pub warnings: Vec<String>,
 }
 
 impl GameScanResult {
+    /// The scan as a JSON object: every game with all its fields, the
+    /// per-launcher counts and the warnings. Paths are lossy UTF-8 strings.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
     pub fn games_with_prefixes(&self) -> impl Iterator<Item = &Game> {
         self.games.iter().filter(|g| g.has_prefix())
     }
 
+    /// Games grouped by the mount point of their install directory, sorted
+    /// by mount point. Games whose install path is missing are grouped last
+    /// under an empty path.
+    pub fn group_by_mount(&self) -> Vec<(PathBuf, Vec<&Game>)> {
+        crate::utils::group_by_mount(&self.games, |g| g.install_path.as_path())
+    }
+
+    /// Games installed into the same directory (or one inside another's),
+    /// such as bundles and region variants. Only groups of two or more are
+    /// returned, sorted by directory.
+    pub fn shared_install_dirs(&self) -> Vec<Vec<&Game>> {
+        crate::utils::group_overlapping_paths(&self.games, |g| g.install_path.as_path())
+            .into_iter()
+            .map(|(_, games)| games)
+            .collect()
+    }
+
+    /// Total install size per launcher as `(kind, bytes, unknown)`, where
+    /// `unknown` counts games whose launcher records no size (they add
+    /// nothing to `bytes`). Launchers appear in scan order, only if they
+    /// have games.
+    pub fn size_by_launcher(&self) -> Vec<(String, u64, usize)> {
+        let mut totals: Vec<(String, u64, usize)> = Vec::new();
+        for game in &self.games {
+            let kind = game.launcher.kind();
+            let index = match totals.iter().position(|(k, _, _)| k == kind) {
+                Some(index) => index,
+                None => {
+                    totals.push((kind.to_string(), 0, 0));
+                    totals.len() - 1
+                }
+            };
+            match game.install_size {
+                Some(size) => totals[index].1 += size,
+                None => totals[index].2 += 1,
+            }
+        }
+        totals
+    }
+
+    /// Games from one launcher, by [`Launcher::kind`] key. Unknown keys match nothing.
     pub fn games_by_launcher(&self, launcher_type: &str) -> Vec<&Game> {
         self.games
             .iter()
-            .filter(|g| {
-                matches!(
-                    (&g.launcher, launcher_type),
-                    (Launcher::Steam { .. }, "steam")
-                        | (Launcher::Heroic { .. }, "heroic")
-                        | (Launcher::Bottles, "bottles")
-                )
-            })
+            .filter(|g| g.launcher.kind() == launcher_type)
             .collect()
     }
 
@@ -162,14 +438,31 @@ impl GameScanResult {
 // ============================================================================
 
 /// Detect all installed games from all supported launchers
+///
+/// Tools such as Proton and the Steam Linux Runtime are left out; use
+/// [`detect_all_games_with`] to include them.
 pub fn detect_all_games() -> GameScanResult {
+    detect_all_games_with(false)
+}
+
+/// Detect all installed games, optionally including Steam tools
+pub fn detect_all_games_with(include_tools: bool) -> GameScanResult {
     let mut result = GameScanResult::default();
 
-    let steam_games = detect_steam_games();
+    let mut steam_games = detect_steam_games();
+    if !include_tools {
+        steam_games.retain(|g| g.app_type != AppType::Tool);
+    }
     result.steam_count = steam_games.len();
     result.games.extend(steam_games);
+    result.warnings.extend(steam_library_warnings());
 
-    let heroic_games = detect_heroic_games();
+    let gog_roots: Vec<PathBuf> = crate::config::AppConfig::load()
+        .gog_library_roots
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+    let heroic_games = detect_heroic_games_with_roots(&gog_roots);
     result.heroic_count = heroic_games.len();
     result.games.extend(heroic_games);
 
@@ -177,15 +470,155 @@ pub fn detect_all_games() -> GameScanResult {
     result.bottles_count = bottles_games.len();
     result.games.extend(bottles_games);
 
+    let lutris_games = detect_lutris_games();
+    result.lutris_count = lutris_games.len();
+    result.games.extend(lutris_games);
+
+    normalize_prefix_layouts(&mut result.games);
+    mark_shared_prefixes(&mut result.games);
+
     result
 }
 
+/// Flag games whose prefix is also used by another detected game
+fn mark_shared_prefixes(games: &mut [Game]) {
+    let prefixes: Vec<Option<PathBuf>> = games.iter().map(|g| g.prefix_path.clone()).collect();
+
+    for game in games.iter_mut() {
+        let Some(prefix) = &game.prefix_path else {
+            continue;
+        };
+        if prefixes.iter().filter(|p| p.as_ref() == Some(prefix)).count() > 1 {
+            game.prefix_shared = true;
+        }
+    }
+}
+
 /// Detect only Steam games
 pub fn detect_steam_only() -> GameScanResult {
-    let steam_games = detect_steam_games();
+    let mut steam_games = detect_steam_games();
+    steam_games.retain(|g| g.app_type != AppType::Tool);
+    normalize_prefix_layouts(&mut steam_games);
     GameScanResult {
         steam_count: steam_games.len(),
         games: steam_games,
+        warnings: steam_library_warnings(),
         ..Default::default()
     }
 }
+
+/// Warnings for duplicated entries in libraryfolders.vdf
+fn steam_library_warnings() -> Vec<String> {
+    find_duplicate_library_entries()
+        .iter()
+        .map(|d| {
+            let message = d.message();
+            log_warning(&message);
+            message
+        })
+        .collect()
+}
+
+/// Every detected prefix that belongs to a known game, across launchers
+/// (e.g. the same game owned on Steam and GOG), so the user can pick one.
+///
+/// Games are matched by Steam app id, GOG id, Bottles id or name; each
+/// prefix is listed once.
+pub fn prefixes_for_known_game(known: &KnownGame) -> Vec<(Launcher, PathBuf)> {
+    let mut prefixes: Vec<(Launcher, PathBuf)> = Vec::new();
+
+    for game in detect_all_games().games {
+        let matches = find_by_steam_id(game.app_id.trim_start_matches("bottles-"))
+            .is_some_and(|k| k.steam_app_id == known.steam_app_id)
+            || known.gog_app_id == Some(game.app_id.as_str())
+            || game.name.eq_ignore_ascii_case(known.name);
+        if !matches {
+            continue;
+        }
+        let Some(prefix) = game.prefix_path else {
+            continue;
+        };
+        if !prefixes.iter().any(|(_, p)| *p == prefix) {
+            prefixes.push((game.launcher, prefix));
+        }
+    }
+
+    prefixes
+}
+
+/// Recommended controller-related env vars for a known game, for inclusion
+/// in its launch options. Empty for games without a known fix.
+pub fn controller_env_for(game: &Game) -> Vec<(String, String)> {
+    let known = find_by_steam_id(game.app_id.trim_start_matches("bottles-"))
+        .or_else(|| find_by_name(&game.name));
+    known_controller_env(known)
+}
+
+/// [`controller_env_for`] by app id alone (Steam id or `bottles-<id>`)
+pub fn controller_env_for_app_id(app_id: &str) -> Vec<(String, String)> {
+    known_controller_env(find_by_steam_id(app_id.trim_start_matches("bottles-")))
+}
+
+fn known_controller_env(known: Option<&KnownGame>) -> Vec<(String, String)> {
+    known
+        .map(|g| {
+            g.controller_env
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_folder_nested() {
+        let base = Path::new("/pfx/AppData/Local");
+        assert_eq!(
+            join_folder(base, "CD Projekt Red/Cyberpunk 2077"),
+            PathBuf::from("/pfx/AppData/Local/CD Projekt Red/Cyberpunk 2077")
+        );
+        assert_eq!(
+            join_folder(base, r"Larian Studios\Baldur's Gate 3\"),
+            PathBuf::from("/pfx/AppData/Local/Larian Studios/Baldur's Gate 3")
+        );
+        assert_eq!(join_folder(base, "../Skyrim"), PathBuf::from("/pfx/AppData/Local/Skyrim"));
+    }
+
+    #[test]
+    fn test_scan_result_json() {
+        let result = GameScanResult {
+            games: vec![Game {
+                name: "Cyberpunk 2077".to_string(),
+                app_id: "1423049311".to_string(),
+                install_path: PathBuf::from("/games/Cyberpunk 2077"),
+                prefix_path: None,
+                launcher: Launcher::Heroic { store: HeroicStore::GOG },
+                my_games_folder: None,
+                appdata_local_folder: Some("CD Projekt Red/Cyberpunk 2077".to_string()),
+                appdata_roaming_folder: None,
+                registry_path: None,
+                registry_value: None,
+                prefix_shared: false,
+                prefix_redirected: false,
+                bundled_runtime: None,
+                family_shared: false,
+                install_size: Some(1024),
+                app_type: AppType::Game,
+                install_state: InstallState::FullyInstalled,
+            }],
+            heroic_count: 1,
+            ..Default::default()
+        };
+        let json: serde_json::Value = serde_json::from_str(&result.to_json().unwrap()).unwrap();
+        let game = &json["games"][0];
+        assert_eq!(game["launcher"], serde_json::json!({"kind": "heroic", "store": "gog"}));
+        assert_eq!(game["install_path"], "/games/Cyberpunk 2077");
+        assert_eq!(game["prefix_path"], serde_json::Value::Null);
+        assert_eq!(game["app_type"], "game");
+        assert_eq!(json["heroic_count"], 1);
+    }
+}
@@ -0,0 +1,112 @@
+//! Proton version tracking for existing prefixes
+//!
+//! Proton records the version that last touched a prefix in the compatdata
+//! directory (`version`, falling back to the first line of `config_info`).
+//! Comparing that against the selected Proton finds aging prefixes that
+//! often benefit from being recreated.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::detect_all_games;
+
+/// Major-version gap at which a prefix is considered stale
+const STALE_MAJOR_GAP: u32 = 2;
+
+/// A game whose prefix was created by a much older Proton
+#[derive(Debug, Clone)]
+pub struct PrefixUpgrade {
+    pub game_name: String,
+    pub app_id: String,
+    pub prefix_path: PathBuf,
+    /// Version string recorded in the prefix (e.g. "8.0-104")
+    pub prefix_version: String,
+    pub prefix_major: u32,
+    pub current_major: u32,
+}
+
+/// Read the Proton version recorded for a prefix (`.../compatdata/<id>/pfx`).
+pub fn detect_prefix_proton_version(prefix_path: &Path) -> Option<String> {
+    let compat_dir = prefix_path.parent()?;
+
+    let from_version = fs::read_to_string(compat_dir.join("version")).ok();
+    let from_config_info = || {
+        fs::read_to_string(compat_dir.join("config_info"))
+            .ok()
+            .and_then(|c| c.lines().next().map(str::to_string))
+    };
+
+    from_version
+        .or_else(from_config_info)
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Find games whose prefix was last used by a Proton at least two major
+/// versions older than `current_proton` (a Proton name like "GE-Proton10-4"
+/// or "Proton Experimental").
+pub fn prefixes_needing_upgrade(current_proton: &str) -> Vec<PrefixUpgrade> {
+    let Some(current_major) = current_proton_major(current_proton) else {
+        return Vec::new();
+    };
+
+    detect_all_games()
+        .games
+        .into_iter()
+        .filter(|game| game.is_fully_installed())
+        .filter_map(|game| {
+            let prefix_path = game.prefix_path?;
+            let prefix_version = detect_prefix_proton_version(&prefix_path)?;
+            let prefix_major = proton_major_version(&prefix_version)?;
+            (current_major.saturating_sub(prefix_major) >= STALE_MAJOR_GAP).then_some(
+                PrefixUpgrade {
+                    game_name: game.name,
+                    app_id: game.app_id,
+                    prefix_path,
+                    prefix_version,
+                    prefix_major,
+                    current_major,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Major version of the selected Proton, reading its `version` file when the
+/// name carries no number (e.g. "Proton Experimental")
+fn current_proton_major(current_proton: &str) -> Option<u32> {
+    if let Some(major) = proton_major_version(current_proton) {
+        return Some(major);
+    }
+
+    let proton = crate::steam::find_steam_protons()
+        .into_iter()
+        .find(|p| p.name == current_proton || p.config_name == current_proton)?;
+    let version = fs::read_to_string(proton.path.join("version")).ok()?;
+    proton_major_version(&version)
+}
+
+/// Extract the major version from strings like "8.0-104", "GE-Proton9-20",
+/// "proton-9.0-2" or Proton's `version` file ("1713285093 proton-9.0-2").
+fn proton_major_version(version: &str) -> Option<u32> {
+    let version = version.split_whitespace().last()?;
+    let start = version.find(|c: char| c.is_ascii_digit())?;
+    let digits: String = version[start..]
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    digits.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proton_major_version() {
+        assert_eq!(proton_major_version("8.0-104"), Some(8));
+        assert_eq!(proton_major_version("GE-Proton9-20"), Some(9));
+        assert_eq!(proton_major_version("1713285093 proton-10.0-1\n"), Some(10));
+        assert_eq!(proton_major_version("Proton Experimental"), None);
+    }
+}
@@ -20,23 +20,68 @@ pub fn read_registry_value(
     key_path: &str,
     value_name: &str,
 ) -> Option<String> {
+    // Pure 32-bit prefixes have no Wow6432Node redirection
+    let wow64 = prefix_arch(prefix_path) != Some(PrefixArch::Win32);
+
     // Try system.reg first (HKEY_LOCAL_MACHINE)
     let system_reg = prefix_path.join("system.reg");
-    if let Some(value) = read_value_from_reg_file(&system_reg, key_path, value_name) {
+    if let Some(value) = read_value_with_wow64(&system_reg, key_path, value_name, wow64) {
         return Some(value);
     }
 
     // Try user.reg (HKEY_CURRENT_USER)
     let user_reg = prefix_path.join("user.reg");
-    if let Some(value) = read_value_from_reg_file(&user_reg, key_path, value_name) {
+    if let Some(value) = read_value_with_wow64(&user_reg, key_path, value_name, wow64) {
         return Some(value);
     }
 
     None
 }
 
+/// Architecture of a Wine prefix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrefixArch {
+    Win32,
+    Win64,
+}
+
+/// Read the prefix architecture from the `#arch=` header of system.reg.
+///
+/// Returns None if the prefix has no registry yet.
+pub fn prefix_arch(prefix_path: &Path) -> Option<PrefixArch> {
+    use std::io::{BufRead, BufReader};
+
+    let file = fs::File::open(prefix_path.join("system.reg")).ok()?;
+    // The header sits in the first few lines; don't scan the whole hive
+    BufReader::new(file)
+        .lines()
+        .take(8)
+        .map_while(Result::ok)
+        .find_map(|line| match line.trim().strip_prefix("#arch=")? {
+            "win32" => Some(PrefixArch::Win32),
+            "win64" => Some(PrefixArch::Win64),
+            _ => None,
+        })
+}
+
 /// Read a value from a specific .reg file
-fn read_value_from_reg_file(reg_file: &Path, key_path: &str, value_name: &str) -> Option<String> {
+pub(crate) fn read_value_from_reg_file(reg_file: &Path, key_path: &str, value_name: &str) -> Option<String> {
+    read_value_with_wow64(reg_file, key_path, value_name, true)
+}
+
+/// Read a value from .reg file content already in memory. `key_header` is
+/// the bracketed key as written in the file, as for [`set_reg_values`].
+pub(crate) fn read_value_from_reg_content(content: &str, key_header: &str, value_name: &str) -> Option<String> {
+    find_value_in_content(content, key_header, value_name)
+}
+
+/// Read a value, also trying the Wow6432Node variant when `wow64` is set
+fn read_value_with_wow64(
+    reg_file: &Path,
+    key_path: &str,
+    value_name: &str,
+    wow64: bool,
+) -> Option<String> {
     let content = fs::read_to_string(reg_file).ok()?;
 
     // Convert the key path to Wine's format
@@ -58,7 +103,12 @@ fn read_value_from_reg_file(reg_file: &Path, key_path: &str, value_name: &str) -
     );
 
     // Find the key section and extract the value
-    for key_to_find in [&wine_key, &wine_key_wow64] {
+    let keys: &[&String] = if wow64 {
+        &[&wine_key, &wine_key_wow64]
+    } else {
+        &[&wine_key]
+    };
+    for key_to_find in keys {
         if let Some(value) = find_value_in_content(&content, key_to_find, value_name) {
             return Some(value);
         }
@@ -75,9 +125,9 @@ fn find_value_in_content(content: &str, key: &str, value_name: &str) -> Option<S
     for line in content.lines() {
         let trimmed = line.trim();
 
-        // Check for key header
-        if trimmed.starts_with('[') && trimmed.ends_with(']') {
-            in_target_key = trimmed.to_lowercase() == key.to_lowercase();
+        // Check for key header (Wine appends a timestamp: "[key] 1700000000")
+        if let Some(header) = reg_key_header(trimmed) {
+            in_target_key = header.to_lowercase() == key.to_lowercase();
             continue;
         }
 
@@ -103,6 +153,88 @@ fn find_value_in_content(content: &str, key: &str, value_name: &str) -> Option<S
     None
 }
 
+/// Return the `[key]` part of a key header line, without Wine's trailing timestamp
+pub(crate) fn reg_key_header(line: &str) -> Option<&str> {
+    if !line.starts_with('[') {
+        return None;
+    }
+    let end = line.rfind(']')?;
+    Some(&line[..=end])
+}
+
+/// Set (Some) or remove (None) values in one key of .reg file content,
+/// creating the key at the end if needed.
+///
+/// `key_header` is the bracketed key as written in the file (e.g.
+/// `[Software\\Wine\\DllOverrides]`); values are raw right-hand sides such
+/// as `"native,builtin"` or `dword:00000060`.
+pub(crate) fn set_reg_values(
+    content: &str,
+    key_header: &str,
+    changes: &[(&str, Option<String>)],
+) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let header_idx = lines.iter().position(|l| {
+        reg_key_header(l.trim()).is_some_and(|h| h.eq_ignore_ascii_case(key_header))
+    });
+
+    let mut out: Vec<String> = Vec::with_capacity(lines.len() + changes.len() + 3);
+    let new_values = changes
+        .iter()
+        .filter_map(|(name, value)| value.as_ref().map(|v| format!("\"{}\"={}", name, v)));
+
+    let Some(header_idx) = header_idx else {
+        out.extend(lines.iter().map(|l| l.to_string()));
+        if out.last().is_some_and(|l| !l.is_empty()) {
+            out.push(String::new());
+        }
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        out.push(format!("{} {}", key_header, timestamp));
+        out.extend(new_values);
+        return out.join("\n") + "\n";
+    };
+
+    let is_changed = |line: &str| {
+        let name = line.trim().split('=').next().unwrap_or("").trim_matches('"');
+        changes.iter().any(|(changed, _)| name.eq_ignore_ascii_case(changed))
+    };
+
+    let mut i = 0;
+    while i < lines.len() {
+        out.push(lines[i].to_string());
+        if i == header_idx {
+            // Keep Wine's #time line directly under the header
+            if lines.get(i + 1).is_some_and(|l| l.starts_with("#time=")) {
+                i += 1;
+                out.push(lines[i].to_string());
+            }
+            i += 1;
+            while i < lines.len() && !lines[i].trim().is_empty() && !lines[i].starts_with('[') {
+                let keep = !is_changed(lines[i]);
+                // Long hex values continue onto following lines ending in '\'
+                while lines[i].trim_end().ends_with('\\') && i + 1 < lines.len() {
+                    if keep {
+                        out.push(lines[i].to_string());
+                    }
+                    i += 1;
+                }
+                if keep {
+                    out.push(lines[i].to_string());
+                }
+                i += 1;
+            }
+            out.extend(new_values.clone());
+            continue;
+        }
+        i += 1;
+    }
+
+    out.join("\n") + "\n"
+}
+
 /// Parse a registry value line like "ValueName"="value"
 fn parse_reg_value_line(line: &str) -> Option<(String, String)> {
     // Format: "name"="value" or "name"=dword:00000000 or @="default"
@@ -219,6 +351,43 @@ mod tests {
         assert_eq!(value, r"Z:\mnt\games\Skyrim");
     }
 
+    #[test]
+    fn test_find_value_after_timestamped_header() {
+        let content = "[Software\\\\Wine\\\\DllOverrides] 1712345678\n#time=1da8c3f\n\"d3d11\"=\"native,builtin\"\n";
+        assert_eq!(
+            find_value_in_content(content, r"[software\\wine\\dlloverrides]", "d3d11").as_deref(),
+            Some("native,builtin")
+        );
+    }
+
+    #[test]
+    fn test_set_reg_values_dword() {
+        let content = "WINE REGISTRY Version 2\n\n[Control Panel\\\\Desktop] 1712345678\n\"LogPixels\"=dword:00000060\n";
+        let updated = set_reg_values(
+            content,
+            r"[Control Panel\\Desktop]",
+            &[("LogPixels", Some("dword:00000090".to_string()))],
+        );
+        assert_eq!(
+            find_value_in_content(&updated, r"[control panel\\desktop]", "LogPixels").as_deref(),
+            Some("144")
+        );
+        assert_eq!(updated.matches("LogPixels").count(), 1);
+    }
+
+    #[test]
+    fn test_set_reg_values_removes_continued_value() {
+        let content = "[System\\\\CurrentControlSet\\\\Control\\\\Session Manager] 1712345678\n\"PendingFileRenameOperations\"=hex(7):5c,00,3f,00,\\\n  00,00,00,00\n\"ProtectionMode\"=dword:00000001\n";
+        let updated = set_reg_values(
+            content,
+            r"[System\\CurrentControlSet\\Control\\Session Manager]",
+            &[("PendingFileRenameOperations", None)],
+        );
+        assert!(!updated.contains("PendingFileRenameOperations"));
+        assert!(!updated.contains("00,00,00,00"));
+        assert!(updated.contains("\"ProtectionMode\"=dword:00000001"));
+    }
+
     #[test]
     fn test_wine_path_to_linux() {
         let linux = wine_path_to_linux(r"Z:\mnt\games\Skyrim").unwrap();
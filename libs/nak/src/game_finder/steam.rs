@@ -3,13 +3,21 @@
 //! Detects games installed via Steam by parsing appmanifest_*.acf files.
 //! Supports native, Flatpak, and Snap Steam installations.
 
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use super::known_games::{find_by_steam_id, KnownGame};
-use super::vdf::{parse_library_folders, AppManifest};
-use super::{Game, Launcher};
-use crate::logging::log_info;
+use super::vdf::{parse_base_install_folders, parse_library_folders, AppManifest};
+use super::{AppType, Game, InstallState, Launcher};
+use crate::logging::{log_info, log_warning};
+use crate::utils::is_populated_dir;
+
+/// Steam tools that ship without a toolmanifest.vdf
+const STEAM_TOOL_APP_IDS: &[&str] = &[
+    "228980", // Steamworks Common Redistributables
+    "250820", // SteamVR
+];
 
 /// All possible Steam installation paths to check
 const STEAM_PATHS: &[&str] = &[
@@ -29,11 +37,24 @@ pub fn detect_steam_games() -> Vec<Game> {
         Err(_) => return games,
     };
 
+    // Launch options can redirect a game's prefix via STEAM_COMPAT_DATA_PATH
+    let launch_options = crate::steam::read_all_launch_options();
+    let current_owner = current_steam_id64();
+
+    // Installations can share a library; scan each one only once
+    let mut scanned: Vec<PathBuf> = Vec::new();
+
     // Find all Steam installations
     for steam_info in find_steam_installations(&home) {
         let libraries = get_library_folders(&steam_info.path);
+        let compat_tools = crate::steam::read_compat_tool_mapping(&steam_info.path);
 
         for library_path in libraries {
+            if scanned.contains(&library_path) {
+                continue;
+            }
+            scanned.push(library_path.clone());
+
             let steamapps = library_path.join("steamapps");
             if !steamapps.exists() {
                 continue;
@@ -51,7 +72,14 @@ pub fn detect_steam_games() -> Vec<Game> {
                 };
 
                 if name.starts_with("appmanifest_") && name.ends_with(".acf") {
-                    if let Some(game) = parse_appmanifest(&path, &steamapps, &steam_info) {
+                    if let Some(game) = parse_appmanifest(
+                        &path,
+                        &steamapps,
+                        &steam_info,
+                        &launch_options,
+                        &compat_tools,
+                        current_owner,
+                    ) {
                         games.push(game);
                     }
                 }
@@ -63,6 +91,60 @@ pub fn detect_steam_games() -> Vec<Game> {
     games
 }
 
+/// All Steam library folders across all installations (deduplicated)
+pub fn find_library_folders() -> Vec<PathBuf> {
+    let mut folders: Vec<PathBuf> = Vec::new();
+    let Ok(home) = std::env::var("HOME") else {
+        return folders;
+    };
+
+    for steam_info in find_steam_installations(&home) {
+        for library in get_library_folders(&steam_info.path) {
+            if !folders.contains(&library) {
+                folders.push(library);
+            }
+        }
+    }
+
+    folders
+}
+
+/// A libraryfolders.vdf entry that resolves to a library already listed in
+/// the same file
+#[derive(Debug, Clone)]
+pub struct DuplicateLibrary {
+    pub vdf_path: PathBuf,
+    /// Path as written in the VDF
+    pub entry: String,
+    /// Library it resolves to
+    pub canonical: PathBuf,
+}
+
+impl DuplicateLibrary {
+    pub fn message(&self) -> String {
+        format!(
+            "{} lists the Steam library {} more than once (\"{}\"); remove the duplicate entry",
+            self.vdf_path.display(),
+            self.canonical.display(),
+            self.entry
+        )
+    }
+}
+
+/// Find duplicated library entries in every Steam installation's
+/// libraryfolders.vdf. Duplicates are already ignored during detection;
+/// this is for telling the user to clean up the file.
+pub fn find_duplicate_library_entries() -> Vec<DuplicateLibrary> {
+    let Ok(home) = std::env::var("HOME") else {
+        return Vec::new();
+    };
+
+    find_steam_installations(&home)
+        .iter()
+        .flat_map(|steam_info| scan_library_folders(&steam_info.path).1)
+        .collect()
+}
+
 /// Information about a Steam installation
 struct SteamInstallation {
     path: PathBuf,
@@ -72,71 +154,106 @@ struct SteamInstallation {
 
 /// Find all Steam installations on the system
 fn find_steam_installations(home: &str) -> Vec<SteamInstallation> {
-    let mut installations = Vec::new();
+    let mut installations: Vec<SteamInstallation> = Vec::new();
 
     for relative_path in STEAM_PATHS {
         let full_path = PathBuf::from(home).join(relative_path);
 
-        // Check if this is a valid Steam installation
-        if full_path.join("steamapps").exists() || full_path.join("steam.pid").exists() {
-            let is_flatpak = relative_path.contains(".var/app/com.valvesoftware.Steam");
-            let is_snap = relative_path.contains("snap/steam");
-
-            // Avoid duplicates (symlinks can cause the same installation to appear twice)
-            let canonical = full_path.canonicalize().unwrap_or(full_path.clone());
-            if !installations.iter().any(|i: &SteamInstallation| {
-                i.path.canonicalize().unwrap_or(i.path.clone()) == canonical
-            }) {
-                log_info(&format!(
-                    "Found Steam installation: {} (flatpak={}, snap={})",
-                    full_path.display(),
-                    is_flatpak,
-                    is_snap
-                ));
-                installations.push(SteamInstallation {
-                    path: full_path,
-                    is_flatpak,
-                    is_snap,
-                });
-            }
+        // Only canonical roots with a steamapps folder count; dangling Snap
+        // symlinks would otherwise produce dead library paths
+        let Some(canonical) = crate::steam::resolve_steam_root(&full_path) else {
+            continue;
+        };
+        let is_flatpak = relative_path.contains(".var/app/com.valvesoftware.Steam");
+        let is_snap = relative_path.contains("snap/steam");
+
+        // Avoid duplicates (symlinks can cause the same installation to appear twice)
+        if !installations.iter().any(|i| i.path == canonical) {
+            log_info(&format!(
+                "Found Steam installation: {} (flatpak={}, snap={})",
+                canonical.display(),
+                is_flatpak,
+                is_snap
+            ));
+            installations.push(SteamInstallation {
+                path: canonical,
+                is_flatpak,
+                is_snap,
+            });
         }
     }
 
     installations
 }
 
-/// Get all library folders for a Steam installation
+/// Get all library folders for a Steam installation (canonical, deduplicated)
 fn get_library_folders(steam_path: &Path) -> Vec<PathBuf> {
+    scan_library_folders(steam_path).0
+}
+
+/// Canonical library folders for a Steam installation, plus the entries a
+/// single libraryfolders.vdf lists more than once
+fn scan_library_folders(steam_path: &Path) -> (Vec<PathBuf>, Vec<DuplicateLibrary>) {
     let mut folders = Vec::new();
+    let mut duplicates = Vec::new();
 
     // The Steam installation directory itself is always a library
-    folders.push(steam_path.to_path_buf());
-
-    // Parse libraryfolders.vdf for additional libraries
-    let vdf_path = steam_path.join("steamapps/libraryfolders.vdf");
-    if let Ok(content) = fs::read_to_string(&vdf_path) {
-        for path_str in parse_library_folders(&content) {
-            let path = PathBuf::from(&path_str);
-            if path.exists() && !folders.contains(&path) {
-                folders.push(path);
+    folders.push(steam_path.canonicalize().unwrap_or_else(|_| steam_path.to_path_buf()));
+
+    // libraryfolders.vdf, plus the older config/ location (which normally
+    // mirrors the first, so overlap between the two files is expected)
+    for vdf_path in [
+        steam_path.join("steamapps/libraryfolders.vdf"),
+        steam_path.join("config/libraryfolders.vdf"),
+    ] {
+        let Ok(content) = fs::read_to_string(&vdf_path) else {
+            continue;
+        };
+
+        let mut in_file: Vec<PathBuf> = Vec::new();
+        for entry in parse_library_folders(&content) {
+            // Resolves symlinks and trailing slashes; skips missing paths
+            let Ok(canonical) = Path::new(&entry).canonicalize() else {
+                continue;
+            };
+
+            if in_file.contains(&canonical) {
+                duplicates.push(DuplicateLibrary {
+                    vdf_path: vdf_path.clone(),
+                    entry,
+                    canonical,
+                });
+                continue;
+            }
+            in_file.push(canonical.clone());
+
+            if !folders.contains(&canonical) {
+                folders.push(canonical);
             }
         }
     }
 
-    // Also check the older config/libraryfolders.vdf location
-    let old_vdf_path = steam_path.join("config/libraryfolders.vdf");
-    if old_vdf_path != vdf_path {
-        if let Ok(content) = fs::read_to_string(&old_vdf_path) {
-            for path_str in parse_library_folders(&content) {
-                let path = PathBuf::from(&path_str);
-                if path.exists() && !folders.contains(&path) {
-                    folders.push(path);
+    // Libraries an owning account added on older clients are only listed as
+    // BaseInstallFolder_N in config.vdf; family-shared games can live there
+    if let Ok(content) = fs::read_to_string(steam_path.join("config/config.vdf")) {
+        for entry in parse_base_install_folders(&content) {
+            if let Ok(canonical) = Path::new(&entry).canonicalize() {
+                if !folders.contains(&canonical) {
+                    folders.push(canonical);
                 }
             }
         }
     }
 
-    folders
+    (folders, duplicates)
+}
+
+/// SteamID64 of the active account, from its userdata directory name
+fn current_steam_id64() -> Option<u64> {
+    const STEAM_ID64_BASE: u64 = 76561197960265728;
+    let userdata = crate::steam::find_userdata_path()?;
+    let account_id: u64 = userdata.file_name()?.to_str()?.parse().ok()?;
+    Some(STEAM_ID64_BASE + account_id)
 }
 
 /// Parse an appmanifest_*.acf file and create a Game struct
@@ -144,18 +261,31 @@ fn parse_appmanifest(
     manifest_path: &Path,
     steamapps_path: &Path,
     steam_info: &SteamInstallation,
+    launch_options: &HashMap<String, String>,
+    compat_tools: &HashMap<String, String>,
+    current_owner: Option<u64>,
 ) -> Option<Game> {
     let content = fs::read_to_string(manifest_path).ok()?;
     let manifest = AppManifest::from_vdf(&content)?;
 
-    // Only consider fully installed games
-    if !manifest.is_installed() {
+    // Queued and downloading games are listed (flagged) so the UI can show
+    // them greyed out; uninstalled ones are not
+    let install_state = manifest.install_state();
+    if install_state == InstallState::NotInstalled {
         return None;
     }
 
-    // Build the install path
+    // Build the install path. Files deleted outside Steam leave the manifest
+    // behind, so an empty or missing directory is a ghost entry, unless the
+    // first download is still filling it.
     let install_path = steamapps_path.join("common").join(&manifest.install_dir);
-    if !install_path.exists() {
+    if install_state != InstallState::Downloading && !is_populated_dir(&install_path) {
+        log_warning(&format!(
+            "Skipping {} ({}): install directory {} is missing or empty",
+            manifest.name,
+            manifest.app_id,
+            install_path.display()
+        ));
         return None;
     }
 
@@ -165,14 +295,50 @@ fn parse_appmanifest(
         .join(&manifest.app_id)
         .join("pfx");
 
-    let prefix_path = if prefix_path.exists() {
-        Some(prefix_path)
-    } else {
-        None
+    // A STEAM_COMPAT_DATA_PATH override points the game at another (often shared) prefix
+    let override_prefix = launch_options
+        .get(&manifest.app_id)
+        .and_then(|opts| redirected_prefix(opts, &manifest.name))
+        .filter(|p| *p != prefix_path);
+
+    let prefix_redirected = override_prefix.is_some();
+    if let Some(redirected) = &override_prefix {
+        log_info(&format!(
+            "{} uses redirected prefix {} (STEAM_COMPAT_DATA_PATH)",
+            manifest.name,
+            redirected.display()
+        ));
+    }
+
+    let prefix_path = match override_prefix {
+        Some(p) => Some(p),
+        None if prefix_path.exists() => Some(prefix_path),
+        None => None,
     };
 
+    let bundled_runtime = compat_tools
+        .get(&manifest.app_id)
+        .and_then(|tool| find_bundled_runtime(&install_path, tool));
+    if let Some(runtime) = &bundled_runtime {
+        log_info(&format!(
+            "{} uses its bundled runtime {}",
+            manifest.name,
+            runtime.display()
+        ));
+    }
+
+    // Installed by another account, i.e. borrowed through Family Sharing
+    let family_shared = matches!(
+        (manifest.last_owner, current_owner),
+        (Some(owner), Some(current)) if owner != current
+    );
+    if family_shared {
+        log_info(&format!("{} is shared from another Steam account", manifest.name));
+    }
+
     // Look up known game info
     let known_game = find_by_steam_id(&manifest.app_id);
+    let app_type = steam_app_type(&manifest.app_id, &manifest.name, &install_path);
 
     Some(Game {
         name: manifest.name,
@@ -188,9 +354,93 @@ fn parse_appmanifest(
         appdata_roaming_folder: known_game.and_then(|g| g.appdata_roaming_folder.map(String::from)),
         registry_path: known_game.map(|g| g.registry_path.to_string()),
         registry_value: known_game.map(|g| g.registry_value.to_string()),
+        prefix_shared: prefix_redirected,
+        prefix_redirected,
+        bundled_runtime,
+        family_shared,
+        install_size: manifest.size_on_disk,
+        app_type,
+        install_state,
     })
 }
 
+/// Classify a Steam app from what its install directory and manifest show.
+///
+/// The app type proper lives only in Steam's binary appinfo cache; compat
+/// tools (Proton, Steam Linux Runtime) are recognisable by their
+/// toolmanifest.vdf, and the remaining tools by app id.
+fn steam_app_type(app_id: &str, name: &str, install_path: &Path) -> AppType {
+    if install_path.join("toolmanifest.vdf").is_file() || STEAM_TOOL_APP_IDS.contains(&app_id) {
+        return AppType::Tool;
+    }
+    let name = name.trim().to_lowercase();
+    if name.ends_with(" demo") || name.ends_with("(demo)") {
+        return AppType::Demo;
+    }
+    AppType::Game
+}
+
+/// Resolve a compat tool name that is a relative path inside the game's own
+/// install directory to a Proton/Wine runtime there.
+///
+/// Regular tool names ("proton_9", "GE-Proton9-20") and paths escaping the
+/// install directory are ignored.
+fn find_bundled_runtime(install_path: &Path, tool: &str) -> Option<PathBuf> {
+    if !tool.contains('/') || tool.starts_with('/') {
+        return None;
+    }
+
+    let install_root = install_path.canonicalize().ok()?;
+    let runtime = install_root.join(tool).canonicalize().ok()?;
+    if !runtime.starts_with(&install_root) {
+        return None;
+    }
+
+    let is_runtime = ["proton", "files/bin/wine", "dist/bin/wine", "bin/wine"]
+        .iter()
+        .any(|entry| runtime.join(entry).is_file());
+    is_runtime.then_some(runtime)
+}
+
+/// Look up one installed Steam game by App ID without a full scan
+///
+/// Reads only `appmanifest_<app_id>.acf` in each library folder.
+pub fn find_game_by_app_id(app_id: &str) -> Option<Game> {
+    let home = std::env::var("HOME").ok()?;
+    let launch_options = crate::steam::read_all_launch_options();
+    let current_owner = current_steam_id64();
+    let mut scanned: Vec<PathBuf> = Vec::new();
+
+    for steam_info in find_steam_installations(&home) {
+        let compat_tools = crate::steam::read_compat_tool_mapping(&steam_info.path);
+
+        for library_path in get_library_folders(&steam_info.path) {
+            if scanned.contains(&library_path) {
+                continue;
+            }
+            scanned.push(library_path.clone());
+
+            let steamapps = library_path.join("steamapps");
+            let manifest_path = steamapps.join(format!("appmanifest_{}.acf", app_id));
+            if !manifest_path.exists() {
+                continue;
+            }
+            if let Some(game) = parse_appmanifest(
+                &manifest_path,
+                &steamapps,
+                &steam_info,
+                &launch_options,
+                &compat_tools,
+                current_owner,
+            ) {
+                return Some(game);
+            }
+        }
+    }
+
+    None
+}
+
 /// Find the installation path for a specific Steam game by App ID
 pub fn find_game_install_path(app_id: &str) -> Option<PathBuf> {
     let home = std::env::var("HOME").ok()?;
@@ -207,12 +457,12 @@ pub fn find_game_install_path(app_id: &str) -> Option<PathBuf> {
                 let content = fs::read_to_string(&manifest_path).ok()?;
                 let manifest = AppManifest::from_vdf(&content)?;
 
-                if manifest.is_installed() {
+                if manifest.install_state() == InstallState::FullyInstalled {
                     let install_path = library_path
                         .join("steamapps/common")
                         .join(&manifest.install_dir);
 
-                    if install_path.exists() {
+                    if is_populated_dir(&install_path) {
                         return Some(install_path);
                     }
                 }
@@ -223,21 +473,50 @@ pub fn find_game_install_path(app_id: &str) -> Option<PathBuf> {
     None
 }
 
+/// A game's resolved Wine prefix
+#[derive(Debug, Clone)]
+pub struct ResolvedPrefix {
+    pub path: PathBuf,
+    /// Set by a STEAM_COMPAT_DATA_PATH override in the launch options
+    pub redirected: bool,
+}
+
 /// Find the Wine prefix for a specific Steam game by App ID
+///
+/// Honours a STEAM_COMPAT_DATA_PATH override in the game's launch options.
 pub fn find_game_prefix_path(app_id: &str) -> Option<PathBuf> {
+    resolve_game_prefix(app_id).map(|p| p.path)
+}
+
+/// Like [`find_game_prefix_path`], also reporting whether the prefix was
+/// redirected through launch options.
+pub fn resolve_game_prefix(app_id: &str) -> Option<ResolvedPrefix> {
     let home = std::env::var("HOME").ok()?;
 
+    let override_prefix = crate::steam::read_all_launch_options()
+        .get(app_id)
+        .and_then(|opts| redirected_prefix(opts, app_id));
+    if let Some(path) = override_prefix {
+        return Some(ResolvedPrefix {
+            path,
+            redirected: true,
+        });
+    }
+
     for steam_info in find_steam_installations(&home) {
         let libraries = get_library_folders(&steam_info.path);
 
         for library_path in libraries {
-            let prefix_path = library_path
-                .join("steamapps/compatdata")
-                .join(app_id)
-                .join("pfx");
-
-            if prefix_path.exists() {
-                return Some(prefix_path);
+            let compat_dir = library_path.join("steamapps/compatdata").join(app_id);
+            // Fresh prefixes have pfx/ before wineboot fills in drive_c
+            let prefix_path = super::resolve_prefix_root(&compat_dir)
+                .or_else(|| Some(compat_dir.join("pfx")).filter(|p| p.exists()));
+
+            if let Some(path) = prefix_path {
+                return Some(ResolvedPrefix {
+                    path,
+                    redirected: false,
+                });
             }
         }
     }
@@ -245,7 +524,85 @@ pub fn find_game_prefix_path(app_id: &str) -> Option<PathBuf> {
     None
 }
 
+/// The `pfx` under a STEAM_COMPAT_DATA_PATH override, if the launch options
+/// set one and Steam has created it
+fn redirected_prefix(launch_options: &str, label: &str) -> Option<PathBuf> {
+    let compat_dir = crate::steam::compat_data_override(launch_options)?;
+    if let Some(root) = super::resolve_prefix_root(&compat_dir) {
+        return Some(root);
+    }
+    let prefix = compat_dir.join("pfx");
+    if !prefix.exists() {
+        log_warning(&format!(
+            "{}: STEAM_COMPAT_DATA_PATH points to {}, which has no prefix yet; launch the game once",
+            label,
+            prefix.display()
+        ));
+        return None;
+    }
+    Some(prefix)
+}
+
 /// Get the known game configuration for a Steam App ID
 pub fn get_known_game(app_id: &str) -> Option<&'static KnownGame> {
     find_by_steam_id(app_id)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+
+    #[test]
+    fn test_scan_library_folders_duplicates() {
+        let tmp = TempDir::new("libdup");
+        let root = tmp.path();
+        let steam = root.join("Steam");
+        let library = root.join("SteamLibrary");
+        fs::create_dir_all(steam.join("steamapps")).unwrap();
+        fs::create_dir_all(library.join("steamapps")).unwrap();
+        std::os::unix::fs::symlink(&library, root.join("games")).unwrap();
+
+        let vdf = format!(
+            "\"libraryfolders\"\n{{\n\"0\" {{ \"path\" \"{}\" }}\n\"1\" {{ \"path\" \"{}\" }}\n\"2\" {{ \"path\" \"{}/\" }}\n\"3\" {{ \"path\" \"{}\" }}\n}}\n",
+            steam.display(),
+            library.display(),
+            library.display(),
+            root.join("games").display()
+        );
+        fs::write(steam.join("steamapps/libraryfolders.vdf"), vdf).unwrap();
+
+        let (folders, duplicates) = scan_library_folders(&steam);
+
+        assert_eq!(folders.len(), 2);
+        assert_eq!(duplicates.len(), 2);
+        assert!(duplicates.iter().all(|d| d.canonical == folders[1]));
+    }
+
+    #[test]
+    fn test_steam_app_type() {
+        let tmp = TempDir::new("apptype");
+        let tool = tmp.path();
+        fs::write(tool.join("toolmanifest.vdf"), "\"manifest\" {}").unwrap();
+
+        assert_eq!(steam_app_type("2805730", "Proton 9.0", tool), AppType::Tool);
+
+        let missing = Path::new("/nonexistent/nak");
+        assert_eq!(steam_app_type("228980", "Steamworks Common Redistributables", missing), AppType::Tool);
+        assert_eq!(steam_app_type("1", "Some Game Demo", missing), AppType::Demo);
+        assert_eq!(steam_app_type("489830", "Skyrim Special Edition", missing), AppType::Game);
+    }
+
+    #[test]
+    fn test_find_bundled_runtime() {
+        let tmp = TempDir::new("bundled");
+        let game = tmp.path();
+        fs::create_dir_all(game.join("runtime/files/bin")).unwrap();
+        fs::write(game.join("runtime/files/bin/wine"), "").unwrap();
+
+        let found = find_bundled_runtime(game, "./runtime");
+        assert_eq!(found, game.canonicalize().ok().map(|g| g.join("runtime")));
+        assert_eq!(find_bundled_runtime(game, "proton_9"), None);
+        assert_eq!(find_bundled_runtime(game, "../"), None);
+    }
+}
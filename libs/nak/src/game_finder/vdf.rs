@@ -5,6 +5,8 @@
 
 use std::collections::HashMap;
 
+use super::InstallState;
+
 /// A VDF value - either a string or a nested object
 #[derive(Debug, Clone)]
 pub enum VdfValue {
@@ -38,6 +40,14 @@ impl VdfValue {
     pub fn get_str(&self, key: &str) -> Option<&str> {
         self.get(key)?.as_str()
     }
+
+    /// Get a nested value by key, ignoring ASCII case (Steam is inconsistent)
+    pub fn get_ignore_case(&self, key: &str) -> Option<&VdfValue> {
+        self.as_object()?
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
 }
 
 /// Parse a VDF file content into a root object
@@ -155,6 +165,215 @@ fn skip_whitespace_and_comments<I: Iterator<Item = char>>(chars: &mut std::iter:
     }
 }
 
+// ============================================================================
+// In-place Editing
+// ============================================================================
+
+/// A token from VDF text with its byte span
+#[derive(Debug, Clone, PartialEq)]
+enum SpannedToken {
+    /// Quoted string: (unescaped text, span including quotes)
+    Str(String, std::ops::Range<usize>),
+    Open(usize),
+    Close(usize),
+}
+
+fn tokenize_spanned(content: &str) -> Option<Vec<SpannedToken>> {
+    let bytes = content.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' => {
+                tokens.push(SpannedToken::Open(i));
+                i += 1;
+            }
+            b'}' => {
+                tokens.push(SpannedToken::Close(i));
+                i += 1;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'"' => {
+                let start = i;
+                i += 1;
+                let mut value = String::new();
+                loop {
+                    match bytes.get(i)? {
+                        b'"' => break,
+                        b'\\' => {
+                            let escaped = match bytes.get(i + 1)? {
+                                b'n' => Some('\n'),
+                                b't' => Some('\t'),
+                                b'\\' => Some('\\'),
+                                b'"' => Some('"'),
+                                _ => None,
+                            };
+                            match escaped {
+                                Some(c) => {
+                                    value.push(c);
+                                    i += 2;
+                                }
+                                None => {
+                                    value.push('\\');
+                                    i += 1;
+                                }
+                            }
+                        }
+                        _ => {
+                            // Copy a full UTF-8 character
+                            let ch = content[i..].chars().next()?;
+                            value.push(ch);
+                            i += ch.len_utf8();
+                        }
+                    }
+                }
+                i += 1;
+                tokens.push(SpannedToken::Str(value, start..i));
+            }
+            _ => i += 1,
+        }
+    }
+
+    Some(tokens)
+}
+
+/// Find the byte range between the braces of the object at `path`
+/// (keys matched case-insensitively). The range excludes the braces.
+pub fn find_object_span(content: &str, path: &[&str]) -> Option<std::ops::Range<usize>> {
+    let tokens = tokenize_spanned(content)?;
+    let mut idx = 0;
+    let mut end = tokens.len();
+    let mut span = 0..content.len();
+
+    for key in path {
+        let mut depth = 0;
+        let mut found = None;
+        let mut i = idx;
+        while i < end {
+            match &tokens[i] {
+                SpannedToken::Open(_) => depth += 1,
+                SpannedToken::Close(_) => depth -= 1,
+                SpannedToken::Str(k, _) if depth == 0 => {
+                    if let Some(SpannedToken::Open(open)) = tokens.get(i + 1) {
+                        if k.eq_ignore_ascii_case(key) {
+                            found = Some((i + 1, *open));
+                            break;
+                        }
+                    } else {
+                        // key/value pair - skip the value
+                        i += 1;
+                    }
+                }
+                SpannedToken::Str(..) => {}
+            }
+            i += 1;
+        }
+
+        let (open_idx, open_pos) = found?;
+        let close_idx = matching_close(&tokens, open_idx)?;
+        let SpannedToken::Close(close_pos) = tokens[close_idx] else {
+            return None;
+        };
+        idx = open_idx + 1;
+        end = close_idx;
+        span = open_pos + 1..close_pos;
+    }
+
+    Some(span)
+}
+
+fn matching_close(tokens: &[SpannedToken], open_idx: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(open_idx) {
+        match token {
+            SpannedToken::Open(_) => depth += 1,
+            SpannedToken::Close(_) => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            SpannedToken::Str(..) => {}
+        }
+    }
+    None
+}
+
+/// Escape a string for writing into VDF
+pub fn escape_vdf_string(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Set a direct string value inside the object at `path`, creating the
+/// object chain if needed. Returns the edited content.
+pub fn set_string_value(content: &str, path: &[&str], key: &str, value: &str) -> Option<String> {
+    // Create missing parent objects one level at a time
+    let mut existing = path.len();
+    while existing > 0 && find_object_span(content, &path[..existing]).is_none() {
+        existing -= 1;
+    }
+    if existing == 0 {
+        return None;
+    }
+
+    let mut content = content.to_string();
+    for depth in existing..path.len() {
+        let parent = find_object_span(&content, &path[..depth])?;
+        let indent = "\t".repeat(depth);
+        let insert = format!("{indent}\"{}\"\n{indent}{{\n{indent}}}\n", escape_vdf_string(path[depth]));
+        let pos = line_start(&content, parent.end);
+        content.insert_str(pos, &insert);
+    }
+
+    let span = find_object_span(&content, path)?;
+    let tokens = tokenize_spanned(&content[span.clone()])?;
+    let escaped = escape_vdf_string(value);
+
+    // Replace an existing direct child value
+    let mut depth = 0;
+    let mut i = 0;
+    while i < tokens.len() {
+        match &tokens[i] {
+            SpannedToken::Open(_) => depth += 1,
+            SpannedToken::Close(_) => depth -= 1,
+            SpannedToken::Str(k, _) if depth == 0 => {
+                if let Some(SpannedToken::Str(_, value_span)) = tokens.get(i + 1) {
+                    if k.eq_ignore_ascii_case(key) {
+                        let start = span.start + value_span.start;
+                        let end = span.start + value_span.end;
+                        content.replace_range(start..end, &format!("\"{}\"", escaped));
+                        return Some(content);
+                    }
+                    i += 1;
+                }
+            }
+            SpannedToken::Str(..) => {}
+        }
+        i += 1;
+    }
+
+    // Otherwise insert it before the closing brace
+    let indent = "\t".repeat(path.len());
+    let pos = line_start(&content, span.end);
+    content.insert_str(pos, &format!("{indent}\"{}\"\t\t\"{}\"\n", escape_vdf_string(key), escaped));
+    Some(content)
+}
+
+/// Start of the line containing `pos` (so inserts land before a closing brace's indentation)
+fn line_start(content: &str, pos: usize) -> usize {
+    let line_start = content[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    if content[line_start..pos].trim().is_empty() {
+        line_start
+    } else {
+        pos
+    }
+}
+
 /// Parse an appmanifest_*.acf file and extract app info
 #[derive(Debug, Clone)]
 pub struct AppManifest {
@@ -162,6 +381,10 @@ pub struct AppManifest {
     pub name: String,
     pub install_dir: String,
     pub state_flags: u32,
+    /// SteamID64 of the account that installed the game (`LastOwner`)
+    pub last_owner: Option<u64>,
+    /// Bytes on disk (`SizeOnDisk`), None when unset or zero
+    pub size_on_disk: Option<u64>,
 }
 
 impl AppManifest {
@@ -175,34 +398,86 @@ impl AppManifest {
             name: app_state.get_str("name")?.to_string(),
             install_dir: app_state.get_str("installdir")?.to_string(),
             state_flags: app_state.get_str("StateFlags")?.parse().unwrap_or(0),
+            last_owner: app_state
+                .get_str("LastOwner")
+                .and_then(|s| s.parse().ok())
+                .filter(|&id| id != 0),
+            size_on_disk: app_state
+                .get_str("SizeOnDisk")
+                .and_then(|s| s.parse().ok())
+                .filter(|&size| size != 0),
         })
     }
 
-    /// Check if the game is fully installed (StateFlags == 4)
-    pub fn is_installed(&self) -> bool {
-        self.state_flags == 4
+    /// Install state from `StateFlags`.
+    ///
+    /// A running or paused update outranks a queued one, which outranks the
+    /// FullyInstalled bit (4) that stays set while an update is pending.
+    pub fn install_state(&self) -> InstallState {
+        if self.state_flags & STATE_UPDATING != 0 {
+            InstallState::Downloading
+        } else if self.state_flags & STATE_UPDATE_REQUIRED != 0 {
+            InstallState::UpdateRequired
+        } else if self.state_flags & STATE_FULLY_INSTALLED != 0 {
+            InstallState::FullyInstalled
+        } else {
+            InstallState::NotInstalled
+        }
     }
 }
 
-/// Parse libraryfolders.vdf and extract library paths
-pub fn parse_library_folders(content: &str) -> Vec<String> {
-    let mut paths = Vec::new();
+const STATE_UPDATE_REQUIRED: u32 = 0x2;
+const STATE_FULLY_INSTALLED: u32 = 0x4;
+/// UpdateRunning, UpdatePaused, UpdateStarted, Reconfiguring, Validating,
+/// AddingFiles, Preallocating, Downloading, Staging and Committing
+const STATE_UPDATING: u32 =
+    0x100 | 0x200 | 0x400 | 0x1_0000 | 0x2_0000 | 0x4_0000 | 0x8_0000 | 0x10_0000 | 0x20_0000 | 0x40_0000;
 
+/// Parse libraryfolders.vdf and extract library paths, in index order.
+///
+/// Handles both the current format (`"1" { "path" "..." }`) and the old
+/// flat one (`"LibraryFolders" { "1" "..." }`) that older clients and
+/// migrated installs still have.
+pub fn parse_library_folders(content: &str) -> Vec<String> {
     let Some(root) = parse_vdf(content) else {
-        return paths;
+        return Vec::new();
     };
 
-    let Some(library_folders) = root.get("libraryfolders").and_then(|v| v.as_object()) else {
-        return paths;
+    let Some(library_folders) = root.get_ignore_case("libraryfolders").and_then(|v| v.as_object()) else {
+        return Vec::new();
     };
 
-    // Library folders are keyed by index: "0", "1", "2", etc.
-    for value in library_folders.values() {
-        if let Some(path) = value.get_str("path") {
-            paths.push(path.to_string());
-        }
-    }
+    // Library folders are keyed by index: "0", "1", "2", etc.; the old format
+    // mixes in non-numeric keys like "TimeNextStatsReport"
+    let mut entries: Vec<(u32, String)> = library_folders
+        .iter()
+        .filter_map(|(key, value)| {
+            let index = key.parse::<u32>().ok()?;
+            let path = value.get_str("path").or_else(|| value.as_str())?;
+            Some((index, path.to_string()))
+        })
+        .collect();
+    entries.sort();
+    entries.into_iter().map(|(_, path)| path).collect()
+}
 
+/// Library paths from the legacy `BaseInstallFolder_N` keys in config.vdf
+pub fn parse_base_install_folders(content: &str) -> Vec<String> {
+    let Some(root) = parse_vdf(content) else {
+        return Vec::new();
+    };
+    let steam = ["InstallConfigStore", "Software", "Valve", "Steam"]
+        .iter()
+        .try_fold(&root, |node, key| node.get_ignore_case(key))
+        .and_then(VdfValue::as_object);
+
+    let mut paths: Vec<String> = steam
+        .into_iter()
+        .flatten()
+        .filter(|(key, _)| key.to_lowercase().starts_with("baseinstallfolder_"))
+        .filter_map(|(_, value)| value.as_str().map(str::to_string))
+        .collect();
+    paths.sort();
     paths
 }
 
@@ -226,7 +501,69 @@ mod tests {
         assert_eq!(manifest.app_id, "489830");
         assert_eq!(manifest.name, "Skyrim Special Edition");
         assert_eq!(manifest.install_dir, "Skyrim Special Edition");
-        assert!(manifest.is_installed());
+        assert_eq!(manifest.install_state(), InstallState::FullyInstalled);
+    }
+
+    #[test]
+    fn test_parse_last_owner() {
+        let content = r#"
+"AppState"
+{
+    "appid"         "489830"
+    "name"          "Skyrim Special Edition"
+    "StateFlags"    "4"
+    "installdir"    "Skyrim Special Edition"
+    "LastOwner"     "OWNER"
+}
+"#;
+        let last_owner = |owner: &str| AppManifest::from_vdf(&content.replace("OWNER", owner)).unwrap().last_owner;
+        assert_eq!(last_owner("76561198000000001"), Some(76561198000000001));
+        // 0 or a malformed value means no owner is recorded
+        assert_eq!(last_owner("0"), None);
+        assert_eq!(last_owner(""), None);
+    }
+
+    #[test]
+    fn test_install_state_flags() {
+        let content = r#"
+"AppState"
+{
+    "appid"         "489830"
+    "name"          "Skyrim Special Edition"
+    "StateFlags"    "FLAGS"
+    "installdir"    "Skyrim Special Edition"
+}
+"#;
+        let with_flags = |flags: u32| {
+            let content = content.replace("FLAGS", &flags.to_string());
+            AppManifest::from_vdf(&content).unwrap().install_state()
+        };
+        assert_eq!(with_flags(4 | 64), InstallState::FullyInstalled);
+        assert_eq!(with_flags(6), InstallState::UpdateRequired);
+        assert_eq!(with_flags(4 | 0x400 | 0x10_0000), InstallState::Downloading);
+        assert_eq!(with_flags(1026), InstallState::Downloading);
+        assert_eq!(with_flags(1), InstallState::NotInstalled);
+    }
+
+    #[test]
+    fn test_parse_base_install_folders() {
+        let content = r#"
+"InstallConfigStore"
+{
+    "Software"
+    {
+        "Valve"
+        {
+            "Steam"
+            {
+                "BaseInstallFolder_1"   "/mnt/shared/SteamLibrary"
+                "AutoUpdateWindowEnabled"   "0"
+            }
+        }
+    }
+}
+"#;
+        assert_eq!(parse_base_install_folders(content), ["/mnt/shared/SteamLibrary"]);
     }
 
     #[test]
@@ -251,4 +588,37 @@ mod tests {
         assert!(paths.contains(&"/home/user/.local/share/Steam".to_string()));
         assert!(paths.contains(&"/mnt/games/SteamLibrary".to_string()));
     }
+
+    #[test]
+    fn test_parse_flat_library_folders() {
+        let flat = r#"
+"LibraryFolders"
+{
+    "TimeNextStatsReport"   "1712345678"
+    "ContentStatsID"        "-123456789"
+    "2"     "/media/ssd/SteamLibrary"
+    "1"     "/mnt/games/SteamLibrary"
+}
+"#;
+        assert_eq!(
+            parse_library_folders(flat),
+            ["/mnt/games/SteamLibrary", "/media/ssd/SteamLibrary"]
+        );
+    }
+
+    #[test]
+    fn test_set_string_value() {
+        let content = "\"Root\"\n{\n\t\"apps\"\n\t{\n\t\t\"10\"\n\t\t{\n\t\t\t\"LaunchOptions\"\t\t\"-old\"\n\t\t}\n\t}\n}\n";
+
+        let updated = set_string_value(content, &["Root", "apps", "10"], "LaunchOptions", "A=\"b\" %command%").unwrap();
+        let root = parse_vdf(&updated).unwrap();
+        let app = root.get("Root").unwrap().get("apps").unwrap().get("10").unwrap();
+        assert_eq!(app.get_str("LaunchOptions"), Some("A=\"b\" %command%"));
+
+        let created = set_string_value(content, &["Root", "apps", "20"], "LaunchOptions", "%command%").unwrap();
+        let root = parse_vdf(&created).unwrap();
+        let apps = root.get("Root").unwrap().get("apps").unwrap();
+        assert_eq!(apps.get("20").unwrap().get_str("LaunchOptions"), Some("%command%"));
+        assert_eq!(apps.get("10").unwrap().get_str("LaunchOptions"), Some("-old"));
+    }
 }
@@ -0,0 +1,95 @@
+//! Wine audio driver selection
+//!
+//! Wine picks its audio backend from `HKCU\Software\Wine\Drivers\Audio`,
+//! probing them in order when the value is unset. A prefix copied from
+//! another machine can pin a backend this system lacks, leaving games
+//! silent.
+
+use std::error::Error;
+use std::path::Path;
+
+use super::edit_reg_file;
+use crate::game_finder::read_value_from_reg_file;
+use crate::logging::log_install;
+
+/// user.reg key holding the Audio value
+const DRIVERS_KEY: &str = r"[Software\\Wine\\Drivers]";
+
+/// Driver names Wine accepts; "none" disables audio (stored as an empty value)
+pub const AUDIO_DRIVERS: &[&str] = &["pulse", "alsa", "oss", "coreaudio", "none"];
+
+/// Audio driver pinned in a prefix, or None when Wine picks one itself.
+pub fn get_audio_driver(prefix_path: &Path) -> Option<String> {
+    let value = read_value_from_reg_file(&prefix_path.join("user.reg"), r"Software\Wine\Drivers", "Audio")?;
+    if value.is_empty() {
+        Some("none".to_string())
+    } else {
+        Some(value)
+    }
+}
+
+/// Pin a prefix's audio driver (one of [`AUDIO_DRIVERS`]), or pass "" to
+/// let Wine choose again.
+///
+/// Edits user.reg directly, keeping the previous file as
+/// `user.reg.nak-bak`; nothing may be running in the prefix.
+pub fn set_audio_driver(prefix_path: &Path, driver: &str) -> Result<(), Box<dyn Error>> {
+    let driver = driver.trim().to_lowercase();
+    let value = match driver.as_str() {
+        "" => None,
+        "none" => Some("\"\"".to_string()),
+        // PipeWire serves Wine through its PulseAudio server
+        "pipewire" => return Err("Wine has no PipeWire driver; use \"pulse\" (pipewire-pulse)".into()),
+        d if AUDIO_DRIVERS.contains(&d) => Some(format!("\"{}\"", d)),
+        d => {
+            return Err(format!(
+                "Unknown audio driver '{}' (expected one of: {})",
+                d,
+                AUDIO_DRIVERS.join(", ")
+            )
+            .into())
+        }
+    };
+    edit_reg_file(prefix_path, "user.reg", DRIVERS_KEY, &[("Audio", value)])?;
+
+    if driver.is_empty() {
+        log_install("Cleared prefix audio driver (Wine will auto-detect)");
+    } else {
+        log_install(&format!("Set prefix audio driver to {}", driver));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+    use std::fs;
+
+    #[test]
+    fn test_set_audio_driver() {
+        let tmp = TempDir::new("audio_driver");
+        let prefix = tmp.path();
+        let user_reg = "WINE REGISTRY Version 2\n\n[Software\\\\Wine\\\\Drivers] 1712345678\n\"Audio\"=\"oss\"\n\"Graphics\"=\"x11\"\n";
+        fs::write(prefix.join("user.reg"), user_reg).unwrap();
+        assert_eq!(get_audio_driver(prefix).as_deref(), Some("oss"));
+
+        set_audio_driver(prefix, " Pulse ").unwrap();
+        assert_eq!(get_audio_driver(prefix).as_deref(), Some("pulse"));
+        assert_eq!(fs::read_to_string(prefix.join("user.reg.nak-bak")).unwrap(), user_reg);
+
+        // "none" is stored as an empty value
+        set_audio_driver(prefix, "none").unwrap();
+        assert_eq!(get_audio_driver(prefix).as_deref(), Some("none"));
+        assert!(fs::read_to_string(prefix.join("user.reg")).unwrap().contains("\"Audio\"=\"\"\n"));
+
+        // "" unpins the driver and leaves the rest of the key alone
+        set_audio_driver(prefix, "").unwrap();
+        assert_eq!(get_audio_driver(prefix), None);
+        assert!(fs::read_to_string(prefix.join("user.reg")).unwrap().contains("\"Graphics\"=\"x11\"\n"));
+
+        assert!(set_audio_driver(prefix, "pipewire").is_err());
+        assert!(set_audio_driver(prefix, "jack").is_err());
+        assert_eq!(get_audio_driver(prefix), None);
+    }
+}
@@ -0,0 +1,164 @@
+//! Clearing out stale files from the NaK cache
+//!
+//! Interrupted downloads leave `.part` files and installs leave scratch
+//! files behind. [`clean_cache`] removes those that haven't been modified for
+//! a while: everything in the tmp dir and the per-game staging dirs, plus
+//! partial downloads and NaK's installers in the cache root. Anything else
+//! in the cache (the compatibility notes, winetricks' own cache) is left
+//! alone, as are files another process holds a lock on (such as a download
+//! in progress). The config directory is never touched.
+
+use std::fs;
+use std::io;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use walkdir::WalkDir;
+
+use super::prefix_setup::{installer_file_name, DOTNET9_SDK_URL, DOTNET_DESKTOP10_URL};
+use crate::config::AppConfig;
+use crate::logging::log_info;
+
+/// Delete NaK's downloads and scratch files not modified within `older_than`.
+///
+/// Returns the number of bytes freed. Empty directories left behind are
+/// removed as well.
+pub fn clean_cache(older_than: Duration) -> Result<u64, io::Error> {
+    let cache_dir = AppConfig::load().get_cache_dir();
+    let freed = clean_cache_in(
+        &cache_dir,
+        &AppConfig::get_tmp_path(),
+        older_than,
+        &AppConfig::get_config_dir(),
+    )?;
+    log_info(&format!(
+        "Cleaned cache {}: freed {} MiB",
+        cache_dir.display(),
+        freed / (1024 * 1024)
+    ));
+    Ok(freed)
+}
+
+fn clean_cache_in(
+    cache_dir: &Path,
+    tmp_dir: &Path,
+    older_than: Duration,
+    protected: &Path,
+) -> Result<u64, io::Error> {
+    // Someone pointing the cache at (or above) the config dir must not cost them their config
+    for dir in [cache_dir, tmp_dir] {
+        if protected.starts_with(dir) || dir.starts_with(protected) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Refusing to clean {}: it overlaps the config directory", dir.display()),
+            ));
+        }
+    }
+
+    let installers = [installer_file_name(DOTNET9_SDK_URL), installer_file_name(DOTNET_DESKTOP10_URL)];
+    let now = SystemTime::now();
+    let mut freed = 0;
+    let mut scratch_dirs: Vec<PathBuf> = vec![tmp_dir.to_path_buf()];
+
+    if let Ok(entries) = fs::read_dir(cache_dir) {
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else { continue };
+            let name = entry.file_name().to_string_lossy().to_string();
+            if file_type.is_dir() && name.parse::<u32>().is_ok() {
+                // Per-game staging dir (AppConfig::get_cache_dir_for_app)
+                scratch_dirs.push(entry.path());
+            } else if file_type.is_file() && (name.ends_with(".part") || installers.contains(&name.as_str())) {
+                freed += remove_if_stale(&entry.path(), older_than, now);
+            }
+        }
+    }
+
+    for dir in scratch_dirs {
+        for entry in WalkDir::new(&dir).contents_first(true).into_iter().flatten() {
+            if entry.file_type().is_dir() {
+                // Only succeeds once everything inside is gone
+                let _ = fs::remove_dir(entry.path());
+            } else {
+                freed += remove_if_stale(entry.path(), older_than, now);
+            }
+        }
+    }
+    Ok(freed)
+}
+
+/// Remove `path` if it is older than `older_than` and not locked; returns
+/// the bytes freed
+fn remove_if_stale(path: &Path, older_than: Duration, now: SystemTime) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    let age = meta
+        .modified()
+        .ok()
+        .and_then(|m| now.duration_since(m).ok())
+        .unwrap_or_default();
+    if age < older_than || (meta.is_file() && is_locked(path)) {
+        return 0;
+    }
+    match fs::remove_file(path) {
+        Ok(()) if meta.is_file() => meta.len(),
+        _ => 0,
+    }
+}
+
+/// Whether another open file description holds a `flock` on `path`
+fn is_locked(path: &Path) -> bool {
+    let Ok(file) = fs::File::open(path) else {
+        return false;
+    };
+    // SAFETY: flock on a file descriptor we own; released when `file` drops
+    let rc = unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) };
+    rc != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+
+    #[test]
+    fn test_clean_cache_only_removes_nak_files() {
+        let tmp = TempDir::new("clean_cache");
+        let root = tmp.path();
+        for dir in ["tmp/489830", "489830", "vcrun2022"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+
+        let old = SystemTime::now() - Duration::from_secs(3600);
+        let write = |name: &str, len: usize, modified: SystemTime| {
+            let file = fs::File::create(root.join(name)).unwrap();
+            file.set_len(len as u64).unwrap();
+            file.set_modified(modified).unwrap();
+            file
+        };
+        drop(write("tmp/489830/wine_settings.reg", 100, old));
+        drop(write("489830/dotnet-sdk-9.0.310-win-x64.exe.part", 20, old));
+        drop(write("dotnet-sdk-9.0.310-win-x64.exe", 3, old));
+        drop(write("recent.exe.part", 10, SystemTime::now()));
+        drop(write("compat_notes.json", 10, old));
+        drop(write("vcrun2022/vc_redist.x64.exe", 10, old));
+        let locked = write("locked.exe.part", 10, old);
+        // SAFETY: flock on a file descriptor we own
+        assert_eq!(unsafe { libc::flock(locked.as_raw_fd(), libc::LOCK_EX) }, 0);
+
+        let freed = clean_cache_in(root, &root.join("tmp"), Duration::from_secs(60), Path::new("/nonexistent/nak"))
+            .unwrap();
+        assert_eq!(freed, 123);
+        assert!(!root.join("tmp/489830").exists());
+        assert!(!root.join("489830").exists());
+        assert!(root.join("recent.exe.part").exists());
+        assert!(root.join("locked.exe.part").exists());
+        assert!(root.join("compat_notes.json").exists());
+        assert!(root.join("vcrun2022/vc_redist.x64.exe").exists());
+
+        assert!(clean_cache_in(root, &root.join("tmp"), Duration::ZERO, &root.join("config")).is_err());
+
+        drop(locked);
+    }
+}
@@ -0,0 +1,181 @@
+//! Whether a prefix already has NaK's dependencies
+//!
+//! [`install_all_dependencies`](super::install_all_dependencies) leaves a
+//! JSON marker in the prefix root once it finishes, recording the NaK
+//! version, when it ran and what it installed. [`dependencies_installed`]
+//! doesn't trust the marker alone: a registry reset or a prefix copied
+//! without user.reg loses the DLL overrides and .NET keys the marker vouches
+//! for, so those are checked too, along with the .NET SDK on disk.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::{dotnet_kind, has_dotnet, has_dotnet_sdk, verify_verb_overrides};
+use crate::deps::STANDARD_VERBS;
+
+/// Marker file NaK writes in the prefix root after installing dependencies
+const DEPS_MARKER: &str = ".nak_deps.json";
+
+/// Version of the dependency set. Bump when the verbs or runtimes
+/// `install_all_dependencies` installs change, so older prefixes get them.
+const DEPS_VERSION: u32 = 1;
+
+/// Marker names of the .NET runtimes NaK installs outside winetricks,
+/// following winetricks' verb naming
+pub(super) const DOTNET9_SDK: &str = "dotnetsdk9";
+pub(super) const DOTNET_DESKTOP10: &str = "dotnetdesktop10";
+
+/// What a finished dependency install recorded in the prefix
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DepsMarker {
+    /// NaK version that ran the install
+    pub nak_version: String,
+    /// Dependency set version the last complete install set up, see
+    /// `DEPS_VERSION`; 0 if no install has completed without failures
+    pub deps_version: u32,
+    /// When the install finished (Unix seconds)
+    pub installed_at: u64,
+    /// Winetricks verbs and NaK's own .NET runtimes installed so far,
+    /// including those from earlier installs, sorted
+    pub verbs: Vec<String>,
+}
+
+impl DepsMarker {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
+
+/// Read the marker a dependency install left in the prefix, if any
+pub fn read_deps_marker(prefix: &Path) -> Option<DepsMarker> {
+    let content = fs::read_to_string(prefix.join(DEPS_MARKER)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Whether the prefix has the current dependency set installed.
+///
+/// Requires the marker for the current version, the native DLL overrides of
+/// the standard verbs in user.reg, the .NET desktop runtimes and the .NET 9
+/// SDK NaK installs.
+pub fn dependencies_installed(prefix: &Path) -> bool {
+    if read_deps_marker(prefix).is_none_or(|m| m.deps_version != DEPS_VERSION) {
+        return false;
+    }
+
+    if verify_verb_overrides(prefix, STANDARD_VERBS).iter().any(|(_, ok)| !ok) {
+        return false;
+    }
+
+    // Plain dotnetN verbs leave no registry key of their own to look for
+    STANDARD_VERBS
+        .iter()
+        .copied()
+        .filter(|verb| dotnet_kind(verb).is_some())
+        .chain([DOTNET_DESKTOP10])
+        .all(|verb| has_dotnet(prefix, verb))
+        && has_dotnet_sdk(prefix, 9)
+}
+
+/// Record a finished dependency install, adding `verbs` to those of any
+/// earlier marker.
+///
+/// Only a `complete` install, where the whole standard set succeeded,
+/// records the current `DEPS_VERSION`; otherwise the earlier marker's
+/// version is kept.
+pub(super) fn write_deps_marker(prefix: &Path, verbs: &[String], complete: bool) -> Result<(), io::Error> {
+    let previous = read_deps_marker(prefix);
+    let deps_version = if complete {
+        DEPS_VERSION
+    } else {
+        previous.as_ref().map_or(0, |m| m.deps_version)
+    };
+    let mut all: Vec<String> = previous.map(|m| m.verbs).unwrap_or_default();
+    all.extend(verbs.iter().cloned());
+    all.sort();
+    all.dedup();
+
+    let marker = DepsMarker {
+        nak_version: env!("CARGO_PKG_VERSION").to_string(),
+        deps_version,
+        installed_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        verbs: all,
+    };
+    let json = serde_json::to_string_pretty(&marker).map_err(io::Error::other)?;
+    fs::write(prefix.join(DEPS_MARKER), json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+
+    #[test]
+    fn test_dependencies_installed() {
+        let tmp = TempDir::new("deps_installed");
+        let prefix = tmp.path();
+        let program_files = prefix.join("drive_c/Program Files/dotnet");
+        for runtime in ["6.0.36", "10.0.2"] {
+            fs::create_dir_all(program_files.join("shared/Microsoft.WindowsDesktop.App").join(runtime)).unwrap();
+        }
+        fs::create_dir_all(program_files.join("sdk/9.0.310")).unwrap();
+        fs::write(prefix.join("winetricks.log"), "vcrun2022\ndotnet8\n").unwrap();
+        fs::write(
+            prefix.join("user.reg"),
+            "[Software\\\\Wine\\\\DllOverrides] 1700000000\n\
+             \"concrt140\"=\"native,builtin\"\n\"msvcp140\"=\"native,builtin\"\n\
+             \"vcruntime140\"=\"native,builtin\"\n\"vcruntime140_1\"=\"native,builtin\"\n",
+        )
+        .unwrap();
+
+        // Everything is in place except the marker
+        assert!(!dependencies_installed(prefix));
+        write_deps_marker(prefix, &["vcrun2022".to_string()], true).unwrap();
+        assert!(dependencies_installed(prefix));
+
+        fs::remove_dir_all(program_files.join("sdk/9.0.310")).unwrap();
+        assert!(!dependencies_installed(prefix));
+        fs::create_dir_all(program_files.join("sdk/9.0.310")).unwrap();
+
+        let user_reg = fs::read_to_string(prefix.join("user.reg")).unwrap();
+        fs::write(prefix.join("user.reg"), user_reg.replace("\"msvcp140\"=\"native,builtin\"\n", "")).unwrap();
+        assert!(!dependencies_installed(prefix));
+        fs::write(prefix.join("user.reg"), &user_reg).unwrap();
+
+        let mut marker = read_deps_marker(prefix).unwrap();
+        marker.deps_version = DEPS_VERSION + 1;
+        fs::write(prefix.join(DEPS_MARKER), marker.to_json().unwrap()).unwrap();
+        assert!(!dependencies_installed(prefix));
+    }
+
+    #[test]
+    fn test_deps_marker_round_trip() {
+        let tmp = TempDir::new("deps_marker");
+        let prefix = tmp.path();
+        assert!(read_deps_marker(prefix).is_none());
+
+        // A run with failures records what it installed but no dependency set
+        write_deps_marker(prefix, &["vcrun2022".to_string()], false).unwrap();
+        let marker = read_deps_marker(prefix).unwrap();
+        assert_eq!(marker.deps_version, 0);
+        assert_eq!(marker.verbs, ["vcrun2022"]);
+        assert_eq!(marker.nak_version, env!("CARGO_PKG_VERSION"));
+
+        write_deps_marker(prefix, &[DOTNET_DESKTOP10.to_string(), "vcrun2022".to_string()], true).unwrap();
+        let marker = read_deps_marker(prefix).unwrap();
+        assert_eq!(marker.deps_version, DEPS_VERSION);
+        assert_eq!(marker.verbs, [DOTNET_DESKTOP10, "vcrun2022"]);
+
+        // A later failed run doesn't take the completed set away
+        write_deps_marker(prefix, &[DOTNET9_SDK.to_string()], false).unwrap();
+        let marker = read_deps_marker(prefix).unwrap();
+        assert_eq!(marker.deps_version, DEPS_VERSION);
+        assert_eq!(marker.verbs, [DOTNET_DESKTOP10, DOTNET9_SDK, "vcrun2022"]);
+    }
+}
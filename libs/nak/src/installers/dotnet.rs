@@ -0,0 +1,168 @@
+//! .NET Framework and .NET desktop runtime detection
+//!
+//! Classic .NET Framework apps (4.x and older) and modern .NET (Core)
+//! WinForms/WPF apps need different installs: the Framework registers itself
+//! under `NDP` in the registry, while desktop runtimes are side-by-side
+//! folders under `Program Files/dotnet`. Installing one never satisfies a
+//! game that needs the other.
+
+use std::fs;
+use std::path::Path;
+
+use crate::game_finder::read_registry_value;
+
+/// Which kind of .NET a winetricks verb installs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DotnetKind {
+    /// Classic .NET Framework (`dotnet20` ... `dotnet48`)
+    Framework,
+    /// .NET (Core) Windows Desktop Runtime (`dotnetdesktop6` ...)
+    Desktop,
+}
+
+/// .NET Framework 4.x verbs and the minimum `Release` value of each, newest first
+const FRAMEWORK_4_RELEASES: &[(&str, u32)] = &[
+    ("dotnet48", 528040),
+    ("dotnet472", 461808),
+    ("dotnet471", 461308),
+    ("dotnet47", 460798),
+    ("dotnet462", 394802),
+    ("dotnet461", 394254),
+    ("dotnet46", 393295),
+    ("dotnet452", 379893),
+    ("dotnet45", 378389),
+];
+
+/// Pre-4.5 Framework verbs and the NDP key whose `Install` value marks them
+const FRAMEWORK_LEGACY: &[(&str, &str)] = &[
+    ("dotnet40", r"Software\Microsoft\NET Framework Setup\NDP\v4\Full"),
+    ("dotnet35", r"Software\Microsoft\NET Framework Setup\NDP\v3.5"),
+    ("dotnet20", r"Software\Microsoft\NET Framework Setup\NDP\v2.0.50727"),
+];
+
+const FRAMEWORK_4_KEY: &str = r"Software\Microsoft\NET Framework Setup\NDP\v4\Full";
+
+/// Classify a winetricks .NET verb, or `None` if it isn't one.
+///
+/// Plain `dotnet6`/`dotnet8` verbs install the console runtime only and are
+/// neither kind.
+pub fn dotnet_kind(verb: &str) -> Option<DotnetKind> {
+    if verb.strip_prefix("dotnetdesktop").is_some_and(is_number) {
+        return Some(DotnetKind::Desktop);
+    }
+    let version = verb.strip_prefix("dotnet")?;
+    // Framework verbs are 2.0 through 4.8 ("dotnet20", "dotnet472")
+    let major = version.chars().next()?.to_digit(10)?;
+    (version.len() >= 2 && (2..=4).contains(&major) && is_number(version)).then_some(DotnetKind::Framework)
+}
+
+/// Report which .NET Framework versions and desktop runtimes a prefix has.
+///
+/// Returns winetricks verb names: the Framework entries first (newest first,
+/// e.g. `"dotnet48"`), then one `"dotnetdesktopN"` per installed major
+/// version of the desktop runtime.
+pub fn installed_dotnet(prefix_path: &Path) -> Vec<String> {
+    let mut verbs: Vec<String> = Vec::new();
+
+    if let Some(release) = framework_release(prefix_path) {
+        if let Some((verb, _)) = FRAMEWORK_4_RELEASES.iter().find(|(_, min)| release >= *min) {
+            verbs.push(verb.to_string());
+        }
+    }
+    for (verb, key) in FRAMEWORK_LEGACY {
+        if read_registry_value(prefix_path, key, "Install").as_deref() == Some("1") {
+            verbs.push(verb.to_string());
+        }
+    }
+
+    let mut majors = desktop_runtime_majors(prefix_path);
+    majors.sort_unstable_by(|a, b| b.cmp(a));
+    verbs.extend(majors.into_iter().map(|major| format!("dotnetdesktop{}", major)));
+    verbs
+}
+
+/// Whether the prefix already satisfies a .NET verb.
+///
+/// Framework 4.x is an in-place upgrade, so a newer 4.x covers an older one.
+/// Desktop runtimes don't roll forward across major versions, so the exact
+/// major must be present.
+pub fn has_dotnet(prefix_path: &Path, verb: &str) -> bool {
+    match dotnet_kind(verb) {
+        Some(DotnetKind::Framework) => {
+            if let Some((_, needed)) = FRAMEWORK_4_RELEASES.iter().find(|(v, _)| *v == verb) {
+                return framework_release(prefix_path).is_some_and(|release| release >= *needed);
+            }
+            if verb == "dotnet40" && framework_release(prefix_path).is_some() {
+                return true;
+            }
+            FRAMEWORK_LEGACY
+                .iter()
+                .find(|(v, _)| *v == verb)
+                .is_some_and(|(_, key)| read_registry_value(prefix_path, key, "Install").as_deref() == Some("1"))
+        }
+        Some(DotnetKind::Desktop) => verb
+            .strip_prefix("dotnetdesktop")
+            .and_then(|major| major.parse::<u32>().ok())
+            .is_some_and(|major| desktop_runtime_majors(prefix_path).contains(&major)),
+        None => false,
+    }
+}
+
+/// Whether a .NET SDK with the given major version is installed in the prefix.
+///
+/// SDKs aren't winetricks verbs; NaK installs the .NET 9 SDK itself.
+pub fn has_dotnet_sdk(prefix_path: &Path, major: u32) -> bool {
+    let prefix = format!("{}.", major);
+    fs::read_dir(prefix_path.join("drive_c/Program Files/dotnet/sdk"))
+        .is_ok_and(|entries| entries.flatten().any(|e| e.file_name().to_string_lossy().starts_with(&prefix)))
+}
+
+/// `Release` value of the installed .NET Framework 4.5+, if any
+fn framework_release(prefix_path: &Path) -> Option<u32> {
+    read_registry_value(prefix_path, FRAMEWORK_4_KEY, "Release")?.parse().ok()
+}
+
+/// Major versions of the Microsoft.WindowsDesktop.App runtimes in the prefix
+fn desktop_runtime_majors(prefix_path: &Path) -> Vec<u32> {
+    let mut majors: Vec<u32> = Vec::new();
+    for program_files in ["Program Files", "Program Files (x86)"] {
+        let shared = prefix_path
+            .join("drive_c")
+            .join(program_files)
+            .join("dotnet/shared/Microsoft.WindowsDesktop.App");
+        let Ok(entries) = fs::read_dir(shared) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Some(major) = name.split('.').next().and_then(|m| m.parse::<u32>().ok()) else {
+                continue;
+            };
+            if !majors.contains(&major) {
+                majors.push(major);
+            }
+        }
+    }
+    majors
+}
+
+fn is_number(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dotnet_kind() {
+        assert_eq!(dotnet_kind("dotnet48"), Some(DotnetKind::Framework));
+        assert_eq!(dotnet_kind("dotnet35"), Some(DotnetKind::Framework));
+        assert_eq!(dotnet_kind("dotnet20"), Some(DotnetKind::Framework));
+        assert_eq!(dotnet_kind("dotnetdesktop8"), Some(DotnetKind::Desktop));
+        assert_eq!(dotnet_kind("dotnet8"), None);
+        assert_eq!(dotnet_kind("dotnet6"), None);
+        assert_eq!(dotnet_kind("vcrun2022"), None);
+        assert_eq!(dotnet_kind("dotnetdesktop"), None);
+    }
+}
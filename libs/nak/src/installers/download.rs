@@ -0,0 +1,356 @@
+//! Resumable downloads into the NaK cache
+//!
+//! A download is written to `<file>.part` in a staging directory and only
+//! renamed into place once it is complete and verified, so a cached file is
+//! never a truncated one. Cancelling or losing the connection keeps the
+//! partial file, and the next attempt asks the server for just the rest with
+//! an HTTP range request.
+
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Write};
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use super::{InstallError, InstallErrorKind, TaskContext};
+use crate::logging::{log_download, log_warning};
+
+const USER_AGENT: &str = "NaK-Rust";
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Download `url` to `dest`, resuming a partial download from an earlier
+/// attempt. The partial file is kept in `staging_dir` (which should be on
+/// the same filesystem as `dest`). Checks the size the server reported and,
+/// when given, the SHA-256 (hex) before moving the file into place.
+/// Fails if another NaK process is downloading the same file.
+pub(super) fn download_resumable(
+    url: &str,
+    dest: &Path,
+    staging_dir: &Path,
+    expected_sha256: Option<&str>,
+    ctx: &TaskContext,
+) -> Result<(), Box<dyn Error>> {
+    let part = part_path(dest, staging_dir);
+    fs::create_dir_all(staging_dir)?;
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Held until the file is in place, so another NaK process can't write
+    // the same download and a cache cleanup leaves it alone
+    let lock = lock_part(&part)?;
+
+    let total = match fetch_into(url, &part, ctx)? {
+        Fetched::Total(total) => total,
+        // The partial didn't match what the server has; start over once
+        Fetched::Restart => {
+            lock.set_len(0)?;
+            match fetch_into(url, &part, ctx)? {
+                Fetched::Total(total) => total,
+                Fetched::Restart => return Err(format!("Server refused to send {}", url).into()),
+            }
+        }
+    };
+
+    if let Err(e) = verify(&part, total, expected_sha256) {
+        // A bad file can't be resumed into a good one
+        let _ = fs::remove_file(&part);
+        return Err(e);
+    }
+    fs::rename(&part, dest)?;
+    Ok(())
+}
+
+/// Open (creating if needed) and lock a partial download
+fn lock_part(part: &Path) -> Result<fs::File, Box<dyn Error>> {
+    let file = OpenOptions::new().create(true).append(true).open(part)?;
+    // SAFETY: flock on a file descriptor we own; released when `file` drops
+    let rc = unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) };
+    if rc != 0 {
+        let err = io::Error::last_os_error();
+        if err.kind() == io::ErrorKind::WouldBlock {
+            let name = part.file_name().unwrap_or_default().to_string_lossy();
+            return Err(InstallError::new(
+                InstallErrorKind::Other,
+                format!("{} is already being downloaded by another NaK process", name),
+            )
+            .into());
+        }
+        return Err(err.into());
+    }
+    Ok(file)
+}
+
+/// Outcome of one request for the rest of a download
+enum Fetched {
+    /// The partial file is complete; the server's total size, if it gave one
+    Total(Option<u64>),
+    /// The partial file has to be discarded
+    Restart,
+}
+
+fn fetch_into(url: &str, part: &Path, ctx: &TaskContext) -> Result<Fetched, Box<dyn Error>> {
+    let offset = fs::metadata(part).map(|m| m.len()).unwrap_or(0);
+    let name = part.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+    let mut request = ureq::get(url).set("User-Agent", USER_AGENT);
+    if offset > 0 {
+        request = request.set("Range", &format!("bytes={}-", offset));
+    }
+    let response = match request.call() {
+        Ok(response) => response,
+        // Nothing left past the offset: complete if the sizes agree
+        Err(ureq::Error::Status(416, response)) if offset > 0 => {
+            return Ok(match content_range_total(&response) {
+                Some(total) if total == offset => Fetched::Total(Some(total)),
+                _ => Fetched::Restart,
+            });
+        }
+        Err(e) => {
+            return Err(InstallError::new(InstallErrorKind::Network, format!("{}: {}", url, e)).into());
+        }
+    };
+
+    let (mut file, total) = if response.status() == 206 && offset > 0 {
+        let total = content_range_total(&response);
+        let start = response
+            .header("Content-Range")
+            .and_then(|r| r.strip_prefix("bytes "))
+            .and_then(|r| r.split('-').next())
+            .and_then(|s| s.parse::<u64>().ok());
+        if start != Some(offset) {
+            return Ok(Fetched::Restart);
+        }
+        let resumed = format!("Resuming {} at {} MiB", name, offset / (1024 * 1024));
+        log_download(&resumed);
+        ctx.log(resumed);
+        (OpenOptions::new().append(true).open(part)?, total)
+    } else {
+        if offset > 0 {
+            log_warning(&format!("Server can't resume {}; downloading it again", name));
+        }
+        let total = response
+            .header("Content-Length")
+            .and_then(|l| l.parse::<u64>().ok());
+        (fs::File::create(part)?, total)
+    };
+
+    let mut reader = response.into_reader();
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    loop {
+        if ctx.is_cancelled() {
+            // Keep what arrived so far for the next attempt
+            file.flush()?;
+            return Err(InstallError::cancelled().into());
+        }
+        let read = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => {
+                file.flush()?;
+                return Err(InstallError::new(
+                    InstallErrorKind::Network,
+                    format!("connection to {} lost: {}", url, e),
+                )
+                .into());
+            }
+        };
+        file.write_all(&buf[..read])?;
+    }
+    file.sync_all()?;
+    Ok(Fetched::Total(total))
+}
+
+/// Total size from a `Content-Range: bytes a-b/total` (or `bytes */total`) header
+fn content_range_total(response: &ureq::Response) -> Option<u64> {
+    response
+        .header("Content-Range")?
+        .rsplit('/')
+        .next()?
+        .parse()
+        .ok()
+}
+
+fn verify(path: &Path, total: Option<u64>, expected_sha256: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let size = fs::metadata(path)?.len();
+    if let Some(total) = total {
+        if size != total {
+            return Err(format!("Download incomplete: got {} of {} bytes", size, total).into());
+        }
+    }
+
+    if let Some(expected) = expected_sha256.map(str::trim).filter(|s| !s.is_empty()) {
+        let mut hasher = Sha256::new();
+        io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+        let actual: String = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+        if !actual.eq_ignore_ascii_case(expected) {
+            return Err(format!("Checksum mismatch: expected {}, got {}", expected, actual).into());
+        }
+    }
+    Ok(())
+}
+
+/// `<staging_dir>/<dest file name>.part`
+fn part_path(dest: &Path, staging_dir: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    staging_dir.join(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    use crate::utils::TempDir;
+
+    const BODY: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+    #[derive(Clone, Copy)]
+    enum Server {
+        /// Honors range requests
+        Ranges,
+        /// Always sends the whole file with a 200
+        NoRanges,
+        /// Answers range requests with a 206 from the wrong offset
+        WrongRange,
+    }
+
+    /// Serve `requests` requests for BODY on a local port; returns the URL
+    fn serve(server: Server, requests: usize) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/file.exe", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(requests) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut offset = None;
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap() == 0 || line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some(range) = line.to_ascii_lowercase().strip_prefix("range: bytes=") {
+                        offset = range.trim().trim_end_matches('-').parse::<usize>().ok();
+                    }
+                }
+
+                let total = BODY.len();
+                let (head, body): (String, &[u8]) = match (server, offset) {
+                    (Server::Ranges, Some(o)) if o >= total => (
+                        format!("HTTP/1.1 416 Range Not Satisfiable\r\nContent-Range: bytes */{}\r\nContent-Length: 0", total),
+                        &[],
+                    ),
+                    (Server::Ranges, Some(o)) => (
+                        format!(
+                            "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes {}-{}/{}\r\nContent-Length: {}",
+                            o,
+                            total - 1,
+                            total,
+                            total - o
+                        ),
+                        &BODY[o..],
+                    ),
+                    (Server::WrongRange, Some(_)) => (
+                        format!(
+                            "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes 0-{}/{}\r\nContent-Length: {}",
+                            total - 1,
+                            total,
+                            total
+                        ),
+                        BODY,
+                    ),
+                    _ => (format!("HTTP/1.1 200 OK\r\nContent-Length: {}", total), BODY),
+                };
+                let _ = stream.write_all(format!("{}\r\nConnection: close\r\n\r\n", head).as_bytes());
+                let _ = stream.write_all(body);
+            }
+        });
+        url
+    }
+
+    fn context() -> TaskContext {
+        TaskContext::new(|_| {}, |_| {}, |_| {}, Arc::new(AtomicBool::new(false)))
+    }
+
+    /// Download BODY from `server` with `partial` already in the .part file
+    fn download_with_partial(name: &str, server: Server, requests: usize, partial: &[u8]) -> Vec<u8> {
+        let tmp = TempDir::new(&format!("download_{}", name));
+        let dir = tmp.path();
+        let dest = dir.join("file.exe");
+        let staging = dir.join("480");
+        fs::create_dir_all(&staging).unwrap();
+        fs::write(part_path(&dest, &staging), partial).unwrap();
+
+        download_resumable(&serve(server, requests), &dest, &staging, None, &context()).unwrap();
+        assert!(!part_path(&dest, &staging).exists());
+        fs::read(&dest).unwrap()
+    }
+
+    #[test]
+    fn test_part_path() {
+        assert_eq!(
+            part_path(Path::new("/cache/dotnet-sdk.exe"), Path::new("/cache/480")),
+            PathBuf::from("/cache/480/dotnet-sdk.exe.part")
+        );
+    }
+
+    #[test]
+    fn test_content_range_total() {
+        let partial: ureq::Response = "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes 10-35/36\r\n\r\n"
+            .parse()
+            .unwrap();
+        assert_eq!(content_range_total(&partial), Some(36));
+        let unsatisfiable: ureq::Response = "HTTP/1.1 416 Range Not Satisfiable\r\nContent-Range: bytes */36\r\n\r\n"
+            .parse()
+            .unwrap();
+        assert_eq!(content_range_total(&unsatisfiable), Some(36));
+        let full: ureq::Response = "HTTP/1.1 200 OK\r\n\r\n".parse().unwrap();
+        assert_eq!(content_range_total(&full), None);
+    }
+
+    #[test]
+    fn test_verify_checks_size_and_hash() {
+        let tmp = TempDir::new("download_verify");
+        let path = tmp.path().join("file.exe");
+        fs::write(&path, b"abc").unwrap();
+        let sha_abc = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+        assert!(verify(&path, Some(3), Some(sha_abc)).is_ok());
+        assert!(verify(&path, None, Some(&sha_abc.to_uppercase())).is_ok());
+        assert!(verify(&path, Some(4), None).is_err());
+        assert!(verify(&path, None, Some(&sha_abc.replace('b', "c"))).is_err());
+    }
+
+    #[test]
+    fn test_locked_part_is_refused() {
+        let tmp = TempDir::new("download_locked");
+        let dir = tmp.path();
+        let dest = dir.join("file.exe");
+        let staging = dir.join("480");
+        fs::create_dir_all(&staging).unwrap();
+
+        // Another download of the same file is in progress
+        let other = lock_part(&part_path(&dest, &staging)).unwrap();
+        let err = download_resumable("http://127.0.0.1:9/file.exe", &dest, &staging, None, &context()).unwrap_err();
+        assert!(err.to_string().contains("already being downloaded"));
+        drop(other);
+    }
+
+    #[test]
+    fn test_resume_and_restart() {
+        // Resumed from the offset
+        assert_eq!(download_with_partial("resume", Server::Ranges, 1, &BODY[..10]), BODY);
+        // Already complete: the server answers 416 with the same total
+        assert_eq!(download_with_partial("complete", Server::Ranges, 1, BODY), BODY);
+        // Server ignores the range and sends everything again
+        assert_eq!(download_with_partial("norange", Server::NoRanges, 1, b"stale"), BODY);
+        // Range answered from the wrong offset: start over without one
+        assert_eq!(download_with_partial("wrongrange", Server::WrongRange, 2, &BODY[..10]), BODY);
+    }
+}
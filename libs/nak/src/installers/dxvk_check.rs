@@ -0,0 +1,183 @@
+//! DXVK install vs. DllOverrides consistency
+//!
+//! DXVK only loads when its DLLs are in the prefix *and* Wine is told to
+//! prefer them. This cross-checks the DLLs in system32/syswow64 against the
+//! `HKCU\Software\Wine\DllOverrides` entries in user.reg and can reconcile
+//! the two. Proton prefixes get their overrides from WINEDLLOVERRIDES at
+//! launch, so a missing registry override is not a problem there.
+
+use std::error::Error;
+use std::path::Path;
+
+use super::edit_reg_file;
+use super::prefix_kind::{prefix_kind, PrefixKind};
+use super::vcredist::is_native_dll;
+use crate::game_finder::read_value_from_reg_file;
+use crate::logging::log_install;
+
+/// DLLs DXVK replaces
+const DXVK_DLLS: &[&str] = &["d3d9", "d3d10core", "d3d11", "dxgi"];
+
+/// Key header for DllOverrides as written in user.reg
+const DLL_OVERRIDES_KEY: &str = r"[Software\\Wine\\DllOverrides]";
+
+/// How a DXVK DLL and its override disagree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DxvkMismatch {
+    /// DXVK DLL is installed but no override makes Wine load it
+    MissingOverride,
+    /// A native-only override is set but there is no native DLL to load
+    OverrideWithoutDll,
+}
+
+impl DxvkMismatch {
+    pub fn description(&self) -> &'static str {
+        match self {
+            DxvkMismatch::MissingOverride => "DXVK DLL installed but not overridden to native",
+            DxvkMismatch::OverrideWithoutDll => "Native override set but DXVK DLL is missing",
+        }
+    }
+}
+
+/// State of one DXVK DLL in a prefix
+#[derive(Debug, Clone)]
+pub struct DxvkDllCheck {
+    pub dll: &'static str,
+    /// A native (non-Wine) DLL is present in system32 or syswow64
+    pub dll_present: bool,
+    /// Current DllOverrides value, if any (e.g. "native,builtin")
+    pub override_value: Option<String>,
+    pub mismatch: Option<DxvkMismatch>,
+}
+
+/// Result of [`verify_dxvk_setup`]
+#[derive(Debug, Clone)]
+pub struct DxvkSetupStatus {
+    /// Prefix is managed by Proton, which sets overrides at launch
+    pub proton_managed: bool,
+    pub dlls: Vec<DxvkDllCheck>,
+}
+
+impl DxvkSetupStatus {
+    pub fn is_consistent(&self) -> bool {
+        self.dlls.iter().all(|d| d.mismatch.is_none())
+    }
+}
+
+/// Cross-check DXVK DLLs against the prefix's d3d/dxgi DllOverrides.
+pub fn verify_dxvk_setup(prefix_path: &Path) -> DxvkSetupStatus {
+    let proton_managed = prefix_kind(prefix_path) == PrefixKind::Proton;
+    let user_reg = prefix_path.join("user.reg");
+    let windows = prefix_path.join("drive_c/windows");
+
+    let dlls = DXVK_DLLS
+        .iter()
+        .map(|&dll| {
+            let file = format!("{}.dll", dll);
+            let dll_present = is_native_dll(&windows.join("system32").join(&file))
+                || is_native_dll(&windows.join("syswow64").join(&file));
+            let override_value =
+                read_value_from_reg_file(&user_reg, r"Software\Wine\DllOverrides", dll);
+
+            let prefers_native = override_value.as_deref().is_some_and(is_native_first);
+            let native_only = override_value.as_deref().is_some_and(is_native_only);
+
+            let mismatch = if dll_present && !prefers_native && !proton_managed {
+                Some(DxvkMismatch::MissingOverride)
+            } else if !dll_present && native_only {
+                Some(DxvkMismatch::OverrideWithoutDll)
+            } else {
+                None
+            };
+
+            DxvkDllCheck {
+                dll,
+                dll_present,
+                override_value,
+                mismatch,
+            }
+        })
+        .collect();
+
+    DxvkSetupStatus {
+        proton_managed,
+        dlls,
+    }
+}
+
+/// Reconcile DllOverrides with the installed DXVK DLLs.
+///
+/// Adds `native,builtin` overrides for installed DLLs and drops native-only
+/// overrides whose DLL is missing. Edits user.reg directly, keeping the
+/// previous file as `user.reg.nak-bak`; nothing may be running in the
+/// prefix. Returns the number of overrides changed.
+pub fn fix_dxvk_setup(prefix_path: &Path) -> Result<usize, Box<dyn Error>> {
+    let status = verify_dxvk_setup(prefix_path);
+    let changes: Vec<(&str, Option<String>)> = status
+        .dlls
+        .iter()
+        .filter_map(|d| match d.mismatch? {
+            DxvkMismatch::MissingOverride => Some((d.dll, Some("\"native,builtin\"".to_string()))),
+            DxvkMismatch::OverrideWithoutDll => Some((d.dll, None)),
+        })
+        .collect();
+
+    if changes.is_empty() {
+        return Ok(0);
+    }
+    edit_reg_file(prefix_path, "user.reg", DLL_OVERRIDES_KEY, &changes)?;
+
+    for (dll, value) in &changes {
+        log_install(&format!(
+            "DXVK override for {}: {}",
+            dll,
+            value.as_deref().unwrap_or("removed")
+        ));
+    }
+    Ok(changes.len())
+}
+
+/// "native", "native,builtin", "n,b" etc.
+fn is_native_first(value: &str) -> bool {
+    value.trim().to_lowercase().starts_with('n')
+}
+
+/// Native with no builtin fallback
+fn is_native_only(value: &str) -> bool {
+    let value = value.trim().to_lowercase();
+    value == "native" || value == "n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+    use std::fs;
+
+    #[test]
+    fn test_fix_dxvk_setup() {
+        let tmp = TempDir::new("dxvk_setup");
+        let prefix = tmp.path().join("pfx");
+        let system32 = prefix.join("drive_c/windows/system32");
+        fs::create_dir_all(&system32).unwrap();
+        fs::write(system32.join("dxgi.dll"), "MZ dxvk").unwrap();
+        fs::write(system32.join("d3d9.dll"), "MZ Wine builtin DLL").unwrap();
+        let user_reg = "WINE REGISTRY Version 2\n\n[Software\\\\Wine\\\\DllOverrides] 1712345678\n#time=1da8c3f\n\"d3d11\"=\"native\"\n\"dinput8\"=\"native,builtin\"\n";
+        fs::write(prefix.join("user.reg"), user_reg).unwrap();
+
+        let status = verify_dxvk_setup(&prefix);
+        let mismatch = |dll: &str| status.dlls.iter().find(|d| d.dll == dll).unwrap().mismatch;
+        assert_eq!(mismatch("dxgi"), Some(DxvkMismatch::MissingOverride));
+        assert_eq!(mismatch("d3d11"), Some(DxvkMismatch::OverrideWithoutDll));
+        assert_eq!(mismatch("d3d9"), None);
+
+        assert_eq!(fix_dxvk_setup(&prefix).unwrap(), 2);
+        assert_eq!(
+            fs::read_to_string(prefix.join("user.reg")).unwrap(),
+            "WINE REGISTRY Version 2\n\n[Software\\\\Wine\\\\DllOverrides] 1712345678\n#time=1da8c3f\n\"dinput8\"=\"native,builtin\"\n\"dxgi\"=\"native,builtin\"\n"
+        );
+        assert_eq!(fs::read_to_string(prefix.join("user.reg.nak-bak")).unwrap(), user_reg);
+        assert!(verify_dxvk_setup(&prefix).is_consistent());
+        assert_eq!(fix_dxvk_setup(&prefix).unwrap(), 0);
+    }
+}
@@ -0,0 +1,84 @@
+//! Installer error causes
+//!
+//! Installer functions return `Box<dyn Error>`, which callers could only
+//! tell apart by message. Errors with a cause worth reacting to (cancel,
+//! bad prefix, missing Proton) are raised as [`InstallError`], and
+//! [`error_kind`] classifies any boxed error, recognizing I/O and HTTP
+//! failures from the standard library and ureq as well.
+
+use std::error::Error;
+use std::fmt;
+
+/// Broad cause of an installer failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallErrorKind {
+    /// A download or HTTP request failed
+    Network,
+    /// Reading or writing a file failed
+    Io,
+    /// The user cancelled the task
+    Cancelled,
+    /// The requested Proton build (or its wine binaries) wasn't found
+    ProtonNotFound,
+    /// The prefix can't be used for this (wrong architecture, missing files,
+    /// a mod manager running in it)
+    PrefixInvalid,
+    /// Anything else
+    Other,
+}
+
+/// An installer error with a known cause
+#[derive(Debug)]
+pub struct InstallError {
+    kind: InstallErrorKind,
+    message: String,
+}
+
+impl InstallError {
+    pub fn new(kind: InstallErrorKind, message: impl Into<String>) -> Self {
+        Self { kind, message: message.into() }
+    }
+
+    /// The error cancellable tasks return once the cancel flag is set
+    pub fn cancelled() -> Self {
+        Self::new(InstallErrorKind::Cancelled, "Cancelled")
+    }
+
+    pub fn kind(&self) -> InstallErrorKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for InstallError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for InstallError {}
+
+/// Classify an error returned by an installer function.
+///
+/// Follows the `source()` chain, so a wrapped I/O or ureq error still counts.
+pub fn error_kind(err: &(dyn Error + 'static)) -> InstallErrorKind {
+    let mut current = Some(err);
+    while let Some(e) = current {
+        if let Some(install) = e.downcast_ref::<InstallError>() {
+            return install.kind;
+        }
+        if e.is::<ureq::Error>() {
+            return InstallErrorKind::Network;
+        }
+        if let Some(io) = e.downcast_ref::<std::io::Error>() {
+            // ureq reports a dropped connection mid-body as an io::Error
+            return match io.kind() {
+                std::io::ErrorKind::ConnectionReset
+                | std::io::ErrorKind::ConnectionAborted
+                | std::io::ErrorKind::TimedOut => InstallErrorKind::Network,
+                _ => InstallErrorKind::Io,
+            };
+        }
+        current = e.source();
+    }
+    InstallErrorKind::Other
+}
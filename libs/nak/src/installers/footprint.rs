@@ -0,0 +1,145 @@
+//! Pre-flight disk space estimate for a dependency install
+//!
+//! Adds up what [`install_all_dependencies`](super::install_all_dependencies)
+//! would download and unpack for a prefix and game, skipping verbs the
+//! prefix's winetricks.log already lists and downloads already in the cache.
+//! Sizes are rounded figures for the current redistributables, so the result
+//! is an estimate to warn on, not a guarantee.
+
+use std::path::Path;
+
+use super::deps_marker::DOTNET_DESKTOP10;
+use super::prefix_setup::{installer_file_name, DOTNET9_SDK_URL, DOTNET_DESKTOP10_URL};
+use super::{has_dotnet, has_dotnet_sdk, missing_vcredist_for_game, winetricks_log};
+use crate::config::AppConfig;
+use crate::deps::{supported_verb, STANDARD_VERBS};
+use crate::utils::is_populated_dir;
+
+const MIB: u64 = 1024 * 1024;
+
+/// A freshly created Proton prefix, before any dependencies
+const NEW_PREFIX_SIZE: u64 = 700 * MIB;
+/// Registry growth and temporary files in an existing prefix
+const REGISTRY_OVERHEAD: u64 = 50 * MIB;
+
+/// Approximate (download, extracted) size of the custom .NET installers
+const DOTNET9_SDK_COST: (u64, u64) = (210 * MIB, 800 * MIB);
+const DOTNET_DESKTOP10_COST: (u64, u64) = (60 * MIB, 150 * MIB);
+
+/// Disk space a dependency install for a game is expected to need
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SetupFootprint {
+    /// Bytes to download into the cache
+    pub download: u64,
+    /// Bytes unpacked into the prefix
+    pub extracted: u64,
+    /// Download + extracted + prefix/registry overhead
+    pub total_required: u64,
+}
+
+/// Estimate the disk space setting up `prefix_path` for `app_id` needs.
+///
+/// Downloads land in the NaK cache and the rest in the prefix; when those
+/// are on different filesystems, `download` and `extracted` say how to split
+/// the check.
+pub fn estimated_setup_footprint(prefix_path: &Path, app_id: u32) -> SetupFootprint {
+    // Winetricks keeps its downloads in the default cache; NaK's own
+    // installers follow the configured cache location
+    footprint_in(
+        prefix_path,
+        app_id,
+        &AppConfig::get_default_cache_dir(),
+        &AppConfig::load().get_cache_dir(),
+    )
+}
+
+fn footprint_in(prefix_path: &Path, app_id: u32, cache_dir: &Path, installer_cache: &Path) -> SetupFootprint {
+    let app_id = app_id.to_string();
+    let installed = winetricks_log(prefix_path).unwrap_or_default();
+
+    let mut verbs: Vec<&str> = STANDARD_VERBS.to_vec();
+    verbs.extend(missing_vcredist_for_game(prefix_path, &app_id));
+
+    let mut footprint = SetupFootprint::default();
+    let mut downloads: Vec<&str> = Vec::new();
+    for verb in verbs {
+        if installed.iter().any(|v| v == verb) {
+            continue;
+        }
+        let Some(cost) = supported_verb(verb) else {
+            continue;
+        };
+        footprint.extracted += cost.installed_mib * MIB;
+        if !downloads.contains(&cost.cache) && !is_populated_dir(&cache_dir.join(cost.cache)) {
+            downloads.push(cost.cache);
+            footprint.download += cost.download_mib * MIB;
+        }
+    }
+
+    let custom = [
+        (DOTNET9_SDK_URL, DOTNET9_SDK_COST, has_dotnet_sdk(prefix_path, 9)),
+        (DOTNET_DESKTOP10_URL, DOTNET_DESKTOP10_COST, has_dotnet(prefix_path, DOTNET_DESKTOP10)),
+    ];
+    for (url, (download, extracted), present) in custom {
+        if present {
+            continue;
+        }
+        footprint.extracted += extracted;
+        if !installer_cache.join(installer_file_name(url)).exists() {
+            footprint.download += download;
+        }
+    }
+
+    let overhead = if prefix_path.join("drive_c").is_dir() {
+        REGISTRY_OVERHEAD
+    } else {
+        NEW_PREFIX_SIZE
+    };
+    footprint.total_required = footprint.download + footprint.extracted + overhead;
+    footprint
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+    use std::fs;
+
+    #[test]
+    fn test_estimated_setup_footprint() {
+        let tmp = TempDir::new("footprint");
+        let root = tmp.path();
+        let (prefix, cache_dir, installer_cache) = (root.join("pfx"), root.join("winetricks"), root.join("nak"));
+        for dir in [prefix.join("drive_c"), cache_dir.clone(), installer_cache.clone()] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        // Only d3dx9 and xact are left, both from the DirectX redistributable
+        let done: Vec<&str> = STANDARD_VERBS.iter().copied().filter(|v| !matches!(*v, "d3dx9" | "xact")).collect();
+        fs::write(prefix.join("winetricks.log"), done.join("\n")).unwrap();
+
+        let (sdk_download, sdk_extracted) = DOTNET9_SDK_COST;
+        let (desktop_download, desktop_extracted) = DOTNET_DESKTOP10_COST;
+        let footprint = footprint_in(&prefix, 0, &cache_dir, &installer_cache);
+        assert_eq!(footprint.download, 96 * MIB + sdk_download + desktop_download);
+        assert_eq!(footprint.extracted, 75 * MIB + sdk_extracted + desktop_extracted);
+        assert_eq!(
+            footprint.total_required,
+            footprint.download + footprint.extracted + REGISTRY_OVERHEAD
+        );
+
+        // Cached downloads cost only their extracted size
+        fs::create_dir_all(cache_dir.join("directx9")).unwrap();
+        fs::write(cache_dir.join("directx9/directx_Jun2010_redist.exe"), "").unwrap();
+        fs::write(installer_cache.join(installer_file_name(DOTNET9_SDK_URL)), "").unwrap();
+        let footprint = footprint_in(&prefix, 0, &cache_dir, &installer_cache);
+        assert_eq!(footprint.download, desktop_download);
+        assert_eq!(footprint.extracted, 75 * MIB + sdk_extracted + desktop_extracted);
+
+        // A prefix that doesn't exist yet needs the full base size
+        let footprint = footprint_in(&root.join("new"), 0, &cache_dir, &installer_cache);
+        assert_eq!(
+            footprint.total_required,
+            footprint.download + footprint.extracted + NEW_PREFIX_SIZE
+        );
+    }
+}
@@ -0,0 +1,87 @@
+//! Wine Gecko / Wine Mono detection and installation
+//!
+//! Games with embedded web views need Wine Gecko (mshtml) and unmanaged
+//! .NET launchers need Wine Mono. Proton normally installs both while
+//! creating a prefix, but prefixes made elsewhere or with a stripped build
+//! can lack them, leaving blank web panels or failing .NET launchers.
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::TaskContext;
+use crate::logging::{log_install, log_warning};
+use crate::runtime_wrap;
+use crate::steam::SteamProton;
+use crate::utils::is_populated_dir;
+
+/// Whether Wine Gecko and Wine Mono are installed in a prefix, as
+/// `(gecko, mono)`.
+pub fn has_gecko_mono(prefix_path: &Path) -> (bool, bool) {
+    let windows = prefix_path.join("drive_c/windows");
+    let gecko = is_populated_dir(&windows.join("system32/gecko"))
+        || is_populated_dir(&windows.join("syswow64/gecko"));
+    let mono = is_populated_dir(&windows.join("mono"));
+    (gecko, mono)
+}
+
+/// Install whichever of Wine Gecko / Wine Mono the prefix lacks from the
+/// MSI packages shipped with `proton`.
+///
+/// Builds that ship the packages unpacked instead of as MSIs are used by
+/// Wine directly from the Proton directory, so nothing needs installing.
+pub fn install_gecko_mono(
+    prefix_path: &Path,
+    proton: &SteamProton,
+    ctx: &TaskContext,
+) -> Result<(), Box<dyn Error>> {
+    let (has_gecko, has_mono) = has_gecko_mono(prefix_path);
+    let mut packages: Vec<PathBuf> = Vec::new();
+    if !has_gecko {
+        packages.extend(shipped_msis(proton, "gecko"));
+    }
+    if !has_mono {
+        packages.extend(shipped_msis(proton, "mono"));
+    }
+
+    if packages.is_empty() {
+        if !has_gecko || !has_mono {
+            log_warning(&format!(
+                "{} ships no Gecko/Mono installers; Wine will use its shared copies if present",
+                proton.name
+            ));
+        }
+        return Ok(());
+    }
+
+    let wine_bin = proton.wine_binary().ok_or("Wine binary not found")?;
+    let envs: Vec<(&str, String)> = vec![("WINEPREFIX", prefix_path.display().to_string())];
+
+    for msi in packages {
+        let name = msi.file_name().unwrap_or_default().to_string_lossy().to_string();
+        ctx.log(format!("Installing {}...", name));
+        log_install(&format!("Installing {}", name));
+
+        let mut cmd = runtime_wrap::build_command(&wine_bin, &envs);
+        cmd.arg("msiexec").arg("/i").arg(&msi).arg("/q");
+        let status = ctx.run_cancellable(cmd)?;
+        if !status.success() {
+            return Err(format!("{} installer exited with code {:?}", name, status.code()).into());
+        }
+    }
+    Ok(())
+}
+
+/// MSI packages under the Proton build's share/wine/<component> directory
+fn shipped_msis(proton: &SteamProton, component: &str) -> Vec<PathBuf> {
+    let mut msis: Vec<PathBuf> = ["files/share/wine", "dist/share/wine"]
+        .iter()
+        .filter_map(|share| fs::read_dir(proton.path.join(share).join(component)).ok())
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("msi"))
+        .collect();
+    msis.sort();
+    msis
+}
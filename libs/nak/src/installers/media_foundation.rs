@@ -0,0 +1,19 @@
+//! Windows Media Foundation detection
+//!
+//! Wine's builtin Media Foundation only covers what GStreamer can decode;
+//! games whose intro videos need the native codecs hang on a black screen
+//! until winetricks' `mf` (and the WMP runtime) are installed.
+
+use std::path::Path;
+
+use super::vcredist::is_native_dll;
+
+/// DLL the native Media Foundation install places in the prefix
+const MF_DLL: &str = "mfplat.dll";
+
+/// Whether the prefix has native Media Foundation installed.
+pub fn has_media_foundation(prefix_path: &Path) -> bool {
+    let windows = prefix_path.join("drive_c/windows");
+    is_native_dll(&windows.join("system32").join(MF_DLL))
+        || is_native_dll(&windows.join("syswow64").join(MF_DLL))
+}
@@ -0,0 +1,113 @@
+//! Download mirror validation
+//!
+//! Checks a user-supplied mirror before any download starts, so a typo
+//! fails with a clear message instead of an obscure error halfway through
+//! an install. Steam's download region ([`crate::steam::download_region`])
+//! is logged alongside a validated mirror so slow-download reports show
+//! where the user is relative to it.
+
+use std::error::Error;
+use std::time::Duration;
+
+use url::Url;
+
+/// How long the reachability probe may take
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Validate and normalize a mirror URL.
+///
+/// Accepts http, https and file URLs (a bare absolute path is taken as a
+/// file URL). The result always ends in `/` so file names can be joined
+/// onto it. Local mirrors must exist; remote ones must answer a HEAD request.
+pub fn validate_mirror(input: &str) -> Result<Url, Box<dyn Error>> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("Mirror URL is empty".into());
+    }
+
+    let mut url = if input.starts_with('/') {
+        Url::from_directory_path(input)
+            .map_err(|_| format!("Mirror path {} is not a valid directory path", input))?
+    } else {
+        Url::parse(input).map_err(|e| {
+            format!(
+                "Mirror URL \"{}\" is invalid ({}); expected something like https://example.com/nak/",
+                input, e
+            )
+        })?
+    };
+
+    match url.scheme() {
+        "http" | "https" => {
+            if url.host_str().is_none_or(str::is_empty) {
+                return Err(format!("Mirror URL \"{}\" has no host", input).into());
+            }
+        }
+        "file" => {}
+        other => {
+            return Err(format!(
+                "Mirror URL scheme \"{}\" is not supported; use http, https or file",
+                other
+            )
+            .into())
+        }
+    }
+
+    url.set_fragment(None);
+    if !url.path().ends_with('/') {
+        let path = format!("{}/", url.path());
+        url.set_path(&path);
+    }
+
+    check_reachable(&url)?;
+    if let Some(region) = crate::steam::download_region() {
+        crate::logging::log_info(&format!(
+            "Using mirror {} (Steam download region {})",
+            url, region
+        ));
+    }
+    Ok(url)
+}
+
+fn check_reachable(url: &Url) -> Result<(), Box<dyn Error>> {
+    if url.scheme() == "file" {
+        let path = url
+            .to_file_path()
+            .map_err(|_| format!("Mirror {} is not a local path", url))?;
+        if !path.is_dir() {
+            return Err(format!("Mirror directory {} does not exist", path.display()).into());
+        }
+        return Ok(());
+    }
+
+    let agent = ureq::AgentBuilder::new().timeout(PROBE_TIMEOUT).build();
+    match agent.head(url.as_str()).call() {
+        Ok(_) => Ok(()),
+        // Some servers reject HEAD or directory listings but serve files fine
+        Err(ureq::Error::Status(403 | 405, _)) => Ok(()),
+        Err(ureq::Error::Status(code, _)) => {
+            Err(format!("Mirror {} answered HTTP {}; check the URL", url, code).into())
+        }
+        Err(ureq::Error::Transport(e)) => {
+            Err(format!("Mirror {} is not reachable: {}", url, e).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_mirror_local() {
+        let dir = std::env::temp_dir();
+        let url = validate_mirror(&dir.to_string_lossy()).unwrap();
+        assert_eq!(url.scheme(), "file");
+        assert!(url.path().ends_with('/'));
+
+        assert!(validate_mirror("ftp://example.com/nak").is_err());
+        assert!(validate_mirror("example.com/nak").is_err());
+        assert!(validate_mirror("   ").is_err());
+        assert!(validate_mirror("/nonexistent/nak-mirror").is_err());
+    }
+}
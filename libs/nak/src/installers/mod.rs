@@ -4,12 +4,55 @@
 
 pub mod symlinks;
 
+mod audio;
+mod cache;
+mod deps_marker;
+mod dotnet;
+mod download;
+mod dxvk_check;
+mod error;
+mod footprint;
+mod gecko_mono;
+mod media_foundation;
+mod mirror;
+mod mod_manager;
+mod multilib;
+mod pending_renames;
+mod permissions;
+mod prefix_kind;
+mod prefix_processes;
 mod prefix_setup;
+mod shortcuts;
+mod vcredist;
+mod verb_overrides;
+mod vulkan_ext;
 
 pub use prefix_setup::{
     apply_dpi, apply_registry_for_game_path, auto_apply_game_registries, cleanup_prefix_drives,
-    install_all_dependencies, kill_wineserver, known_game_names, launch_dpi_test_app, DPI_PRESETS,
+    flush_prefix_registry, get_prefix_dpi, install_all_dependencies, install_winetricks_verbs, kill_wineserver,
+    known_game_names, launch_dpi_test_app, set_prefix_dpi, DPI_PRESETS,
 };
+pub use audio::{get_audio_driver, set_audio_driver, AUDIO_DRIVERS};
+pub use cache::clean_cache;
+pub use deps_marker::{dependencies_installed, read_deps_marker, DepsMarker};
+pub use dotnet::{dotnet_kind, has_dotnet, has_dotnet_sdk, installed_dotnet, DotnetKind};
+pub use dxvk_check::{fix_dxvk_setup, verify_dxvk_setup, DxvkDllCheck, DxvkMismatch, DxvkSetupStatus};
+pub use error::{error_kind, InstallError, InstallErrorKind};
+pub use footprint::{estimated_setup_footprint, SetupFootprint};
+pub use gecko_mono::{has_gecko_mono, install_gecko_mono};
+pub use media_foundation::has_media_foundation;
+pub use mirror::validate_mirror;
+pub use mod_manager::{detect_mod_manager, ModManagerInfo, ModManagerKind};
+pub use multilib::{check_multilib, MultilibStatus};
+pub use pending_renames::{clear_pending_renames, has_pending_renames};
+pub use permissions::{check_permissions, PermissionIssue, PermissionProblem};
+pub use prefix_kind::{prefix_kind, prefix_tracked_files, PrefixKind};
+pub use prefix_processes::{prefix_in_use, running_mod_manager, RunningModManager};
+pub use shortcuts::{list_start_menu_shortcuts, Shortcut};
+pub use symlinks::ensure_steamuser_symlink;
+pub use vcredist::{installed_vcredist, missing_vcredist_for_game};
+pub use verb_overrides::{fix_verb_overrides, verify_verb_overrides};
+pub use vulkan_ext::{proton_vulkan_ok, vulkan_extensions};
 
 use std::error::Error;
 use std::fs;
@@ -74,7 +117,7 @@ impl TaskContext {
                     if self.is_cancelled() {
                         let _ = child.kill();
                         let _ = child.wait();
-                        return Err("Cancelled".into());
+                        return Err(InstallError::cancelled().into());
                     }
                     std::thread::sleep(std::time::Duration::from_millis(250));
                 }
@@ -250,19 +293,139 @@ pub const WINE_SETTINGS_REG: &str = r#"Windows Registry Editor Version 5.00
 // Shared Functions
 // ============================================================================
 
+/// Whether NaK's download cache is on the same filesystem as a prefix.
+///
+/// When it isn't, moving downloads into the prefix becomes a full copy that
+/// is slow and can hit ENOSPC on the target. Unknown counts as the same.
+pub fn cache_prefix_same_fs(prefix_path: &std::path::Path) -> bool {
+    let cache_dir = crate::config::AppConfig::load().get_cache_dir();
+    crate::utils::same_filesystem(&cache_dir, prefix_path).unwrap_or(true)
+}
+
+/// Set (Some) or remove (None) values in one key of a prefix's `user.reg`
+/// or `system.reg`, editing the file directly.
+///
+/// Refuses while anything runs in the prefix, since its wineserver would
+/// overwrite the edit when it exits. The previous file is kept as
+/// `<file>.nak-bak`.
+pub(crate) fn edit_reg_file(
+    prefix_path: &std::path::Path,
+    file: &str,
+    key_header: &str,
+    changes: &[(&str, Option<String>)],
+) -> Result<(), Box<dyn Error>> {
+    if prefix_in_use(prefix_path) {
+        return Err(format!("Close everything running in this prefix before editing its {}", file).into());
+    }
+
+    let reg = prefix_path.join(file);
+    let content = fs::read_to_string(&reg)?;
+    fs::copy(&reg, reg.with_extension("reg.nak-bak"))?;
+
+    let updated = crate::game_finder::set_reg_values(&content, key_header, changes);
+    let tmp = reg.with_extension("reg.nak-tmp");
+    fs::write(&tmp, updated)?;
+    fs::rename(&tmp, &reg)?;
+    Ok(())
+}
+
+/// Verbs recorded in a prefix's winetricks.log, or None if it has none
+pub(crate) fn winetricks_log(prefix_path: &std::path::Path) -> Option<Vec<String>> {
+    let content = fs::read_to_string(prefix_path.join("winetricks.log")).ok()?;
+    Some(
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect(),
+    )
+}
+
+/// Pick a writable temp directory with at least `required_bytes` free.
+///
+/// Tries NaK's cache tmp dir, then a directory on the prefix's own
+/// filesystem (`drive_c/windows/temp/nak`, only once Wine has initialized
+/// the prefix), then `$TMPDIR` (or `/tmp`). Free space that can't be
+/// determined counts as enough.
+pub fn select_temp_dir(
+    prefix_path: &std::path::Path,
+    required_bytes: u64,
+) -> Result<std::path::PathBuf, Box<dyn Error>> {
+    let system_tmp = std::env::var_os("TMPDIR")
+        .filter(|t| !t.is_empty())
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| "/tmp".into());
+    let mut candidates = vec![crate::config::AppConfig::get_tmp_path()];
+    let windows_dir = prefix_path.join("drive_c/windows");
+    if windows_dir.is_dir() {
+        candidates.push(windows_dir.join("temp/nak"));
+    }
+    candidates.push(system_tmp.join("nak"));
+
+    let mut problems = Vec::new();
+    for (i, dir) in candidates.iter().enumerate() {
+        if let Err(e) = fs::create_dir_all(dir) {
+            problems.push(format!("{}: {}", dir.display(), e));
+            continue;
+        }
+        let probe = dir.join(format!(".nak-write-test-{}", std::process::id()));
+        if let Err(e) = fs::write(&probe, b"") {
+            problems.push(format!("{}: not writable ({})", dir.display(), e));
+            continue;
+        }
+        let _ = fs::remove_file(&probe);
+
+        if let Some(free) = crate::utils::free_space(dir) {
+            if free < required_bytes {
+                problems.push(format!(
+                    "{}: only {} MB free, {} MB needed",
+                    dir.display(),
+                    free / 1024 / 1024,
+                    required_bytes / 1024 / 1024
+                ));
+                continue;
+            }
+        }
+
+        if i > 0 {
+            crate::logging::log_warning(&format!(
+                "Using {} for temporary files ({})",
+                dir.display(),
+                problems.join("; ")
+            ));
+        }
+        return Ok(dir.clone());
+    }
+
+    Err(InstallError::new(
+        InstallErrorKind::Io,
+        format!("No usable temp directory: {}", problems.join("; ")),
+    )
+    .into())
+}
+
+/// Temp directory for an install: per game when the app id is known, so
+/// concurrent installs don't overwrite each other's .reg files
+pub(crate) fn tmp_dir_for(app_id: Option<u32>) -> std::path::PathBuf {
+    match app_id {
+        Some(app_id) => crate::config::AppConfig::get_tmp_path_for_app(app_id),
+        None => crate::config::AppConfig::get_tmp_path(),
+    }
+}
+
 /// Apply Wine registry settings to a prefix
 pub fn apply_wine_registry_settings(
     prefix_path: &std::path::Path,
     proton: &SteamProton,
     log_callback: &impl Fn(String),
-    _app_id: Option<u32>,
+    app_id: Option<u32>,
 ) -> Result<(), Box<dyn Error>> {
     use std::io::Write;
-    use crate::config::AppConfig;
     use crate::logging::{log_error, log_warning};
     use crate::runtime_wrap;
 
-    let tmp_dir = AppConfig::get_tmp_path();
+    let tmp_dir = tmp_dir_for(app_id);
     fs::create_dir_all(&tmp_dir)?;
     let reg_file = tmp_dir.join("wine_settings.reg");
 
@@ -275,7 +438,7 @@ pub fn apply_wine_registry_settings(
             proton.name
         );
         log_callback(format!("Error: {}", err_msg));
-        err_msg
+        InstallError::new(InstallErrorKind::ProtonNotFound, err_msg)
     })?;
 
     let wineserver_bin = proton.wineserver_binary().unwrap_or_else(|| {
@@ -313,6 +476,9 @@ pub fn apply_wine_registry_settings(
     match regedit_status {
         Ok(status) => {
             if status.success() {
+                if let Err(e) = prefix_setup::flush_prefix_registry(prefix_path, proton) {
+                    log_warning(&e.to_string());
+                }
                 log_callback("Registry settings applied successfully".to_string());
                 log_install("Wine registry settings applied successfully");
             } else {
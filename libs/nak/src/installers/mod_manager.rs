@@ -0,0 +1,167 @@
+//! Mod manager detection inside a prefix
+//!
+//! Looks for an existing MO2 or Vortex install so the UI can offer to open it
+//! instead of installing a fresh copy.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Which mod manager was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModManagerKind {
+    ModOrganizer2,
+    Vortex,
+}
+
+impl ModManagerKind {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ModManagerKind::ModOrganizer2 => "Mod Organizer 2",
+            ModManagerKind::Vortex => "Vortex",
+        }
+    }
+
+    fn exe_name(&self) -> &'static str {
+        match self {
+            ModManagerKind::ModOrganizer2 => "ModOrganizer.exe",
+            ModManagerKind::Vortex => "Vortex.exe",
+        }
+    }
+}
+
+/// A mod manager installation found inside a prefix
+#[derive(Debug, Clone)]
+pub struct ModManagerInfo {
+    pub kind: ModManagerKind,
+    pub exe_path: PathBuf,
+    pub install_dir: PathBuf,
+    /// File version from the exe's version resource (e.g. "2.5.2.0")
+    pub version: Option<String>,
+}
+
+/// Common install locations, relative to drive_c
+const KNOWN_LOCATIONS: &[(&str, ModManagerKind)] = &[
+    ("Modding/MO2", ModManagerKind::ModOrganizer2),
+    ("MO2", ModManagerKind::ModOrganizer2),
+    ("Program Files/Mod Organizer 2", ModManagerKind::ModOrganizer2),
+    ("Program Files/ModOrganizer2", ModManagerKind::ModOrganizer2),
+    ("Modding/Vortex", ModManagerKind::Vortex),
+    ("Program Files/Black Tree Gaming Ltd/Vortex", ModManagerKind::Vortex),
+];
+
+/// How deep to search drive_c when no known location matches
+const SEARCH_DEPTH: usize = 4;
+
+/// Find an installed mod manager (MO2 preferred over Vortex) in a prefix.
+pub fn detect_mod_manager(prefix_path: &Path) -> Option<ModManagerInfo> {
+    let drive_c = prefix_path.join("drive_c");
+
+    for (relative, kind) in KNOWN_LOCATIONS {
+        let exe = drive_c.join(relative).join(kind.exe_name());
+        if exe.is_file() {
+            return Some(build_info(*kind, exe));
+        }
+    }
+
+    // Fall back to a shallow search, skipping the Windows system tree
+    let mut vortex = None;
+    let walker = walkdir::WalkDir::new(&drive_c)
+        .max_depth(SEARCH_DEPTH)
+        .into_iter()
+        .filter_entry(|e| e.depth() != 1 || !e.file_name().eq_ignore_ascii_case("windows"));
+
+    for entry in walker.flatten() {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let name = entry.file_name();
+        if name.eq_ignore_ascii_case(ModManagerKind::ModOrganizer2.exe_name()) {
+            return Some(build_info(ModManagerKind::ModOrganizer2, entry.into_path()));
+        }
+        if vortex.is_none() && name.eq_ignore_ascii_case(ModManagerKind::Vortex.exe_name()) {
+            vortex = Some(entry.into_path());
+        }
+    }
+
+    vortex.map(|exe| build_info(ModManagerKind::Vortex, exe))
+}
+
+fn build_info(kind: ModManagerKind, exe_path: PathBuf) -> ModManagerInfo {
+    ModManagerInfo {
+        kind,
+        install_dir: exe_path.parent().map(Path::to_path_buf).unwrap_or_default(),
+        version: read_exe_version(&exe_path),
+        exe_path,
+    }
+}
+
+// ============================================================================
+// PE Version Resource
+// ============================================================================
+
+/// VS_FIXEDFILEINFO signature (0xFEEF04BD, little-endian)
+const FIXED_FILE_INFO_SIGNATURE: [u8; 4] = [0xBD, 0x04, 0xEF, 0xFE];
+
+/// Read the file version from an exe's VS_FIXEDFILEINFO block.
+///
+/// Streams the file so large Electron binaries aren't loaded into memory.
+pub fn read_exe_version(exe_path: &Path) -> Option<String> {
+    let mut file = File::open(exe_path).ok()?;
+    let mut buf = vec![0u8; 1 << 20];
+    // Keep the tail of the previous chunk so a split signature is still found
+    let overlap = 16;
+    let mut carry: Vec<u8> = Vec::new();
+
+    loop {
+        let n = file.read(&mut buf).ok()?;
+        if n == 0 {
+            return None;
+        }
+
+        let mut window = std::mem::take(&mut carry);
+        window.extend_from_slice(&buf[..n]);
+
+        if let Some(version) = find_fixed_file_version(&window) {
+            return Some(version);
+        }
+
+        carry = window[window.len().saturating_sub(overlap)..].to_vec();
+    }
+}
+
+/// Find VS_FIXEDFILEINFO in a byte buffer and format its file version
+fn find_fixed_file_version(data: &[u8]) -> Option<String> {
+    let pos = data
+        .windows(FIXED_FILE_INFO_SIGNATURE.len())
+        .position(|w| w == FIXED_FILE_INFO_SIGNATURE)?;
+
+    // Layout: dwSignature, dwStrucVersion, dwFileVersionMS, dwFileVersionLS
+    let fields = data.get(pos + 8..pos + 16)?;
+    let ms = u32::from_le_bytes([fields[0], fields[1], fields[2], fields[3]]);
+    let ls = u32::from_le_bytes([fields[4], fields[5], fields[6], fields[7]]);
+
+    Some(format!(
+        "{}.{}.{}.{}",
+        ms >> 16,
+        ms & 0xFFFF,
+        ls >> 16,
+        ls & 0xFFFF
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_fixed_file_version() {
+        let mut data = vec![0u8; 32];
+        data.extend_from_slice(&FIXED_FILE_INFO_SIGNATURE);
+        data.extend_from_slice(&0x0001_0000u32.to_le_bytes());
+        data.extend_from_slice(&((2u32 << 16) | 5).to_le_bytes());
+        data.extend_from_slice(&(2u32 << 16).to_le_bytes());
+        assert_eq!(find_fixed_file_version(&data).as_deref(), Some("2.5.2.0"));
+        assert_eq!(find_fixed_file_version(&[0u8; 64]), None);
+    }
+}
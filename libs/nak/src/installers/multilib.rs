@@ -0,0 +1,159 @@
+//! 32-bit (multilib) library detection
+//!
+//! Proton needs 32-bit graphics libraries for its 32-bit Wine half; without
+//! them many games fail in ways that look like prefix problems. This is a
+//! diagnostic only: the ldconfig cache is consulted first (on the host when
+//! running under Flatpak), then the usual 32-bit library directories.
+
+use std::path::Path;
+
+use crate::runtime_wrap;
+
+/// 32-bit Vulkan loader
+const VULKAN_LIB: &str = "libvulkan.so.1";
+/// 32-bit OpenGL libraries (either is enough)
+const GL_LIBS: &[&str] = &["libGL.so.1", "libGLX.so.0"];
+/// Other 32-bit libraries Wine loads for nearly every game
+const OTHER_LIBS: &[&str] = &["libX11.so.6", "libfreetype.so.6"];
+
+/// Directories that hold 32-bit libraries across common distros
+const LIB32_DIRS: &[&str] = &[
+    "/usr/lib32",
+    "/usr/lib/i386-linux-gnu",
+    "/lib/i386-linux-gnu",
+    "/usr/lib/i686-linux-gnu",
+];
+
+/// Result of a multilib check
+#[derive(Debug, Clone, Default)]
+pub struct MultilibStatus {
+    /// Whether detection was possible at all; when false the other fields
+    /// are unreliable and should not be reported as missing
+    pub detected: bool,
+    /// 32-bit Vulkan loader found
+    pub vulkan: bool,
+    /// 32-bit OpenGL found
+    pub gl: bool,
+    /// Key 32-bit libraries that could not be found
+    pub missing: Vec<String>,
+}
+
+impl MultilibStatus {
+    /// True when detection worked and nothing is missing
+    pub fn is_ok(&self) -> bool {
+        self.detected && self.missing.is_empty()
+    }
+}
+
+/// Check for the 32-bit libraries Proton needs.
+pub fn check_multilib() -> MultilibStatus {
+    let Some(source) = Lib32Source::detect() else {
+        return MultilibStatus::default();
+    };
+
+    let vulkan = source.has(VULKAN_LIB);
+    let gl = GL_LIBS.iter().any(|lib| source.has(lib));
+
+    let mut missing = Vec::new();
+    if !vulkan {
+        missing.push(VULKAN_LIB.to_string());
+    }
+    if !gl {
+        missing.push(GL_LIBS[0].to_string());
+    }
+    missing.extend(
+        OTHER_LIBS
+            .iter()
+            .filter(|lib| !source.has(lib))
+            .map(|lib| lib.to_string()),
+    );
+
+    MultilibStatus {
+        detected: true,
+        vulkan,
+        gl,
+        missing,
+    }
+}
+
+/// Where 32-bit library presence is looked up
+enum Lib32Source {
+    /// 32-bit entries from the ldconfig cache
+    Ldconfig(Vec<String>),
+    /// Existing 32-bit library directories
+    Dirs(Vec<&'static Path>),
+}
+
+impl Lib32Source {
+    /// Pick a lookup source, or None if nothing can be probed
+    fn detect() -> Option<Self> {
+        if let Some(cache) = ldconfig_cache() {
+            return Some(Self::Ldconfig(parse_ldconfig_32bit(&cache)));
+        }
+
+        // The sandbox can't see host libraries, so directory probing would lie
+        if runtime_wrap::is_flatpak() {
+            return None;
+        }
+
+        let dirs: Vec<&Path> = LIB32_DIRS.iter().map(Path::new).filter(|d| d.is_dir()).collect();
+        (!dirs.is_empty()).then_some(Self::Dirs(dirs))
+    }
+
+    fn has(&self, lib: &str) -> bool {
+        match self {
+            Self::Ldconfig(libs) => libs.iter().any(|l| l == lib),
+            Self::Dirs(dirs) => dirs.iter().any(|d| d.join(lib).exists()),
+        }
+    }
+}
+
+/// Run `ldconfig -p` and return its output
+fn ldconfig_cache() -> Option<String> {
+    for ldconfig in ["ldconfig", "/sbin/ldconfig", "/usr/sbin/ldconfig"] {
+        let Ok(output) = runtime_wrap::command_for(ldconfig).arg("-p").output() else {
+            continue;
+        };
+        if output.status.success() && !output.stdout.is_empty() {
+            return Some(String::from_utf8_lossy(&output.stdout).into_owned());
+        }
+    }
+    None
+}
+
+/// Extract 32-bit library names from `ldconfig -p` output.
+///
+/// Entries look like `libvulkan.so.1 (libc6,x86-64) => /usr/lib/libvulkan.so.1`;
+/// 32-bit x86 entries carry a bare `(libc6)` tag.
+fn parse_ldconfig_32bit(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, rest) = line.trim().split_once(' ')?;
+            let tag = rest.trim_start().strip_prefix('(')?.split(')').next()?;
+            let is_32bit = tag.split(',').all(|t| {
+                let t = t.trim();
+                t == "libc6" || t.starts_with("OS ABI")
+            });
+            is_32bit.then(|| name.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ldconfig_32bit() {
+        let output = "\
+1234 libs found in cache `/etc/ld.so.cache'
+\tlibvulkan.so.1 (libc6,x86-64) => /usr/lib/libvulkan.so.1
+\tlibvulkan.so.1 (libc6) => /usr/lib32/libvulkan.so.1
+\tlibGL.so.1 (libc6,x86-64) => /usr/lib/libGL.so.1
+\tlibX11.so.6 (libc6, OS ABI: Linux 3.2.0) => /usr/lib32/libX11.so.6
+";
+        let libs = parse_ldconfig_32bit(output);
+        assert_eq!(libs, vec!["libvulkan.so.1", "libX11.so.6"]);
+    }
+}
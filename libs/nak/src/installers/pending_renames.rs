@@ -0,0 +1,97 @@
+//! Leftover PendingFileRenameOperations
+//!
+//! Installers queue files they couldn't replace in
+//! `PendingFileRenameOperations` for wineboot to move on the next start. An
+//! installer killed mid-way can leave entries pointing at temp files that no
+//! longer exist, and some installers then refuse to run until "after a
+//! reboot" that never clears them.
+
+use std::error::Error;
+use std::path::Path;
+
+use super::edit_reg_file;
+use crate::game_finder::read_value_from_reg_file;
+use crate::logging::log_install;
+
+/// system.reg key holding the pending operations
+const SESSION_MANAGER_KEY: &str = r"System\CurrentControlSet\Control\Session Manager";
+const SESSION_MANAGER_HEADER: &str = r"[System\\CurrentControlSet\\Control\\Session Manager]";
+
+/// Values Windows uses for queued renames
+const PENDING_VALUES: &[&str] = &["PendingFileRenameOperations", "PendingFileRenameOperations2"];
+
+/// Whether a prefix has queued file renames left over from an installer.
+pub fn has_pending_renames(prefix_path: &Path) -> bool {
+    let system_reg = prefix_path.join("system.reg");
+    PENDING_VALUES
+        .iter()
+        .any(|value| read_value_from_reg_file(&system_reg, SESSION_MANAGER_KEY, value).is_some())
+}
+
+/// Remove leftover queued renames so installers stop waiting for a reboot.
+///
+/// Edits system.reg directly, keeping the previous file as
+/// `system.reg.nak-bak`; nothing may be running in the prefix. Returns
+/// whether anything was cleared.
+pub fn clear_pending_renames(prefix_path: &Path) -> Result<bool, Box<dyn Error>> {
+    if !has_pending_renames(prefix_path) {
+        return Ok(false);
+    }
+    let changes: Vec<(&str, Option<String>)> = PENDING_VALUES.iter().map(|v| (*v, None)).collect();
+    edit_reg_file(prefix_path, "system.reg", SESSION_MANAGER_HEADER, &changes)?;
+
+    log_install("Cleared leftover PendingFileRenameOperations");
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+    use std::fs;
+
+    const SYSTEM_REG: &str = "WINE REGISTRY Version 2\n\n\
+        [System\\\\CurrentControlSet\\\\Control\\\\Session Manager] 1712345678\n\
+        #time=1da8c3f\n\
+        \"CriticalSectionTimeout\"=dword:00278d00\n\
+        \"PendingFileRenameOperations\"=hex(7):5c,00,3f,00,3f,00,5c,00,43,00,3a,00,5c,00,\\\n  \
+        74,00,6d,00,70,00,00,00,00,00\n\
+        \"ProtectionMode\"=dword:00000001\n\n\
+        [System\\\\CurrentControlSet\\\\Control\\\\Session Manager\\\\Environment] 1712345678\n\
+        \"TEMP\"=\"C:\\\\windows\\\\temp\"\n";
+
+    #[test]
+    fn test_clear_pending_renames() {
+        let tmp = TempDir::new("pending_renames");
+        let prefix = tmp.path();
+        fs::write(prefix.join("system.reg"), SYSTEM_REG).unwrap();
+
+        assert!(has_pending_renames(prefix));
+        assert!(clear_pending_renames(prefix).unwrap());
+        assert!(!has_pending_renames(prefix));
+        assert_eq!(fs::read_to_string(prefix.join("system.reg.nak-bak")).unwrap(), SYSTEM_REG);
+
+        // The continuation line goes with the value; everything else stays
+        let system_reg = fs::read_to_string(prefix.join("system.reg")).unwrap();
+        let pending = "\"PendingFileRenameOperations\"=hex(7):5c,00,3f,00,3f,00,5c,00,43,00,3a,00,5c,00,\\\n  \
+             74,00,6d,00,70,00,00,00,00,00\n";
+        assert_eq!(system_reg, SYSTEM_REG.replace(pending, ""));
+
+        // Nothing left to clear
+        assert!(!clear_pending_renames(prefix).unwrap());
+    }
+
+    #[test]
+    fn test_has_pending_renames_without_value() {
+        let tmp = TempDir::new("no_pending_renames");
+        let prefix = tmp.path();
+        assert!(!has_pending_renames(prefix));
+        let write = |value: &str| {
+            fs::write(prefix.join("system.reg"), SYSTEM_REG.replace("PendingFileRenameOperations", value)).unwrap();
+        };
+        write("Other");
+        assert!(!has_pending_renames(prefix));
+        write("PendingFileRenameOperations2");
+        assert!(has_pending_renames(prefix));
+    }
+}
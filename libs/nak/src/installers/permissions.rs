@@ -0,0 +1,100 @@
+//! Permission checks for prefix and cache directories
+//!
+//! A prefix or cache touched by a `sudo` run ends up owned by root, and later
+//! installs fail deep inside winetricks. These checks catch that up front.
+
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+use crate::config::AppConfig;
+
+/// What is wrong with a path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermissionProblem {
+    NotReadable,
+    NotWritable,
+    OwnedByOtherUser { uid: u32 },
+}
+
+impl PermissionProblem {
+    pub fn description(&self) -> String {
+        match self {
+            PermissionProblem::NotReadable => "not readable by the current user".to_string(),
+            PermissionProblem::NotWritable => "not writable by the current user".to_string(),
+            PermissionProblem::OwnedByOtherUser { uid: 0 } => "owned by root".to_string(),
+            PermissionProblem::OwnedByOtherUser { uid } => format!("owned by another user (uid {})", uid),
+        }
+    }
+}
+
+/// A permission problem found on a specific path
+#[derive(Debug, Clone)]
+pub struct PermissionIssue {
+    pub path: PathBuf,
+    pub problem: PermissionProblem,
+}
+
+/// Check that the current user can read and write the prefix, its compatdata
+/// directory and the NaK cache directory.
+///
+/// Paths that don't exist yet are skipped. Returns an empty list if all is well.
+pub fn check_permissions(prefix_path: &Path) -> Vec<PermissionIssue> {
+    let mut paths = vec![
+        prefix_path.to_path_buf(),
+        prefix_path.join("drive_c"),
+        prefix_path.join("system.reg"),
+        prefix_path.join("user.reg"),
+    ];
+
+    // Steam prefixes: compatdata/<appid>/pfx
+    if prefix_path.file_name().is_some_and(|n| n == "pfx") {
+        if let Some(compatdata) = prefix_path.parent() {
+            paths.push(compatdata.to_path_buf());
+        }
+    }
+
+    let config = AppConfig::load();
+    paths.push(config.get_cache_dir());
+    paths.push(AppConfig::get_tmp_path());
+
+    let uid = unsafe { libc::getuid() };
+    let mut issues = Vec::new();
+
+    for path in paths {
+        let Ok(meta) = std::fs::metadata(&path) else {
+            continue;
+        };
+
+        if meta.uid() != uid {
+            issues.push(PermissionIssue {
+                path: path.clone(),
+                problem: PermissionProblem::OwnedByOtherUser { uid: meta.uid() },
+            });
+        }
+        if !has_access(&path, libc::R_OK) {
+            issues.push(PermissionIssue {
+                path: path.clone(),
+                problem: PermissionProblem::NotReadable,
+            });
+        }
+        if !has_access(&path, libc::W_OK) {
+            issues.push(PermissionIssue {
+                path,
+                problem: PermissionProblem::NotWritable,
+            });
+        }
+    }
+
+    issues
+}
+
+/// Check access for the real user via access(2)
+fn has_access(path: &Path, mode: libc::c_int) -> bool {
+    let Ok(c_path) = CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+    // SAFETY: c_path is a valid NUL-terminated string
+    unsafe { libc::access(c_path.as_ptr(), mode) == 0 }
+}
@@ -0,0 +1,79 @@
+//! Prefix origin detection
+//!
+//! Proton keeps bookkeeping files (`version`, `pfx.lock`, `tracked_files`,
+//! `config_info`) next to `pfx/` in compatdata; Bottles keeps `bottle.yml`
+//! inside the bottle. Anything else with a Wine layout is a plain Wine
+//! prefix (Lutris, winetricks, manual `WINEPREFIX`).
+
+use std::fs;
+use std::path::Path;
+
+/// Proton's files in the compatdata directory above `pfx/`
+const PROTON_MARKERS: &[&str] = &["version", "pfx.lock", "tracked_files", "config_info"];
+
+/// Which tool created a prefix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrefixKind {
+    Proton,
+    Wine,
+    Bottles,
+    /// Missing or not (yet) a Wine prefix
+    Unknown,
+}
+
+impl PrefixKind {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            PrefixKind::Proton => "Proton",
+            PrefixKind::Wine => "Wine",
+            PrefixKind::Bottles => "Bottles",
+            PrefixKind::Unknown => "Unknown",
+        }
+    }
+}
+
+/// Detect which tool created the prefix at `prefix_path`.
+pub fn prefix_kind(prefix_path: &Path) -> PrefixKind {
+    if prefix_path.join("bottle.yml").is_file() {
+        return PrefixKind::Bottles;
+    }
+
+    let proton_managed = prefix_path
+        .parent()
+        .is_some_and(|compat| PROTON_MARKERS.iter().any(|m| compat.join(m).exists()));
+    if proton_managed {
+        return PrefixKind::Proton;
+    }
+
+    if prefix_path.join("system.reg").is_file() || prefix_path.join("drive_c").is_dir() {
+        PrefixKind::Wine
+    } else {
+        PrefixKind::Unknown
+    }
+}
+
+/// Files Proton installed into the prefix and will replace on upgrade, as
+/// listed in compatdata's `tracked_files` (paths relative to `pfx/`).
+///
+/// Accepts either the `pfx/` path or its compatdata directory. Returns an
+/// empty list when the file is absent.
+pub fn prefix_tracked_files(prefix_path: &Path) -> Vec<String> {
+    let candidates = [
+        prefix_path.parent().map(|compat| compat.join("tracked_files")),
+        Some(prefix_path.join("tracked_files")),
+    ];
+    let Some(content) = candidates
+        .into_iter()
+        .flatten()
+        .find_map(|path| fs::read_to_string(path).ok())
+    else {
+        return Vec::new();
+    };
+
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
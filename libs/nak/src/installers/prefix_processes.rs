@@ -0,0 +1,141 @@
+//! Processes running against a prefix
+//!
+//! Scans `/proc` for processes whose WINEPREFIX (or Proton's
+//! STEAM_COMPAT_DATA_PATH) is a given prefix, so changes aren't applied
+//! while Wine has the registry and files open. Inside a Flatpak sandbox only
+//! sandboxed processes are visible.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::mod_manager::{detect_mod_manager, ModManagerKind};
+
+/// Wine's own background processes, which exit along with the wineserver
+const WINE_SERVICES: &[&str] = &[
+    "wineserver",
+    "services.exe",
+    "winedevice.exe",
+    "plugplay.exe",
+    "svchost.exe",
+    "rpcss.exe",
+    "explorer.exe",
+    "tabtip.exe",
+];
+
+/// A mod manager process using a prefix
+#[derive(Debug, Clone)]
+pub struct RunningModManager {
+    pub kind: ModManagerKind,
+    pub pid: u32,
+}
+
+/// Whether any process is running with `prefix_path` as its Wine prefix.
+pub fn prefix_in_use(prefix_path: &Path) -> bool {
+    !prefix_processes(prefix_path).is_empty()
+}
+
+/// Whether any program other than Wine's own background processes is
+/// running in the prefix, i.e. something that would keep the wineserver alive.
+pub(crate) fn programs_running(prefix_path: &Path) -> bool {
+    prefix_processes(prefix_path)
+        .iter()
+        .any(|(_, cmdline)| !is_wine_service(cmdline))
+}
+
+/// Find a mod manager running against `prefix_path`, so the UI can ask the
+/// user to close it specifically rather than report a generic "in use".
+pub fn running_mod_manager(prefix_path: &Path) -> Option<RunningModManager> {
+    // The installed exe may be renamed; match its actual file name too
+    let installed = detect_mod_manager(prefix_path);
+    let installed_exe = installed.as_ref().and_then(|info| {
+        let name = info.exe_path.file_name()?.to_string_lossy().to_lowercase();
+        Some((info.kind, name))
+    });
+
+    prefix_processes(prefix_path).into_iter().find_map(|(pid, cmdline)| {
+        let cmdline = cmdline.to_lowercase();
+        let kind = if cmdline.contains("modorganizer.exe") {
+            ModManagerKind::ModOrganizer2
+        } else if cmdline.contains("vortex.exe") {
+            ModManagerKind::Vortex
+        } else {
+            match &installed_exe {
+                Some((kind, exe)) if cmdline.contains(exe.as_str()) => *kind,
+                _ => return None,
+            }
+        };
+        Some(RunningModManager { kind, pid })
+    })
+}
+
+/// (pid, NUL-separated command line) of every process using the prefix
+fn prefix_processes(prefix_path: &Path) -> Vec<(u32, String)> {
+    let Ok(target) = prefix_path.canonicalize() else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+            // Other users' processes are unreadable and can't hold our prefix
+            let environ = fs::read(entry.path().join("environ")).ok()?;
+            if process_prefix(&environ)?.canonicalize().ok()? != target {
+                return None;
+            }
+            let cmdline = fs::read(entry.path().join("cmdline")).ok()?;
+            Some((pid, String::from_utf8_lossy(&cmdline).into_owned()))
+        })
+        .collect()
+}
+
+/// Whether a command line belongs to the wineserver or a Wine service
+fn is_wine_service(cmdline: &str) -> bool {
+    let program = cmdline.split('\0').next().unwrap_or_default();
+    let name = program.rsplit(['/', '\\']).next().unwrap_or_default().to_lowercase();
+    WINE_SERVICES.contains(&name.as_str())
+}
+
+/// Prefix a process runs in, from its NUL-separated environment
+fn process_prefix(environ: &[u8]) -> Option<PathBuf> {
+    let mut compat_data = None;
+    for var in environ.split(|&b| b == 0) {
+        let var = String::from_utf8_lossy(var);
+        if let Some(prefix) = var.strip_prefix("WINEPREFIX=") {
+            return Some(PathBuf::from(prefix));
+        }
+        if let Some(path) = var.strip_prefix("STEAM_COMPAT_DATA_PATH=") {
+            compat_data = Some(PathBuf::from(path).join("pfx"));
+        }
+    }
+    compat_data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process_prefix() {
+        let environ = b"HOME=/home/u\0STEAM_COMPAT_DATA_PATH=/s/compatdata/489830\0";
+        assert_eq!(
+            process_prefix(environ),
+            Some(PathBuf::from("/s/compatdata/489830/pfx"))
+        );
+        let environ = b"STEAM_COMPAT_DATA_PATH=/x\0WINEPREFIX=/p/pfx\0";
+        assert_eq!(process_prefix(environ), Some(PathBuf::from("/p/pfx")));
+        assert_eq!(process_prefix(b"PATH=/usr/bin\0"), None);
+    }
+
+    #[test]
+    fn test_is_wine_service() {
+        assert!(is_wine_service("/home/u/.steam/Proton 9.0/files/bin/wineserver\0"));
+        assert!(is_wine_service("C:\\windows\\system32\\services.exe\0"));
+        assert!(is_wine_service("C:\\windows\\system32\\Explorer.exe\0/desktop\0"));
+        assert!(!is_wine_service("C:\\Modding\\MO2\\ModOrganizer.exe\0"));
+        assert!(!is_wine_service("/usr/bin/steam-runtime-launcher-service\0--alongside-steam\0"));
+    }
+}
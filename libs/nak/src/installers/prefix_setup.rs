@@ -12,11 +12,22 @@ use std::error::Error;
 use std::fs;
 use std::path::Path;
 use std::process::Child;
+use std::time::{Duration, Instant};
 
-use super::{apply_wine_registry_settings, TaskContext};
+use super::deps_marker::{dependencies_installed, write_deps_marker, DOTNET9_SDK, DOTNET_DESKTOP10};
+use super::download::download_resumable;
+use super::prefix_kind::{prefix_kind, PrefixKind};
+use super::prefix_processes::{programs_running, running_mod_manager};
+use super::{apply_wine_registry_settings, error_kind, InstallError, InstallErrorKind, TaskContext};
 use crate::config::AppConfig;
-use crate::deps::{install_standard_deps_cancellable, STANDARD_VERBS};
-use crate::game_finder::{detect_all_games, known_games, Game, Launcher};
+use crate::deps::{
+    run_winetricks_cancellable, run_winetricks_with_progress, supported_verb, STANDARD_VERBS,
+    SUPPORTED_VERBS,
+};
+use crate::game_finder::{
+    detect_all_games, known_games, prefix_arch, read_value_from_reg_file, AppType,
+    Game, InstallState, Launcher, PrefixArch,
+};
 use crate::logging::{log_install, log_warning};
 use crate::runtime_wrap;
 use crate::steam::{detect_steam_path_checked, SteamProton};
@@ -26,14 +37,17 @@ use crate::steam::{detect_steam_path_checked, SteamProton};
 // =============================================================================
 
 /// .NET 9 SDK download URL
-const DOTNET9_SDK_URL: &str = "https://builds.dotnet.microsoft.com/dotnet/Sdk/9.0.310/dotnet-sdk-9.0.310-win-x64.exe";
+pub(super) const DOTNET9_SDK_URL: &str = "https://builds.dotnet.microsoft.com/dotnet/Sdk/9.0.310/dotnet-sdk-9.0.310-win-x64.exe";
 
 /// .NET Desktop Runtime 10 download URL
-const DOTNET_DESKTOP10_URL: &str = "https://builds.dotnet.microsoft.com/dotnet/WindowsDesktop/10.0.2/windowsdesktop-runtime-10.0.2-win-x64.exe";
+pub(super) const DOTNET_DESKTOP10_URL: &str = "https://builds.dotnet.microsoft.com/dotnet/WindowsDesktop/10.0.2/windowsdesktop-runtime-10.0.2-win-x64.exe";
 
 /// Drive letters to keep in the prefix (c: is Windows root, z: maps to Linux /)
 const ALLOWED_DRIVE_LETTERS: &[&str] = &["c:", "z:"];
 
+/// Free space the install's scratch files (.reg files for regedit) need
+const INSTALL_SCRATCH_BYTES: u64 = 16 * 1024 * 1024;
+
 /// Install all dependencies to a prefix.
 ///
 /// Order: proton init → winetricks → custom dotnet → game detection → registry → win11 → dotnet fixes
@@ -48,10 +62,37 @@ pub fn install_all_dependencies(
     end_progress: f32,
     app_id: u32,
 ) -> Result<(), Box<dyn Error>> {
-    fs::create_dir_all(AppConfig::get_tmp_path())?;
+    // Held until this function returns
+    let _prefix_lock = crate::config::acquire_prefix_lock(prefix_root)?;
+    check_prefix_installable(prefix_root)?;
+
+    // Per-game scratch space, so installs into other prefixes can run alongside
+    let tmp_dir = super::select_temp_dir(prefix_root, INSTALL_SCRATCH_BYTES)?.join(app_id.to_string());
+    fs::create_dir_all(&tmp_dir)?;
+
+    if !super::cache_prefix_same_fs(prefix_root) {
+        let msg = format!(
+            "NaK's cache ({}) is on a different drive than the prefix; installs will copy files across drives and need free space on both",
+            AppConfig::load().get_cache_dir().display()
+        );
+        ctx.log(format!("Warning: {}", msg));
+        log_warning(&msg);
+    }
+
+    // A prefix set up by an earlier run only needs the game-specific steps
+    let already_installed = dependencies_installed(prefix_root);
+    if already_installed {
+        ctx.log("Standard dependencies already installed, skipping them".to_string());
+        log_install("Prefix already has the current dependency set");
+    }
+    // What this run installed, for the marker written at the end, and
+    // whether the whole standard set succeeded
+    let mut installed_verbs: Vec<String> = Vec::new();
+    let mut complete = true;
 
     // Progress distribution
     let init_end = start_progress + (end_progress - start_progress) * 0.10;
+    let standard_end = start_progress + (end_progress - start_progress) * 0.40;
     let winetricks_end = start_progress + (end_progress - start_progress) * 0.50;
     let dotnet_end = start_progress + (end_progress - start_progress) * 0.65;
     let games_end = start_progress + (end_progress - start_progress) * 0.75;
@@ -60,19 +101,27 @@ pub fn install_all_dependencies(
     // 0. Initialize prefix with Proton wrapper (creates proper prefix structure)
     // =========================================================================
     ctx.set_status("Setting up Windows compatibility layer...".to_string());
-    ctx.log("Initializing Wine prefix with Proton...".to_string());
-    log_install("Running proton wineboot to initialize prefix");
 
-    if let Err(e) = initialize_prefix_with_proton(prefix_root, install_proton, app_id, ctx) {
-        ctx.log(format!("Warning: Proton prefix init failed: {}", e));
-        log_warning(&format!("Proton prefix init failed: {}", e));
-        // Continue anyway - winetricks might still work
+    // Proton's wrapper writes its bookkeeping into the prefix's parent, which
+    // for a bottle is the shared Bottles directory
+    if prefix_kind(prefix_root) == PrefixKind::Bottles {
+        ctx.log("Bottles prefix detected, skipping Proton initialization".to_string());
+        log_install("Skipping proton wineboot for Bottles prefix");
+    } else {
+        ctx.log("Initializing Wine prefix with Proton...".to_string());
+        log_install("Running proton wineboot to initialize prefix");
+
+        if let Err(e) = initialize_prefix_with_proton(prefix_root, install_proton, app_id, ctx) {
+            ctx.log(format!("Warning: Proton prefix init failed: {}", e));
+            log_warning(&format!("Proton prefix init failed: {}", e));
+            // Continue anyway - winetricks might still work
+        }
     }
 
     ctx.set_progress(init_end);
 
     if ctx.is_cancelled() {
-        return Err("Cancelled".into());
+        return Err(InstallError::cancelled().into());
     }
 
     // =========================================================================
@@ -82,69 +131,122 @@ pub fn install_all_dependencies(
     ctx.log("Removing unwanted drive letters (keeping C: and Z:)...".to_string());
     log_install("Cleaning up Wine drive letters");
 
-    if let Err(e) = cleanup_wine_drives(prefix_root, install_proton) {
+    if let Err(e) = cleanup_wine_drives(prefix_root, install_proton, &tmp_dir) {
         ctx.log(format!("Warning: Drive cleanup had issues: {}", e));
         log_warning(&format!("Drive cleanup failed: {}", e));
     }
 
     if ctx.is_cancelled() {
-        return Err("Cancelled".into());
+        return Err(InstallError::cancelled().into());
     }
 
     // =========================================================================
     // 1. Standard Dependencies via Winetricks
     // =========================================================================
-    ctx.set_status("Installing required Windows components (this may take several minutes)...".to_string());
-    ctx.log(format!(
-        "Installing {} dependencies via winetricks: {}",
-        STANDARD_VERBS.len(),
-        STANDARD_VERBS.join(", ")
-    ));
-    log_install(&format!("Running winetricks with {} verbs", STANDARD_VERBS.len()));
+    if !already_installed {
+        ctx.set_status("Installing required Windows components (this may take several minutes)...".to_string());
+        ctx.log(format!(
+            "Installing {} dependencies via winetricks: {}",
+            STANDARD_VERBS.len(),
+            STANDARD_VERBS.join(", ")
+        ));
+        log_install(&format!("Running winetricks with {} verbs", STANDARD_VERBS.len()));
 
-    let winetricks_log_cb = {
-        let ctx = ctx.clone();
-        move |msg: String| {
-            ctx.log(msg.clone());
-            ctx.set_status(msg);
+        let winetricks_log_cb = {
+            let ctx = ctx.clone();
+            move |msg: String| {
+                ctx.log(msg.clone());
+                ctx.set_status(msg);
+            }
+        };
+
+        // Follows winetricks through the verbs so the bar moves during this long step
+        let winetricks_progress_cb = |p: f32| ctx.set_progress(init_end + (standard_end - init_end) * p);
+
+        match run_winetricks_with_progress(
+            prefix_root,
+            install_proton,
+            STANDARD_VERBS,
+            winetricks_log_cb,
+            winetricks_progress_cb,
+            &ctx.cancel_flag,
+        ) {
+            Ok(()) => installed_verbs.extend(STANDARD_VERBS.iter().map(|v| v.to_string())),
+            Err(e) => {
+                complete = false;
+                let msg = format!("Winetricks installation had issues: {}", e);
+                ctx.log(format!("Warning: {}", msg));
+                log_warning(&msg);
+            }
         }
-    };
+    }
+    ctx.set_progress(standard_end);
 
-    if let Err(e) = install_standard_deps_cancellable(prefix_root, install_proton, winetricks_log_cb, &ctx.cancel_flag) {
-        let msg = format!("Winetricks installation had issues: {}", e);
-        ctx.log(format!("Warning: {}", msg));
-        log_warning(&msg);
+    // Extra VC++ runtimes the target game is known to need
+    let missing_vcredist = super::missing_vcredist_for_game(prefix_root, &app_id.to_string());
+    if !missing_vcredist.is_empty() {
+        ctx.set_status("Installing Visual C++ runtimes for this game...".to_string());
+        ctx.log(format!("Installing game-specific VC++ runtimes: {}", missing_vcredist.join(", ")));
+
+        let vcredist_log_cb = {
+            let ctx = ctx.clone();
+            move |msg: String| ctx.log(msg)
+        };
+        match run_winetricks_cancellable(prefix_root, install_proton, &missing_vcredist, vcredist_log_cb, &ctx.cancel_flag) {
+            Ok(()) => installed_verbs.extend(missing_vcredist.iter().map(|v| v.to_string())),
+            Err(e) => {
+                let msg = format!("VC++ runtime installation had issues: {}", e);
+                ctx.log(format!("Warning: {}", msg));
+                log_warning(&msg);
+            }
+        }
     }
 
     ctx.set_progress(winetricks_end);
 
     if ctx.is_cancelled() {
-        return Err("Cancelled".into());
+        return Err(InstallError::cancelled().into());
     }
 
     // =========================================================================
     // 2. Custom .NET Runtimes (not in winetricks yet)
     // =========================================================================
-    ctx.set_status("Installing .NET runtime (1 of 2)...".to_string());
-    ctx.log("Installing .NET 9 SDK...".to_string());
-
-    if let Err(e) = install_dotnet_runtime(prefix_root, install_proton, DOTNET9_SDK_URL, "dotnet-sdk-9", ctx) {
-        ctx.log(format!("Warning: .NET 9 SDK install failed: {}", e));
-        log_warning(&format!(".NET 9 SDK install failed: {}", e));
-    }
+    if already_installed {
+        ctx.log(".NET runtimes already installed".to_string());
+    } else {
+        ctx.set_status("Installing .NET runtime (1 of 2)...".to_string());
+        ctx.log("Installing .NET 9 SDK...".to_string());
 
-    ctx.set_status("Installing .NET runtime (2 of 2)...".to_string());
-    ctx.log("Installing .NET Desktop Runtime 10...".to_string());
+        match install_dotnet_runtime(prefix_root, install_proton, DOTNET9_SDK_URL, DOTNET9_SDK, app_id, ctx) {
+            Ok(()) => installed_verbs.push(DOTNET9_SDK.to_string()),
+            Err(e) => {
+                complete = false;
+                ctx.log(format!("Warning: .NET 9 SDK install failed: {}", e));
+                log_warning(&format!(".NET 9 SDK install failed: {}", e));
+            }
+        }
 
-    if let Err(e) = install_dotnet_runtime(prefix_root, install_proton, DOTNET_DESKTOP10_URL, "dotnet-desktop-10", ctx) {
-        ctx.log(format!("Warning: .NET Desktop 10 install failed: {}", e));
-        log_warning(&format!(".NET Desktop 10 install failed: {}", e));
+        ctx.set_status("Installing .NET runtime (2 of 2)...".to_string());
+        if super::has_dotnet(prefix_root, DOTNET_DESKTOP10) {
+            ctx.log(".NET Desktop Runtime 10 already installed".to_string());
+        } else {
+            ctx.log("Installing .NET Desktop Runtime 10...".to_string());
+
+            match install_dotnet_runtime(prefix_root, install_proton, DOTNET_DESKTOP10_URL, DOTNET_DESKTOP10, app_id, ctx) {
+                Ok(()) => installed_verbs.push(DOTNET_DESKTOP10.to_string()),
+                Err(e) => {
+                    complete = false;
+                    ctx.log(format!("Warning: .NET Desktop 10 install failed: {}", e));
+                    log_warning(&format!(".NET Desktop 10 install failed: {}", e));
+                }
+            }
+        }
     }
 
     ctx.set_progress(dotnet_end);
 
     if ctx.is_cancelled() {
-        return Err("Cancelled".into());
+        return Err(InstallError::cancelled().into());
     }
 
     // =========================================================================
@@ -163,7 +265,7 @@ pub fn install_all_dependencies(
     ctx.set_progress(games_end);
 
     if ctx.is_cancelled() {
-        return Err("Cancelled".into());
+        return Err(InstallError::cancelled().into());
     }
 
     // =========================================================================
@@ -180,7 +282,7 @@ pub fn install_all_dependencies(
     apply_wine_registry_settings(prefix_root, install_proton, &log_cb, Some(app_id))?;
 
     if ctx.is_cancelled() {
-        return Err("Cancelled".into());
+        return Err(InstallError::cancelled().into());
     }
 
     // =========================================================================
@@ -196,38 +298,121 @@ pub fn install_all_dependencies(
     }
 
     if ctx.is_cancelled() {
-        return Err("Cancelled".into());
+        return Err(InstallError::cancelled().into());
+    }
+
+    if let Err(e) = write_deps_marker(prefix_root, &installed_verbs, complete) {
+        log_warning(&format!("Failed to write dependency marker: {}", e));
     }
 
+    let _ = fs::remove_dir_all(&tmp_dir);
     ctx.set_progress(end_progress);
     ctx.set_status("Dependencies installed".to_string());
     Ok(())
 }
 
+/// Refuse prefixes dependencies can't be installed into right now.
+fn check_prefix_installable(prefix_root: &Path) -> Result<(), InstallError> {
+    // Proton can only run 64-bit (WoW64) prefixes; wineboot would fail halfway
+    if prefix_arch(prefix_root) == Some(PrefixArch::Win32) {
+        return Err(InstallError::new(
+            InstallErrorKind::PrefixInvalid,
+            format!(
+                "{} is a 32-bit Wine prefix. Proton needs a 64-bit prefix, so create a new one instead.",
+                prefix_root.display()
+            ),
+        ));
+    }
+
+    // winetricks and regedit would fight a running MO2 over the registry
+    if let Some(running) = running_mod_manager(prefix_root) {
+        return Err(InstallError::new(
+            InstallErrorKind::PrefixInvalid,
+            format!(
+                "Close {} (pid {}) before installing dependencies into this prefix.",
+                running.kind.display_name(),
+                running.pid
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Install individual winetricks verbs into an existing prefix.
+///
+/// For adding one runtime later without rerunning the full setup. Every verb
+/// must be in [`SUPPORTED_VERBS`]; they are installed one at a time in the
+/// given order, and the first failure stops the run with an error naming it.
+pub fn install_winetricks_verbs(
+    prefix_root: &Path,
+    proton: &SteamProton,
+    verbs: &[&str],
+    ctx: &TaskContext,
+) -> Result<(), Box<dyn Error>> {
+    if let Some(unknown) = verbs.iter().find(|v| supported_verb(v).is_none()) {
+        let supported: Vec<&str> = SUPPORTED_VERBS.iter().map(|v| v.name).collect();
+        return Err(format!(
+            "Unsupported winetricks verb '{}' (supported: {})",
+            unknown,
+            supported.join(", ")
+        )
+        .into());
+    }
+
+    let _prefix_lock = crate::config::acquire_prefix_lock(prefix_root)?;
+    check_prefix_installable(prefix_root)?;
+
+    for (i, verb) in verbs.iter().enumerate() {
+        if ctx.is_cancelled() {
+            return Err(InstallError::cancelled().into());
+        }
+        let step = format!("[{}/{}] Installing {}...", i + 1, verbs.len(), verb);
+        ctx.set_status(step.clone());
+        ctx.log(step);
+        ctx.set_progress(i as f32 / verbs.len() as f32);
+
+        let log_cb = {
+            let ctx = ctx.clone();
+            move |msg: String| ctx.log(msg)
+        };
+        let progress_cb = |p: f32| ctx.set_progress((i as f32 + p) / verbs.len() as f32);
+        if let Err(e) = run_winetricks_with_progress(prefix_root, proton, &[verb], log_cb, progress_cb, &ctx.cancel_flag) {
+            let kind = error_kind(e.as_ref());
+            if kind == InstallErrorKind::Cancelled {
+                return Err(e);
+            }
+            return Err(InstallError::new(kind, format!("Installing {} failed: {}", verb, e)).into());
+        }
+        log_install(&format!("Installed winetricks verb {}", verb));
+    }
+
+    ctx.set_progress(1.0);
+    ctx.set_status("Dependencies installed".to_string());
+    Ok(())
+}
+
 /// Install a .NET runtime via direct exe download and wine execution
 fn install_dotnet_runtime(
     prefix_root: &Path,
     proton: &SteamProton,
     url: &str,
     name: &str,
+    app_id: u32,
     ctx: &TaskContext,
 ) -> Result<(), Box<dyn Error>> {
-    let cache_dir = AppConfig::get_default_cache_dir();
+    let cache_dir = AppConfig::load().get_cache_dir();
     fs::create_dir_all(&cache_dir)?;
 
-    let filename = url.split('/').next_back().unwrap_or("dotnet-installer.exe");
-    let installer_path = cache_dir.join(filename);
+    let installer_path = cache_dir.join(installer_file_name(url));
 
-    // Download if not cached
+    // Download if not cached, picking up a partial download from a cancelled run
     if !installer_path.exists() {
         log_install(&format!("Downloading {}...", name));
-        let response = ureq::get(url)
-            .set("User-Agent", "NaK-Rust")
-            .call()
-            .map_err(|e| format!("Failed to download {}: {}", name, e))?;
-
-        let mut file = fs::File::create(&installer_path)?;
-        std::io::copy(&mut response.into_reader(), &mut file)?;
+        // Partial downloads stay in the game's cache dir until complete, so
+        // two installs fetching the same file can't write into each other's
+        let staging_dir = AppConfig::get_cache_dir_for_app(app_id);
+        download_resumable(url, &installer_path, &staging_dir, None, ctx)
+            .map_err(|e| InstallError::new(error_kind(e.as_ref()), format!("Failed to download {}: {}", name, e)))?;
     }
 
     // Run installer with wine
@@ -257,6 +442,11 @@ fn install_dotnet_runtime(
     Ok(())
 }
 
+/// File name a downloaded installer is cached under
+pub(super) fn installer_file_name(url: &str) -> &str {
+    url.split('/').next_back().unwrap_or("dotnet-installer.exe")
+}
+
 /// Initialize prefix with Proton wrapper
 ///
 /// Runs `proton run wineboot -u` to properly initialize the prefix with all
@@ -344,6 +534,7 @@ fn initialize_prefix_with_proton(
 fn cleanup_wine_drives(
     prefix_root: &Path,
     proton: &SteamProton,
+    tmp_dir: &Path,
 ) -> Result<(), Box<dyn Error>> {
     let dosdevices = prefix_root.join("dosdevices");
 
@@ -391,8 +582,7 @@ fn cleanup_wine_drives(
     };
 
     // Create a .reg file to remove drive type entries
-    let tmp_dir = AppConfig::get_tmp_path();
-    fs::create_dir_all(&tmp_dir)?;
+    fs::create_dir_all(tmp_dir)?;
 
     let mut reg_content = String::from("Windows Registry Editor Version 5.00\n\n");
 
@@ -463,7 +653,7 @@ pub fn cleanup_prefix_drives(
     }
 
     // Run the actual cleanup
-    cleanup_wine_drives(prefix_root, proton)?;
+    cleanup_wine_drives(prefix_root, proton, &AppConfig::get_tmp_path())?;
 
     Ok(removed)
 }
@@ -553,11 +743,43 @@ pub fn apply_dpi(
     if !status.success() {
         return Err(format!("Failed to apply DPI setting: exit code {:?}", status.code()).into());
     }
+    if let Err(e) = flush_prefix_registry(prefix_root, proton) {
+        log_warning(&format!("DPI applied but {}", e));
+    }
 
     log_install(&format!("DPI {} applied successfully", dpi_value));
     Ok(())
 }
 
+/// user.reg key holding LogPixels
+const DESKTOP_KEY: &str = r"[Control Panel\\Desktop]";
+
+/// DPI (LogPixels) configured in a prefix, or None if unset (Wine uses 96).
+pub fn get_prefix_dpi(prefix_root: &Path) -> Option<u32> {
+    read_value_from_reg_file(&prefix_root.join("user.reg"), r"Control Panel\Desktop", "LogPixels")?
+        .parse()
+        .ok()
+}
+
+/// Set the prefix DPI by editing user.reg directly.
+///
+/// Unlike [`apply_dpi`] this needs no Proton. The previous user.reg is kept
+/// as `user.reg.nak-bak`; nothing may be running in the prefix.
+pub fn set_prefix_dpi(prefix_root: &Path, dpi: u32) -> Result<(), Box<dyn Error>> {
+    if !(72..=480).contains(&dpi) {
+        return Err(format!("DPI {} is out of range (72-480)", dpi).into());
+    }
+    super::edit_reg_file(
+        prefix_root,
+        "user.reg",
+        DESKTOP_KEY,
+        &[("LogPixels", Some(format!("dword:{:08x}", dpi)))],
+    )?;
+
+    log_install(&format!("Set prefix DPI to {}", dpi));
+    Ok(())
+}
+
 /// Launch a test application (winecfg, regedit, notepad, control) and return its PID
 pub fn launch_dpi_test_app(
     prefix_root: &Path,
@@ -605,6 +827,50 @@ pub fn kill_wineserver(prefix_root: &Path, proton: &SteamProton) {
         .status();
 }
 
+/// How long [`flush_prefix_registry`] waits for the wineserver to exit
+const REGISTRY_FLUSH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Persist registry changes made through `wine reg`/`regedit` to the .reg files.
+///
+/// The wineserver keeps the registry in memory and only writes user.reg and
+/// system.reg periodically or when it shuts down, so reading the files
+/// straight after a write can show stale data. When only Wine's own
+/// services are left in the prefix, this waits (`wineserver -w`) for the
+/// server to exit, which flushes the registry. If programs are still running
+/// the server would outlive them, so it fails at once instead of waiting.
+pub fn flush_prefix_registry(prefix_root: &Path, proton: &SteamProton) -> Result<(), Box<dyn Error>> {
+    let wineserver_bin = proton
+        .wineserver_binary()
+        .ok_or_else(|| InstallError::new(InstallErrorKind::ProtonNotFound, "Wineserver binary not found"))?;
+    if programs_running(prefix_root) {
+        return Err("Registry not flushed: programs are still running in the prefix".into());
+    }
+
+    let envs: Vec<(&str, String)> = vec![
+        ("WINEPREFIX", prefix_root.display().to_string()),
+    ];
+    let mut child = runtime_wrap::build_command(&wineserver_bin, &envs)
+        .arg("-w")
+        .spawn()?;
+
+    let deadline = Instant::now() + REGISTRY_FLUSH_TIMEOUT;
+    loop {
+        if child.try_wait()?.is_some() {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(format!(
+                "Registry not flushed: the wineserver did not exit within {}s",
+                REGISTRY_FLUSH_TIMEOUT.as_secs()
+            )
+            .into());
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
+
 // ============================================================================
 // Game Registry Detection (uses game_finder module)
 // ============================================================================
@@ -618,7 +884,7 @@ pub fn auto_apply_game_registries(
     prefix_path: &Path,
     proton: &SteamProton,
     log_callback: &impl Fn(String),
-    _app_id: Option<u32>,
+    app_id: Option<u32>,
 ) {
     let Some(wine_bin) = proton.wine_binary() else {
         log_warning("Wine binary not found, skipping game registry auto-detection");
@@ -628,8 +894,14 @@ pub fn auto_apply_game_registries(
     // Use the new game_finder module to detect all games
     let scan_result = detect_all_games();
     let mut applied_count = 0;
+    let tmp_dir = super::tmp_dir_for(app_id);
+    if let Err(e) = fs::create_dir_all(&tmp_dir) {
+        log_warning(&format!("Failed to create {}: {}", tmp_dir.display(), e));
+        return;
+    }
 
-    for game in &scan_result.games {
+    // A game still downloading or updating may not have its files in place
+    for game in scan_result.games.iter().filter(|g| g.is_fully_installed()) {
         // Only process games that have registry info
         let (Some(reg_path), Some(reg_value)) = (&game.registry_path, &game.registry_value) else {
             continue;
@@ -639,6 +911,7 @@ pub fn auto_apply_game_registries(
         if apply_game_registry(
             prefix_path,
             &wine_bin,
+            &tmp_dir,
             game,
             reg_path,
             reg_value,
@@ -649,6 +922,9 @@ pub fn auto_apply_game_registries(
     }
 
     if applied_count > 0 {
+        if let Err(e) = flush_prefix_registry(prefix_path, proton) {
+            log_warning(&e.to_string());
+        }
         log_callback(format!("Auto-configured {} game(s) in registry", applied_count));
         log_install(&format!("Auto-applied registry for {} detected game(s)", applied_count));
     }
@@ -665,16 +941,16 @@ pub fn apply_registry_for_game_path(
     game_name: &str,
     install_path: &Path,
     log_callback: &impl Fn(String),
-) -> Result<(), String> {
+) -> Result<(), Box<dyn Error>> {
     let Some(wine_bin) = proton.wine_binary() else {
-        return Err("Wine binary not found".to_string());
+        return Err(InstallError::new(InstallErrorKind::ProtonNotFound, "Wine binary not found").into());
     };
 
     let known = known_games::find_by_name(game_name);
     let (reg_path, reg_value) = if let Some(kg) = known {
         (kg.registry_path, kg.registry_value)
     } else {
-        return Err(format!("Unknown game: {game_name}"));
+        return Err(InstallError::new(InstallErrorKind::Other, format!("Unknown game: {game_name}")).into());
     };
 
     let fake_game = Game {
@@ -688,12 +964,26 @@ pub fn apply_registry_for_game_path(
         appdata_roaming_folder: known.and_then(|k| k.appdata_roaming_folder.map(String::from)),
         registry_path: Some(reg_path.to_string()),
         registry_value: Some(reg_value.to_string()),
+        prefix_shared: false,
+        prefix_redirected: false,
+        bundled_runtime: None,
+        family_shared: false,
+        install_size: None,
+        app_type: AppType::Game,
+        install_state: InstallState::FullyInstalled,
     };
 
-    if apply_game_registry(prefix_path, &wine_bin, &fake_game, reg_path, reg_value, log_callback) {
+    let tmp_dir = AppConfig::get_tmp_path();
+    if let Err(e) = fs::create_dir_all(&tmp_dir) {
+        return Err(InstallError::new(InstallErrorKind::Io, format!("Failed to create {}: {}", tmp_dir.display(), e)).into());
+    }
+    if apply_game_registry(prefix_path, &wine_bin, &tmp_dir, &fake_game, reg_path, reg_value, log_callback) {
+        if let Err(e) = flush_prefix_registry(prefix_path, proton) {
+            log_warning(&e.to_string());
+        }
         Ok(())
     } else {
-        Err(format!("Failed to apply registry for {game_name}"))
+        Err(InstallError::new(InstallErrorKind::Other, format!("Failed to apply registry for {game_name}")).into())
     }
 }
 
@@ -706,6 +996,7 @@ pub fn known_game_names() -> Vec<&'static str> {
 fn apply_game_registry(
     prefix_path: &Path,
     wine_bin: &Path,
+    tmp_dir: &Path,
     game: &Game,
     reg_path: &str,
     reg_value: &str,
@@ -718,25 +1009,28 @@ fn apply_game_registry(
     let wine_path_reg = format!("Z:{}", linux_path.replace('/', "\\\\"));
 
     // Create .reg file content
-    let reg_content = format!(
+    let mut reg_content = format!(
         r#"Windows Registry Editor Version 5.00
 
 [HKEY_LOCAL_MACHINE\{}]
 "{}"="{}"
-
+"#,
+        reg_path, reg_value, wine_path_reg,
+    );
+    // 32-bit prefixes have no Wow6432Node view
+    if prefix_arch(prefix_path) != Some(PrefixArch::Win32) {
+        reg_content.push_str(&format!(
+            r#"
 [HKEY_LOCAL_MACHINE\SOFTWARE\Wow6432Node\{}]
 "{}"="{}"
 "#,
-        reg_path,
-        reg_value,
-        wine_path_reg,
-        reg_path.strip_prefix("Software\\").unwrap_or(reg_path),
-        reg_value,
-        wine_path_reg,
-    );
+            reg_path.strip_prefix("Software\\").unwrap_or(reg_path),
+            reg_value,
+            wine_path_reg,
+        ));
+    }
 
     // Write temp .reg file
-    let tmp_dir = AppConfig::get_tmp_path();
     let reg_file = tmp_dir.join(format!("game_reg_{}.reg", game.app_id));
 
     if let Err(e) = fs::write(&reg_file, &reg_content) {
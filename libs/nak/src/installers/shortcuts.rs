@@ -0,0 +1,307 @@
+//! Start Menu shortcut discovery
+//!
+//! Parses Windows shell link (.lnk) files from a prefix's Start Menu folders
+//! so companion tools installed alongside a game can be offered for launch.
+//! Malformed links are skipped rather than treated as errors.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::symlinks::find_prefix_username;
+
+/// A Start Menu shortcut found in a Wine prefix
+#[derive(Debug, Clone)]
+pub struct Shortcut {
+    /// Display name (the .lnk file name without extension)
+    pub name: String,
+    /// Path to the .lnk file itself
+    pub lnk_path: PathBuf,
+    /// Windows target path (e.g. `C:\Modding\MO2\ModOrganizer.exe`)
+    pub target_path: Option<String>,
+    /// Target resolved to a Linux path (C: and Z: drives only)
+    pub target_linux_path: Option<PathBuf>,
+    /// Command line arguments
+    pub arguments: Option<String>,
+    /// Working directory
+    pub working_dir: Option<String>,
+}
+
+/// List all shortcuts in the prefix's per-user and ProgramData Start Menus.
+pub fn list_start_menu_shortcuts(prefix_path: &Path) -> Vec<Shortcut> {
+    let drive_c = prefix_path.join("drive_c");
+    let username = find_prefix_username(&drive_c.join("users"));
+
+    let start_menus = [
+        drive_c
+            .join("users")
+            .join(&username)
+            .join("AppData/Roaming/Microsoft/Windows/Start Menu"),
+        drive_c.join("ProgramData/Microsoft/Windows/Start Menu"),
+    ];
+
+    let mut shortcuts = Vec::new();
+
+    for start_menu in &start_menus {
+        for entry in walkdir::WalkDir::new(start_menu).into_iter().flatten() {
+            let path = entry.path();
+            let is_lnk = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| e.eq_ignore_ascii_case("lnk"));
+            if !entry.file_type().is_file() || !is_lnk {
+                continue;
+            }
+
+            let Ok(data) = fs::read(path) else {
+                continue;
+            };
+            let Some(link) = parse_shell_link(&data) else {
+                continue;
+            };
+
+            let target_path = link.target_path();
+            let target_linux_path = target_path
+                .as_deref()
+                .and_then(|t| windows_path_to_prefix_path(prefix_path, t));
+
+            shortcuts.push(Shortcut {
+                name: path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                lnk_path: path.to_path_buf(),
+                target_path,
+                target_linux_path,
+                arguments: link.arguments,
+                working_dir: link.working_dir,
+            });
+        }
+    }
+
+    shortcuts.sort_by_key(|s| s.name.to_lowercase());
+    shortcuts
+}
+
+/// Resolve a Windows path inside a prefix to a Linux path
+fn windows_path_to_prefix_path(prefix_path: &Path, windows_path: &str) -> Option<PathBuf> {
+    let (drive, rest) = windows_path.split_once(':')?;
+    let rest = rest.replace('\\', "/");
+    let rest = rest.trim_start_matches('/');
+
+    match drive.to_ascii_lowercase().as_str() {
+        "c" => Some(prefix_path.join("drive_c").join(rest)),
+        "z" => Some(PathBuf::from("/").join(rest)),
+        _ => None,
+    }
+}
+
+// ============================================================================
+// Shell Link (.lnk) Parsing
+// ============================================================================
+
+const HEADER_SIZE: usize = 0x4C;
+
+/// Shell link CLSID {00021401-0000-0000-C000-000000000046}
+const LINK_CLSID: [u8; 16] = [
+    0x01, 0x14, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46,
+];
+
+const HAS_LINK_TARGET_ID_LIST: u32 = 0x01;
+const HAS_LINK_INFO: u32 = 0x02;
+const HAS_NAME: u32 = 0x04;
+const HAS_RELATIVE_PATH: u32 = 0x08;
+const HAS_WORKING_DIR: u32 = 0x10;
+const HAS_ARGUMENTS: u32 = 0x20;
+const IS_UNICODE: u32 = 0x80;
+
+const VOLUME_ID_AND_LOCAL_BASE_PATH: u32 = 0x01;
+
+/// The fields of a shell link we care about
+#[derive(Debug, Default)]
+struct ShellLink {
+    local_base_path: Option<String>,
+    common_path_suffix: Option<String>,
+    relative_path: Option<String>,
+    working_dir: Option<String>,
+    arguments: Option<String>,
+}
+
+impl ShellLink {
+    /// Best-effort target path: LinkInfo path first, then relative path
+    fn target_path(&self) -> Option<String> {
+        if let Some(base) = &self.local_base_path {
+            let suffix = self.common_path_suffix.as_deref().unwrap_or("");
+            return Some(format!("{}{}", base, suffix));
+        }
+        self.relative_path.clone()
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Read a NUL-terminated ANSI string starting at `offset`
+fn read_ansi_z(data: &[u8], offset: usize) -> Option<String> {
+    let slice = data.get(offset..)?;
+    let end = slice.iter().position(|&b| b == 0)?;
+    Some(slice[..end].iter().map(|&b| b as char).collect())
+}
+
+/// Read a NUL-terminated UTF-16LE string starting at `offset`
+fn read_unicode_z(data: &[u8], offset: usize) -> Option<String> {
+    let slice = data.get(offset..)?;
+    let units: Vec<u16> = slice
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|&u| u != 0)
+        .collect();
+    String::from_utf16(&units).ok()
+}
+
+/// Parse a .lnk file. Returns None if the data is not a valid shell link.
+fn parse_shell_link(data: &[u8]) -> Option<ShellLink> {
+    if read_u32(data, 0)? as usize != HEADER_SIZE || data.get(4..20)? != LINK_CLSID {
+        return None;
+    }
+
+    let flags = read_u32(data, 0x14)?;
+    let mut offset = HEADER_SIZE;
+    let mut link = ShellLink::default();
+
+    if flags & HAS_LINK_TARGET_ID_LIST != 0 {
+        let id_list_size = read_u16(data, offset)? as usize;
+        offset += 2 + id_list_size;
+    }
+
+    if flags & HAS_LINK_INFO != 0 {
+        let info_size = read_u32(data, offset)? as usize;
+        let info = data.get(offset..offset + info_size)?;
+        parse_link_info(info, &mut link);
+        offset += info_size;
+    }
+
+    // StringData entries appear in a fixed order, each gated by its flag
+    let unicode = flags & IS_UNICODE != 0;
+    let mut next_string = |present: bool| -> Option<Option<String>> {
+        if !present {
+            return Some(None);
+        }
+        let count = read_u16(data, offset)? as usize;
+        offset += 2;
+        let value = if unicode {
+            let bytes = data.get(offset..offset + count * 2)?;
+            offset += count * 2;
+            let units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                .collect();
+            String::from_utf16(&units).ok()?
+        } else {
+            let bytes = data.get(offset..offset + count)?;
+            offset += count;
+            bytes.iter().map(|&b| b as char).collect()
+        };
+        Some(Some(value))
+    };
+
+    let _description = next_string(flags & HAS_NAME != 0)?;
+    link.relative_path = next_string(flags & HAS_RELATIVE_PATH != 0)?;
+    link.working_dir = next_string(flags & HAS_WORKING_DIR != 0)?;
+    link.arguments = next_string(flags & HAS_ARGUMENTS != 0)?;
+
+    Some(link)
+}
+
+/// Parse the LinkInfo structure, preferring the Unicode paths when present
+fn parse_link_info(info: &[u8], link: &mut ShellLink) {
+    let (Some(header_size), Some(info_flags)) = (read_u32(info, 4), read_u32(info, 8)) else {
+        return;
+    };
+
+    if info_flags & VOLUME_ID_AND_LOCAL_BASE_PATH != 0 {
+        let unicode_offset = if header_size >= 0x24 { read_u32(info, 0x1C) } else { None };
+        link.local_base_path = unicode_offset
+            .filter(|&o| o != 0)
+            .and_then(|o| read_unicode_z(info, o as usize))
+            .or_else(|| read_u32(info, 0x10).and_then(|o| read_ansi_z(info, o as usize)));
+    }
+
+    let unicode_suffix_offset = if header_size >= 0x24 { read_u32(info, 0x20) } else { None };
+    link.common_path_suffix = unicode_suffix_offset
+        .filter(|&o| o != 0)
+        .and_then(|o| read_unicode_z(info, o as usize))
+        .or_else(|| read_u32(info, 0x18).and_then(|o| read_ansi_z(info, o as usize)))
+        .filter(|s| !s.is_empty());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a minimal ANSI shell link with LinkInfo, working dir and arguments
+    fn build_link(target: &str, working_dir: &str, arguments: &str) -> Vec<u8> {
+        let mut data = vec![0u8; HEADER_SIZE];
+        data[0..4].copy_from_slice(&(HEADER_SIZE as u32).to_le_bytes());
+        data[4..20].copy_from_slice(&LINK_CLSID);
+        let flags = HAS_LINK_INFO | HAS_WORKING_DIR | HAS_ARGUMENTS;
+        data[0x14..0x18].copy_from_slice(&flags.to_le_bytes());
+
+        // LinkInfo: 0x1C byte header, local base path, empty suffix
+        let header_size = 0x1Cu32;
+        let base_offset = header_size;
+        let suffix_offset = base_offset + target.len() as u32 + 1;
+        let info_size = suffix_offset + 1;
+        let mut info = Vec::new();
+        for v in [info_size, header_size, VOLUME_ID_AND_LOCAL_BASE_PATH, 0, base_offset, 0, suffix_offset] {
+            info.extend_from_slice(&v.to_le_bytes());
+        }
+        info.extend_from_slice(target.as_bytes());
+        info.push(0);
+        info.push(0);
+        data.extend_from_slice(&info);
+
+        for s in [working_dir, arguments] {
+            data.extend_from_slice(&(s.len() as u16).to_le_bytes());
+            data.extend_from_slice(s.as_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn test_parse_shell_link() {
+        let data = build_link(r"C:\Modding\MO2\ModOrganizer.exe", r"C:\Modding\MO2", "-p Default");
+        let link = parse_shell_link(&data).unwrap();
+        assert_eq!(link.target_path().as_deref(), Some(r"C:\Modding\MO2\ModOrganizer.exe"));
+        assert_eq!(link.working_dir.as_deref(), Some(r"C:\Modding\MO2"));
+        assert_eq!(link.arguments.as_deref(), Some("-p Default"));
+    }
+
+    #[test]
+    fn test_parse_truncated_shell_link() {
+        let data = build_link(r"C:\Tool.exe", r"C:\", "");
+        assert!(parse_shell_link(&data[..data.len() - 4]).is_none());
+        assert!(parse_shell_link(&data[..0x20]).is_none());
+        assert!(parse_shell_link(b"not a link").is_none());
+    }
+
+    #[test]
+    fn test_windows_path_to_prefix_path() {
+        let prefix = Path::new("/prefix/pfx");
+        assert_eq!(
+            windows_path_to_prefix_path(prefix, r"C:\Tools\a.exe"),
+            Some(PathBuf::from("/prefix/pfx/drive_c/Tools/a.exe"))
+        );
+        assert_eq!(
+            windows_path_to_prefix_path(prefix, r"Z:\mnt\a.exe"),
+            Some(PathBuf::from("/mnt/a.exe"))
+        );
+        assert_eq!(windows_path_to_prefix_path(prefix, r"D:\a.exe"), None);
+    }
+}
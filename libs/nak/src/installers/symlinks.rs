@@ -14,8 +14,9 @@
 // Allow unused items - some functions are public API for future use
 #![allow(dead_code)]
 
+use std::error::Error;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use crate::game_finder::{detect_all_games, Game, GameScanResult};
 use crate::logging::{log_info, log_warning};
@@ -24,6 +25,30 @@ use crate::logging::{log_info, log_warning};
 // Public API
 // ============================================================================
 
+/// What applying a planned game symlink would do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymlinkStatus {
+    /// Nothing at the link path yet; the link will be created
+    Create,
+    /// Already a symlink to the right folder
+    Linked,
+    /// A symlink pointing at a folder that no longer exists; left alone
+    Broken,
+    /// A real folder, file or other link is in the way; left alone
+    Occupied,
+}
+
+/// One link [`create_game_symlinks`] would make
+#[derive(Debug, Clone)]
+pub struct PlannedSymlink {
+    /// Path inside the NaK prefix
+    pub link: PathBuf,
+    /// Folder in the game's prefix it points to
+    pub target: PathBuf,
+    pub game_name: String,
+    pub status: SymlinkStatus,
+}
+
 /// Create symlinks from NaK prefix to game prefixes for all detected games
 ///
 /// This creates symlinks in the NaK prefix pointing to the actual save/config
@@ -39,65 +64,22 @@ pub fn create_game_symlinks(nak_prefix: &Path, games: &[Game]) {
     let username = find_prefix_username(&users_dir);
     let user_dir = users_dir.join(&username);
 
-    let documents = user_dir.join("Documents");
-    let my_games = documents.join("My Games");
-    let appdata_local = user_dir.join("AppData/Local");
-    let appdata_roaming = user_dir.join("AppData/Roaming");
-
     // Ensure base directories exist (real folders in NaK prefix)
-    let _ = fs::create_dir_all(&my_games);
-    let _ = fs::create_dir_all(&appdata_local);
-    let _ = fs::create_dir_all(&appdata_roaming);
+    let _ = fs::create_dir_all(user_dir.join("Documents/My Games"));
+    let _ = fs::create_dir_all(user_dir.join("AppData/Local"));
+    let _ = fs::create_dir_all(user_dir.join("AppData/Roaming"));
 
     let mut linked_count = 0;
-
-    for game in games {
-        // Skip games without prefixes
-        let Some(game_prefix) = &game.prefix_path else {
-            continue;
-        };
-
-        // Discover the game prefix's user directory
-        let game_users_dir = game_prefix.join("drive_c/users");
-        let game_username = find_prefix_username(&game_users_dir);
-        let game_user_dir = game_users_dir.join(&game_username);
-
-        // Scan and symlink ALL folders in the game prefix's Documents/My Games/
-        linked_count += scan_and_link_all(
-            &my_games,
-            &game_user_dir.join("Documents/My Games"),
-            "Documents/My Games",
-            &game.name,
-            game_prefix,
-        );
-
-        // Also link the Documents folder itself for non-My Games entries
-        // (some games put saves directly in Documents/<GameName>)
-        linked_count += scan_and_link_all(
-            &documents,
-            &game_user_dir.join("Documents"),
-            "Documents",
-            &game.name,
-            game_prefix,
-        );
-
-        // Scan and symlink ALL folders in AppData/Local/
-        linked_count += scan_and_link_all(
-            &appdata_local,
-            &game_user_dir.join("AppData/Local"),
-            "AppData/Local",
-            &game.name,
-            game_prefix,
-        );
-
-        // Scan and symlink ALL folders in AppData/Roaming/
-        linked_count += scan_and_link_all(
-            &appdata_roaming,
-            &game_user_dir.join("AppData/Roaming"),
-            "AppData/Roaming",
-            &game.name,
-            game_prefix,
-        );
+    for planned in plan_symlinks_for(nak_prefix, games) {
+        match planned.status {
+            SymlinkStatus::Create => {
+                if create_planned_symlink(&planned) {
+                    linked_count += 1;
+                }
+            }
+            SymlinkStatus::Linked => linked_count += 1,
+            SymlinkStatus::Broken | SymlinkStatus::Occupied => {}
+        }
     }
 
     if linked_count > 0 {
@@ -116,6 +98,15 @@ pub fn create_game_symlinks(nak_prefix: &Path, games: &[Game]) {
     }
 }
 
+/// List the game symlinks [`create_game_symlinks_auto`] would create,
+/// without touching the filesystem.
+///
+/// Links already in place, dangling links and paths taken by something else
+/// are included with their [`SymlinkStatus`], so a preview can show them.
+pub fn plan_game_symlinks(nak_prefix: &Path) -> Vec<PlannedSymlink> {
+    plan_symlinks_for(nak_prefix, &detect_all_games().games)
+}
+
 /// Create NaK Tools convenience symlinks pointing INTO the prefix
 ///
 /// Creates symlinks in NaK Tools folder for easy access:
@@ -186,6 +177,43 @@ pub fn ensure_temp_directory(prefix_path: &Path) {
     }
 }
 
+/// Ensure both the per-user Temp and the Windows Temp directories exist
+///
+/// Some tools (xEdit among them) write to `C:\windows\temp` rather than
+/// the user's Temp. Returns the directories that had to be created; nothing
+/// is created in a prefix Wine hasn't initialized yet.
+pub fn ensure_all_temp_dirs(prefix_path: &Path) -> Vec<PathBuf> {
+    let windows_dir = prefix_path.join("drive_c/windows");
+    if !windows_dir.is_dir() {
+        log_warning(&format!("Not a Wine prefix, skipping Temp directories: {}", prefix_path.display()));
+        return Vec::new();
+    }
+    let users_dir = prefix_path.join("drive_c/users");
+    let username = find_prefix_username(&users_dir);
+
+    // Wine creates the Windows one lowercase; accept either spelling
+    let windows_temp = ["temp", "Temp"]
+        .iter()
+        .map(|name| windows_dir.join(name))
+        .find(|dir| dir.is_dir())
+        .unwrap_or_else(|| windows_dir.join("temp"));
+
+    let mut created = Vec::new();
+    for temp_dir in [users_dir.join(&username).join("AppData/Local/Temp"), windows_temp] {
+        if temp_dir.is_dir() {
+            continue;
+        }
+        match fs::create_dir_all(&temp_dir) {
+            Ok(()) => {
+                log_info(&format!("Created missing Temp directory: {}", temp_dir.display()));
+                created.push(temp_dir);
+            }
+            Err(e) => log_warning(&format!("Failed to create {}: {}", temp_dir.display(), e)),
+        }
+    }
+    created
+}
+
 // ============================================================================
 // Internal Functions
 // ============================================================================
@@ -202,26 +230,47 @@ const SKIP_DIRS: &[&str] = &[
     "Contacts", "3D Objects",
 ];
 
-/// Scan all subdirectories in a game prefix folder and create symlinks
-/// for each one in the corresponding NaK prefix folder.
+/// Plan links for every game with a prefix, in creation order.
 ///
-/// Returns the number of symlinks created.
-fn scan_and_link_all(
+/// A link path claimed by an earlier game counts as occupied for later ones,
+/// as it would be once the earlier link exists.
+fn plan_symlinks_for(nak_prefix: &Path, games: &[Game]) -> Vec<PlannedSymlink> {
+    let users_dir = nak_prefix.join("drive_c/users");
+    let user_dir = users_dir.join(find_prefix_username(&users_dir));
+
+    let mut plan = Vec::new();
+    // Half-installed games may not have their prefix folders yet
+    for game in games.iter().filter(|g| g.is_fully_installed()) {
+        // Skip games without prefixes
+        let Some(game_prefix) = &game.prefix_path else {
+            continue;
+        };
+
+        // Discover the game prefix's user directory
+        let game_users_dir = game_prefix.join("drive_c/users");
+        let game_user_dir = game_users_dir.join(find_prefix_username(&game_users_dir));
+
+        // Every folder in My Games, loose Documents folders (some games put
+        // saves directly in Documents/<GameName>), and both AppData roots
+        for rel in ["Documents/My Games", "Documents", "AppData/Local", "AppData/Roaming"] {
+            plan_folder_links(&mut plan, &user_dir.join(rel), &game_user_dir.join(rel), rel, &game.name);
+        }
+    }
+    plan
+}
+
+/// Plan a link in `nak_base` for each subdirectory of `game_base`
+fn plan_folder_links(
+    plan: &mut Vec<PlannedSymlink>,
     nak_base: &Path,
     game_base: &Path,
     label: &str,
     game_name: &str,
-    _game_prefix: &Path,
-) -> usize {
-    if !game_base.is_dir() {
-        return 0;
-    }
-
+) {
     let Ok(entries) = fs::read_dir(game_base) else {
-        return 0;
+        return;
     };
 
-    let mut count = 0;
     for entry in entries.flatten() {
         // Only symlink directories (game folders), not loose files
         if !entry.path().is_dir() {
@@ -240,60 +289,59 @@ fn scan_and_link_all(
             continue;
         }
 
-        let nak_path = nak_base.join(&folder_name);
-        let source_path = entry.path();
-
-        if create_symlink_if_needed(&nak_path, &source_path, game_name, label, &folder_name) {
-            count += 1;
-        }
+        let link = nak_base.join(&folder_name);
+        let target = entry.path();
+        let status = if plan.iter().any(|p| p.link == link) {
+            SymlinkStatus::Occupied
+        } else {
+            link_status(&link, &target)
+        };
+        plan.push(PlannedSymlink {
+            link,
+            target,
+            game_name: game_name.to_string(),
+            status,
+        });
     }
-
-    count
 }
 
-/// Create a symlink if the target doesn't already exist or is already correct.
-///
-/// Returns true if a symlink was created (or already existed correctly).
-fn create_symlink_if_needed(
-    nak_path: &Path,
-    source_path: &Path,
-    game_name: &str,
-    label: &str,
-    folder_name: &str,
-) -> bool {
-    // Check if target already exists
-    if nak_path.exists() || fs::symlink_metadata(nak_path).is_ok() {
-        // Check if it's already a symlink to the correct location
-        if let Ok(target) = fs::read_link(nak_path) {
-            if target == source_path {
-                return true; // Already correctly linked
-            }
-        }
-        // Something else exists here, don't overwrite
-        return false;
+/// What is currently at `link` compared to the wanted `target`
+fn link_status(link: &Path, target: &Path) -> SymlinkStatus {
+    let Ok(meta) = fs::symlink_metadata(link) else {
+        return SymlinkStatus::Create;
+    };
+    if !meta.file_type().is_symlink() {
+        return SymlinkStatus::Occupied;
+    }
+    match fs::read_link(link) {
+        Ok(current) if current == target => SymlinkStatus::Linked,
+        _ if !link.exists() => SymlinkStatus::Broken,
+        _ => SymlinkStatus::Occupied,
     }
+}
 
+/// Create a planned link. Returns true on success.
+fn create_planned_symlink(planned: &PlannedSymlink) -> bool {
     // Ensure parent directory exists
-    if let Some(parent) = nak_path.parent() {
+    if let Some(parent) = planned.link.parent() {
         let _ = fs::create_dir_all(parent);
     }
 
-    // Create the symlink
-    match std::os::unix::fs::symlink(source_path, nak_path) {
+    let name = planned.link.file_name().unwrap_or_default().to_string_lossy();
+    match std::os::unix::fs::symlink(&planned.target, &planned.link) {
         Ok(()) => {
             log_info(&format!(
-                "Linked {}/{} -> {} ({})",
-                label,
-                folder_name,
-                source_path.display(),
-                game_name,
+                "Linked {} -> {} ({})",
+                name,
+                planned.target.display(),
+                planned.game_name,
             ));
             true
         }
         Err(e) => {
             log_warning(&format!(
-                "Failed to create symlink for {} ({}/{}): {}",
-                game_name, label, folder_name, e
+                "Failed to create symlink for {} ({}): {}",
+                planned.game_name, name, e
             ));
             false
         }
@@ -301,7 +349,7 @@ fn create_symlink_if_needed(
 }
 
 /// Find the username from a Wine prefix users directory
-fn find_prefix_username(users_dir: &Path) -> String {
+pub(crate) fn find_prefix_username(users_dir: &Path) -> String {
     if let Ok(entries) = fs::read_dir(users_dir) {
         for entry in entries.flatten() {
             let name = entry.file_name().to_string_lossy().to_string();
@@ -313,6 +361,85 @@ fn find_prefix_username(users_dir: &Path) -> String {
     "steamuser".to_string()
 }
 
+// ============================================================================
+// steamuser Alias
+// ============================================================================
+
+/// Default Proton user directory name
+const STEAMUSER: &str = "steamuser";
+
+/// Make both `steamuser` and the prefix's real user directory resolve.
+///
+/// Migrated prefixes can have a user directory named after the Unix user,
+/// which breaks tools that hardcode `steamuser`. When `steamuser` is missing
+/// (or a dangling link), it is linked to the real user directory; when
+/// `steamuser` is the real directory, `$USER` is linked to it instead.
+/// Only real directories are used as targets and existing directories are
+/// never replaced. Returns the link created, if any.
+pub fn ensure_steamuser_symlink(prefix_path: &Path) -> Result<Option<PathBuf>, Box<dyn Error>> {
+    let users_dir = prefix_path.join("drive_c/users");
+    let steamuser = users_dir.join(STEAMUSER);
+
+    match fs::symlink_metadata(&steamuser) {
+        // Real steamuser directory: alias the Unix username to it
+        Ok(meta) if meta.is_dir() => {
+            let Some(user) = std::env::var("USER")
+                .ok()
+                .filter(|u| !u.is_empty() && u != STEAMUSER && !u.contains('/'))
+            else {
+                return Ok(None);
+            };
+            let alias = users_dir.join(&user);
+            if fs::symlink_metadata(&alias).is_ok() {
+                return Ok(None);
+            }
+            std::os::unix::fs::symlink(STEAMUSER, &alias)?;
+            log_info(&format!("Linked prefix user {} -> {}", user, STEAMUSER));
+            Ok(Some(alias))
+        }
+        Ok(meta) if meta.file_type().is_symlink() => {
+            if steamuser.is_dir() {
+                return Ok(None);
+            }
+            // Dangling alias from an earlier rename; recreate it below
+            fs::remove_file(&steamuser)?;
+            link_steamuser_to_real_user(&users_dir)
+        }
+        Ok(_) => Err(format!("{} exists but is not a directory", steamuser.display()).into()),
+        Err(_) => link_steamuser_to_real_user(&users_dir),
+    }
+}
+
+/// Create `steamuser` -> the single real user directory
+fn link_steamuser_to_real_user(users_dir: &Path) -> Result<Option<PathBuf>, Box<dyn Error>> {
+    let mut real_users: Vec<String> = fs::read_dir(users_dir)?
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| name != "Public" && name != "root" && name != STEAMUSER)
+        .collect();
+
+    // Prefer the Unix user's directory when there are several
+    if real_users.len() > 1 {
+        if let Ok(user) = std::env::var("USER") {
+            real_users.retain(|name| *name == user);
+        }
+    }
+    let [target] = real_users.as_slice() else {
+        return Err(format!(
+            "Cannot pick a user directory to link steamuser to in {}",
+            users_dir.display()
+        )
+        .into());
+    };
+
+    let link = users_dir.join(STEAMUSER);
+    // Relative target so the link survives the prefix being moved
+    std::os::unix::fs::symlink(target, &link)?;
+    log_info(&format!("Linked prefix user {} -> {}", STEAMUSER, target));
+    Ok(Some(link))
+}
+
 // ============================================================================
 // Oblivion Lowercase INI Symlinks
 // ============================================================================
@@ -357,3 +484,87 @@ fn create_lowercase_ini_symlink(dir: &Path, original: &str, lowercase: &str) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+
+    #[test]
+    fn test_ensure_steamuser_symlink() {
+        let tmp = TempDir::new("steamuser");
+        let prefix = tmp.path();
+        let users = prefix.join("drive_c/users");
+        fs::create_dir_all(users.join("alice/Documents")).unwrap();
+        fs::create_dir_all(users.join("Public")).unwrap();
+
+        let created = ensure_steamuser_symlink(prefix).unwrap();
+        assert_eq!(created, Some(users.join("steamuser")));
+        assert!(users.join("steamuser/Documents").is_dir());
+        // Second run is a no-op
+        assert_eq!(ensure_steamuser_symlink(prefix).unwrap(), None);
+    }
+
+    #[test]
+    fn test_ensure_all_temp_dirs() {
+        let tmp = TempDir::new("temp_dirs");
+        let prefix = tmp.path().join("pfx");
+        assert!(ensure_all_temp_dirs(&prefix).is_empty());
+        assert!(!prefix.exists());
+
+        fs::create_dir_all(prefix.join("drive_c/users/steamuser")).unwrap();
+        fs::create_dir_all(prefix.join("drive_c/windows/Temp")).unwrap();
+        assert_eq!(
+            ensure_all_temp_dirs(&prefix),
+            vec![prefix.join("drive_c/users/steamuser/AppData/Local/Temp")]
+        );
+        assert!(!prefix.join("drive_c/windows/temp").exists());
+
+        fs::remove_dir(prefix.join("drive_c/windows/Temp")).unwrap();
+        assert_eq!(ensure_all_temp_dirs(&prefix), vec![prefix.join("drive_c/windows/temp")]);
+        assert!(ensure_all_temp_dirs(&prefix).is_empty());
+    }
+
+    #[test]
+    fn test_plan_symlinks() {
+        let tmp = TempDir::new("symlink_plan");
+        let root = tmp.path();
+        let nak_local = root.join("nak/drive_c/users/steamuser/AppData/Local");
+        let game_local = root.join("game/pfx/drive_c/users/steamuser/AppData/Local");
+        for folder in ["Fresh", "Done", "Gone", "Taken", "Temp"] {
+            fs::create_dir_all(game_local.join(folder)).unwrap();
+        }
+        fs::create_dir_all(nak_local.join("Taken")).unwrap();
+        std::os::unix::fs::symlink(game_local.join("Done"), nak_local.join("Done")).unwrap();
+        std::os::unix::fs::symlink(root.join("missing"), nak_local.join("Gone")).unwrap();
+
+        let game = Game {
+            name: "Test".to_string(),
+            app_id: "1".to_string(),
+            install_path: root.join("game"),
+            prefix_path: Some(root.join("game/pfx")),
+            launcher: crate::game_finder::Launcher::Bottles,
+            my_games_folder: None,
+            appdata_local_folder: None,
+            appdata_roaming_folder: None,
+            registry_path: None,
+            registry_value: None,
+            prefix_shared: false,
+            prefix_redirected: false,
+            bundled_runtime: None,
+            family_shared: false,
+            install_size: None,
+            app_type: crate::game_finder::AppType::Game,
+            install_state: crate::game_finder::InstallState::FullyInstalled,
+        };
+        let plan = plan_symlinks_for(&root.join("nak"), &[game]);
+        let status = |name: &str| plan.iter().find(|p| p.link == nak_local.join(name)).map(|p| p.status);
+        assert_eq!(status("Fresh"), Some(SymlinkStatus::Create));
+        assert_eq!(status("Done"), Some(SymlinkStatus::Linked));
+        assert_eq!(status("Gone"), Some(SymlinkStatus::Broken));
+        assert_eq!(status("Taken"), Some(SymlinkStatus::Occupied));
+        assert_eq!(status("Temp"), None);
+        // Planning never creates anything
+        assert!(fs::symlink_metadata(nak_local.join("Fresh")).is_err());
+    }
+}
@@ -0,0 +1,192 @@
+//! Visual C++ redistributable detection
+//!
+//! Probes a Wine prefix for installed VC++ runtimes. Registry entries written
+//! by the official installers are checked first, then the runtime DLLs in
+//! system32/syswow64 (ignoring Wine's builtin placeholder DLLs).
+
+use std::fs;
+use std::path::Path;
+
+use crate::game_finder::{prefix_arch, read_registry_value, PrefixArch};
+
+/// A VC++ runtime and how to recognise it inside a prefix
+struct VcRedist {
+    /// Winetricks verb that installs this runtime (also the reported name)
+    verb: &'static str,
+    /// Registry key under HKLM written by the official installer
+    registry_key: Option<&'static str>,
+    /// Runtime DLL that only exists once the redistributable is installed
+    dll: &'static str,
+    /// WinSxS assembly folder prefix for side-by-side runtimes
+    winsxs_assembly: Option<&'static str>,
+}
+
+/// Known VC++ redistributables, newest first
+const VC_REDISTS: &[VcRedist] = &[
+    VcRedist {
+        verb: "vcrun2022",
+        registry_key: Some(r"Software\Microsoft\VisualStudio\14.0\VC\Runtimes\x64"),
+        dll: "vcruntime140.dll",
+        winsxs_assembly: None,
+    },
+    VcRedist {
+        verb: "vcrun2013",
+        registry_key: Some(r"Software\Microsoft\VisualStudio\12.0\VC\Runtimes\x64"),
+        dll: "msvcr120.dll",
+        winsxs_assembly: None,
+    },
+    VcRedist {
+        verb: "vcrun2012",
+        registry_key: Some(r"Software\Microsoft\VisualStudio\11.0\VC\Runtimes\x64"),
+        dll: "msvcr110.dll",
+        winsxs_assembly: None,
+    },
+    VcRedist {
+        verb: "vcrun2010",
+        registry_key: Some(r"Software\Microsoft\VisualStudio\10.0\VC\VCRedist\x64"),
+        dll: "msvcr100.dll",
+        winsxs_assembly: None,
+    },
+    VcRedist {
+        verb: "vcrun2008",
+        registry_key: None,
+        dll: "msvcr90.dll",
+        winsxs_assembly: Some("microsoft.vc90.crt"),
+    },
+];
+
+/// Marker Wine embeds in its fake (builtin) DLLs
+const WINE_PLACEHOLDER_MARKERS: &[&[u8]] = &[b"Wine placeholder DLL", b"Wine builtin DLL"];
+
+/// Report which VC++ redistributables are installed in a prefix.
+///
+/// Returns winetricks verb names (e.g. `"vcrun2022"`), newest first.
+pub fn installed_vcredist(prefix_path: &Path) -> Vec<String> {
+    VC_REDISTS
+        .iter()
+        .filter(|redist| is_installed(prefix_path, redist))
+        .map(|redist| redist.verb.to_string())
+        .collect()
+}
+
+/// Return the VC++ verbs a known game needs that the prefix is missing.
+pub fn missing_vcredist_for_game(prefix_path: &Path, app_id: &str) -> Vec<&'static str> {
+    let Some(known) = crate::game_finder::find_by_steam_id(app_id) else {
+        return Vec::new();
+    };
+
+    let installed = installed_vcredist(prefix_path);
+    known
+        .needs_vcredist
+        .iter()
+        .copied()
+        .filter(|verb| !installed.iter().any(|i| i == verb))
+        .collect()
+}
+
+fn is_installed(prefix_path: &Path, redist: &VcRedist) -> bool {
+    if let Some(key) = redist.registry_key {
+        // A 32-bit prefix can only hold the x86 runtime
+        let key = if prefix_arch(prefix_path) == Some(PrefixArch::Win32) {
+            key.replace(r"\x64", r"\x86")
+        } else {
+            key.to_string()
+        };
+        if read_registry_value(prefix_path, &key, "Installed").as_deref() == Some("1") {
+            return true;
+        }
+    }
+
+    let windows = prefix_path.join("drive_c/windows");
+    let candidates = [
+        windows.join("system32").join(redist.dll),
+        windows.join("syswow64").join(redist.dll),
+    ];
+    if candidates.iter().any(|dll| is_native_dll(dll)) {
+        return true;
+    }
+
+    let Some(assembly) = redist.winsxs_assembly else {
+        return false;
+    };
+    let Ok(entries) = fs::read_dir(windows.join("winsxs")) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let name = entry.file_name().to_string_lossy().to_lowercase();
+        name.contains(assembly) && is_native_dll(&entry.path().join(redist.dll))
+    })
+}
+
+/// Check that a DLL exists and is not one of Wine's builtin placeholders.
+pub(crate) fn is_native_dll(path: &Path) -> bool {
+    let Ok(bytes) = fs::read(path) else {
+        return false;
+    };
+
+    !WINE_PLACEHOLDER_MARKERS
+        .iter()
+        .any(|marker| bytes.windows(marker.len()).any(|w| w == *marker))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+
+    const SYSTEM_REG: &str = "WINE REGISTRY Version 2\n#arch=win64\n\n\
+        [Software\\\\Microsoft\\\\VisualStudio\\\\14.0\\\\VC\\\\Runtimes\\\\x64] 1712345678\n\
+        \"Installed\"=dword:00000001\n\"Version\"=\"v14.38.33135.00\"\n\n\
+        [Software\\\\Microsoft\\\\VisualStudio\\\\11.0\\\\VC\\\\Runtimes\\\\x64] 1712345678\n\
+        \"Installed\"=dword:00000000\n";
+
+    /// Prefix with vcrun2022 registered, a native vcrun2013 DLL, a Wine
+    /// placeholder for vcrun2012 and a vcrun2008 WinSxS assembly
+    fn write_prefix(prefix: &Path) {
+        let windows = prefix.join("drive_c/windows");
+        let winsxs = windows.join("winsxs/x86_microsoft.vc90.crt_1fc8b3b9a1e18e3b_9.0.30729.9625_none_508ef7e4bcbbe589");
+        fs::create_dir_all(windows.join("system32")).unwrap();
+        fs::create_dir_all(windows.join("syswow64")).unwrap();
+        fs::create_dir_all(&winsxs).unwrap();
+
+        fs::write(prefix.join("system.reg"), SYSTEM_REG).unwrap();
+        fs::write(windows.join("syswow64/msvcr120.dll"), b"MZ\x90\x00native").unwrap();
+        fs::write(windows.join("system32/msvcr110.dll"), b"MZ\x90\x00Wine placeholder DLL").unwrap();
+        fs::write(windows.join("system32/msvcr100.dll"), b"MZ\x90\x00Wine builtin DLL").unwrap();
+        fs::write(winsxs.join("msvcr90.dll"), b"MZ\x90\x00native").unwrap();
+    }
+
+    #[test]
+    fn test_installed_vcredist() {
+        let tmp = TempDir::new("vcredist");
+        let prefix = tmp.path();
+        assert!(installed_vcredist(prefix).is_empty());
+
+        write_prefix(prefix);
+        assert_eq!(installed_vcredist(prefix), ["vcrun2022", "vcrun2013", "vcrun2008"]);
+
+        // A 32-bit prefix only counts the x86 runtime key
+        fs::write(prefix.join("system.reg"), SYSTEM_REG.replace("#arch=win64", "#arch=win32")).unwrap();
+        assert_eq!(installed_vcredist(prefix), ["vcrun2013", "vcrun2008"]);
+        fs::write(
+            prefix.join("system.reg"),
+            SYSTEM_REG.replace("#arch=win64", "#arch=win32").replace("\\\\x64]", "\\\\x86]"),
+        )
+        .unwrap();
+        assert_eq!(installed_vcredist(prefix), ["vcrun2022", "vcrun2013", "vcrun2008"]);
+    }
+
+    #[test]
+    fn test_missing_vcredist_for_game() {
+        let tmp = TempDir::new("vcredist_game");
+        let prefix = tmp.path();
+        write_prefix(prefix);
+
+        // Enderal Special Edition needs vcrun2022, which is registered
+        assert!(missing_vcredist_for_game(prefix, "976620").is_empty());
+        // Enderal needs vcrun2010, which only has Wine's builtin DLL
+        assert_eq!(missing_vcredist_for_game(prefix, "933480"), ["vcrun2010"]);
+        // Unknown games need nothing
+        assert!(missing_vcredist_for_game(prefix, "12345").is_empty());
+    }
+}
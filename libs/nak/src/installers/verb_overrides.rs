@@ -0,0 +1,170 @@
+//! DllOverrides health for winetricks verbs
+//!
+//! Verbs that drop native DLLs into the prefix (d3dcompiler, d3dx, XAudio,
+//! VC++) also set a DllOverrides entry so Wine loads them instead of its
+//! builtins. When that entry is lost, e.g. to a registry reset or a prefix
+//! copied without user.reg, the verb still shows as installed in
+//! winetricks.log but has no effect.
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use super::{edit_reg_file, winetricks_log};
+use crate::game_finder::read_value_from_reg_content;
+use crate::logging::log_install;
+
+/// Key header for DllOverrides as written in user.reg
+const DLL_OVERRIDES_KEY: &str = r"[Software\\Wine\\DllOverrides]";
+
+/// XAudio2/X3DAudio DLLs shared by the 32- and 64-bit xact verbs
+const XACT_DLLS: &[&str] = &[
+    "xaudio2_0", "xaudio2_1", "xaudio2_2", "xaudio2_3", "xaudio2_4", "xaudio2_5", "xaudio2_6",
+    "xaudio2_7", "x3daudio1_0", "x3daudio1_1", "x3daudio1_2", "x3daudio1_3", "x3daudio1_4",
+    "x3daudio1_5", "x3daudio1_6", "x3daudio1_7",
+];
+
+/// DLLs winetricks overrides to native for each verb
+const VERB_OVERRIDES: &[(&str, &[&str])] = &[
+    ("d3dcompiler_43", &["d3dcompiler_43"]),
+    ("d3dcompiler_47", &["d3dcompiler_47"]),
+    (
+        "d3dx9",
+        &[
+            "d3dx9_24", "d3dx9_25", "d3dx9_26", "d3dx9_27", "d3dx9_28", "d3dx9_29", "d3dx9_30",
+            "d3dx9_31", "d3dx9_32", "d3dx9_33", "d3dx9_34", "d3dx9_35", "d3dx9_36", "d3dx9_37",
+            "d3dx9_38", "d3dx9_39", "d3dx9_40", "d3dx9_41", "d3dx9_42", "d3dx9_43",
+        ],
+    ),
+    ("d3dx11_43", &["d3dx11_42", "d3dx11_43"]),
+    ("xact", XACT_DLLS),
+    ("xact_x64", XACT_DLLS),
+    ("vcrun2022", &["concrt140", "msvcp140", "vcruntime140", "vcruntime140_1"]),
+];
+
+/// Check the DllOverrides of each installed verb in `verbs`.
+///
+/// Returns `(verb, overrides_ok)` for every verb that is recorded in the
+/// prefix's winetricks.log and needs overrides; other verbs are left out.
+/// Without a winetricks.log every verb is treated as installed.
+pub fn verify_verb_overrides(prefix_path: &Path, verbs: &[&str]) -> Vec<(String, bool)> {
+    let user_reg = fs::read_to_string(prefix_path.join("user.reg")).unwrap_or_default();
+    missing_by_verb(prefix_path, verbs, &user_reg)
+        .into_iter()
+        .map(|(verb, missing)| (verb.to_string(), missing.is_empty()))
+        .collect()
+}
+
+/// Restore the missing DllOverrides of the installed verbs in `verbs`.
+///
+/// Missing or builtin-first entries are set to `native,builtin`. Edits
+/// user.reg directly, keeping the previous file as `user.reg.nak-bak`;
+/// nothing may be running in the prefix. Returns the number of overrides set.
+pub fn fix_verb_overrides(prefix_path: &Path, verbs: &[&str]) -> Result<usize, Box<dyn Error>> {
+    let user_reg = fs::read_to_string(prefix_path.join("user.reg")).unwrap_or_default();
+    let mut changes: Vec<(&str, Option<String>)> = Vec::new();
+    for (_, missing) in missing_by_verb(prefix_path, verbs, &user_reg) {
+        for dll in missing {
+            if !changes.iter().any(|(d, _)| *d == dll) {
+                changes.push((dll, Some("\"native,builtin\"".to_string())));
+            }
+        }
+    }
+
+    if changes.is_empty() {
+        return Ok(0);
+    }
+    edit_reg_file(prefix_path, "user.reg", DLL_OVERRIDES_KEY, &changes)?;
+
+    log_install(&format!(
+        "Restored native DLL overrides: {}",
+        changes.iter().map(|(dll, _)| *dll).collect::<Vec<_>>().join(", ")
+    ));
+    Ok(changes.len())
+}
+
+/// Each installed verb of `verbs` that needs overrides, with the DLLs whose
+/// override in `user_reg` (the file's content) doesn't prefer the native copy.
+/// Without a winetricks.log every verb counts as installed.
+fn missing_by_verb<'a>(
+    prefix_path: &Path,
+    verbs: &[&'a str],
+    user_reg: &str,
+) -> Vec<(&'a str, Vec<&'static str>)> {
+    let installed = winetricks_log(prefix_path);
+    verbs
+        .iter()
+        .copied()
+        .filter(|verb| installed.as_ref().is_none_or(|log| log.iter().any(|v| v == verb)))
+        .filter_map(|verb| {
+            let (_, dlls) = VERB_OVERRIDES.iter().find(|(v, _)| *v == verb)?;
+            Some((verb, missing_overrides(user_reg, dlls)))
+        })
+        .collect()
+}
+
+/// DLLs of `dlls` whose override doesn't prefer the native copy
+fn missing_overrides(user_reg: &str, dlls: &[&'static str]) -> Vec<&'static str> {
+    let read = |name: &str| read_value_from_reg_content(user_reg, DLL_OVERRIDES_KEY, name);
+    dlls.iter()
+        .copied()
+        .filter(|dll| {
+            // winetricks writes some entries with a leading '*'
+            let value = read(dll).or_else(|| read(&format!("*{}", dll)));
+            !value.is_some_and(|v| v.trim().to_lowercase().starts_with('n'))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+
+    const USER_REG: &str = "WINE REGISTRY Version 2\n\n[Software\\\\Wine\\\\DllOverrides] 1712345678\n#time=1da8c3f\n\
+        \"*d3dcompiler_47\"=\"native\"\n\"concrt140\"=\"native,builtin\"\n\"msvcp140\"=\"builtin,native\"\n\
+        \"vcruntime140\"=\"native,builtin\"\n\"d3dx11_42\"=\"native\"\n\"d3dx11_43\"=\"native\"\n";
+
+    fn prefix(tmp: &TempDir) -> &Path {
+        let prefix = tmp.path();
+        fs::write(prefix.join("user.reg"), USER_REG).unwrap();
+        fs::write(prefix.join("winetricks.log"), "d3dcompiler_47\nvcrun2022\nd3dx11_43\n").unwrap();
+        prefix
+    }
+
+    #[test]
+    fn test_verify_verb_overrides() {
+        let tmp = TempDir::new("verify_overrides");
+        let prefix = prefix(&tmp);
+        let verbs = ["d3dcompiler_47", "vcrun2022", "d3dx11_43", "d3dx9", "dotnet8"];
+
+        // '*'-prefixed entries count; builtin-first and missing ones don't;
+        // d3dx9 isn't in winetricks.log and dotnet8 needs no overrides
+        let expected = [("d3dcompiler_47", true), ("vcrun2022", false), ("d3dx11_43", true)];
+        let checks = verify_verb_overrides(prefix, &verbs);
+        assert_eq!(checks, expected.map(|(v, ok)| (v.to_string(), ok)));
+
+        fs::remove_file(prefix.join("winetricks.log")).unwrap();
+        let checks = verify_verb_overrides(prefix, &verbs);
+        assert_eq!(checks.len(), 4);
+        assert_eq!(checks[3], ("d3dx9".to_string(), false));
+    }
+
+    #[test]
+    fn test_fix_verb_overrides() {
+        let tmp = TempDir::new("fix_overrides");
+        let prefix = prefix(&tmp);
+        let verbs = ["d3dcompiler_47", "vcrun2022", "d3dx11_43"];
+
+        assert_eq!(fix_verb_overrides(prefix, &verbs).unwrap(), 2);
+        let user_reg = fs::read_to_string(prefix.join("user.reg")).unwrap();
+        assert!(user_reg.contains("\"msvcp140\"=\"native,builtin\"\n"));
+        assert!(user_reg.contains("\"vcruntime140_1\"=\"native,builtin\"\n"));
+        assert!(!user_reg.contains("builtin,native"));
+        assert!(user_reg.contains("\"*d3dcompiler_47\"=\"native\"\n"));
+        assert_eq!(fs::read_to_string(prefix.join("user.reg.nak-bak")).unwrap(), USER_REG);
+
+        assert!(verify_verb_overrides(prefix, &verbs).iter().all(|(_, ok)| *ok));
+        assert_eq!(fix_verb_overrides(prefix, &verbs).unwrap(), 0);
+    }
+}
@@ -0,0 +1,163 @@
+//! Vulkan extension requirements
+//!
+//! DXVK 2.x and VKD3D-Proton refuse to start when the driver lacks certain
+//! device extensions, which shows up as a generic launch failure. This lists
+//! the device extensions `vulkaninfo` reports and compares them with what the
+//! translation layers shipped in a Proton build need.
+
+use crate::runtime_wrap;
+use crate::steam::SteamProton;
+
+/// Device extensions DXVK 2.x requires (each entry lists acceptable alternatives)
+const DXVK_REQUIRED: &[&[&str]] = &[&["VK_EXT_robustness2"], &["VK_EXT_transform_feedback"]];
+
+/// Device extensions VKD3D-Proton requires
+const VKD3D_REQUIRED: &[&[&str]] = &[
+    &["VK_EXT_robustness2"],
+    &["VK_KHR_push_descriptor"],
+    &["VK_EXT_mutable_descriptor_type", "VK_VALVE_mutable_descriptor_type"],
+];
+
+/// Where Proton builds keep the DXVK and VKD3D-Proton DLLs
+const DXVK_DIRS: &[&str] = &["files/lib/wine/dxvk", "files/lib64/wine/dxvk", "dist/lib/wine/dxvk"];
+const VKD3D_DIRS: &[&str] = &[
+    "files/lib/wine/vkd3d-proton",
+    "files/lib64/wine/vkd3d-proton",
+    "dist/lib/wine/vkd3d-proton",
+];
+
+/// Device extensions supported by any Vulkan GPU, sorted.
+///
+/// Returns an empty list if `vulkaninfo` is not installed or fails.
+pub fn vulkan_extensions() -> Vec<String> {
+    let Ok(output) = runtime_wrap::command_for("vulkaninfo").output() else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+    parse_device_extensions(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Check the driver supports the extensions a Proton's DXVK/VKD3D-Proton need.
+///
+/// Returns the missing extensions (alternatives joined with " or "). When the
+/// extensions can't be queried the check passes, since nothing is known.
+pub fn proton_vulkan_ok(proton: &SteamProton) -> Result<(), Vec<String>> {
+    let available = vulkan_extensions();
+    if available.is_empty() {
+        return Ok(());
+    }
+    let missing = missing_extensions(proton, &available);
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(missing)
+    }
+}
+
+fn missing_extensions(proton: &SteamProton, available: &[String]) -> Vec<String> {
+    let has_dxvk = DXVK_DIRS.iter().any(|d| proton.path.join(d).is_dir());
+    let has_vkd3d = VKD3D_DIRS.iter().any(|d| proton.path.join(d).is_dir());
+    // Unknown layout: assume both are bundled, as in every current Proton
+    let (has_dxvk, has_vkd3d) = if has_dxvk || has_vkd3d {
+        (has_dxvk, has_vkd3d)
+    } else {
+        (true, true)
+    };
+
+    let mut required: Vec<&[&str]> = Vec::new();
+    if has_dxvk {
+        required.extend(DXVK_REQUIRED);
+    }
+    if has_vkd3d {
+        required.extend(VKD3D_REQUIRED);
+    }
+
+    let mut missing: Vec<String> = Vec::new();
+    for alternatives in required {
+        if alternatives.iter().any(|ext| available.iter().any(|a| a == ext)) {
+            continue;
+        }
+        let entry = alternatives.join(" or ");
+        if !missing.contains(&entry) {
+            missing.push(entry);
+        }
+    }
+    missing
+}
+
+/// Collect the `Device Extensions` blocks of full `vulkaninfo` output
+fn parse_device_extensions(output: &str) -> Vec<String> {
+    let mut extensions: Vec<String> = Vec::new();
+    let mut in_block = false;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("Device Extensions") {
+            in_block = true;
+            continue;
+        }
+        if !in_block {
+            continue;
+        }
+        if trimmed.is_empty() {
+            in_block = false;
+            continue;
+        }
+        // "VK_KHR_swapchain : extension revision 70"
+        let name = trimmed.split([' ', ':']).next().unwrap_or("");
+        if name.starts_with("VK_") && !extensions.iter().any(|e| e == name) {
+            extensions.push(name.to_string());
+        }
+    }
+
+    extensions.sort();
+    extensions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_device_extensions() {
+        let output = "\
+Instance Extensions: count = 2
+===============================
+\tVK_KHR_surface                         : extension revision 25
+\tVK_KHR_xlib_surface                    : extension revision 6
+
+Device Properties and Extensions:
+=================================
+GPU0:
+Device Extensions: count = 3
+----------------------------
+\tVK_EXT_robustness2                     : extension revision 1
+\tVK_KHR_push_descriptor                 : extension revision 2
+\tVK_KHR_swapchain                       : extension revision 70
+
+VkQueueFamilyProperties:
+";
+        let extensions = parse_device_extensions(output);
+        assert_eq!(
+            extensions,
+            ["VK_EXT_robustness2", "VK_KHR_push_descriptor", "VK_KHR_swapchain"]
+        );
+
+        let proton = SteamProton {
+            name: "GE-Proton10-3".to_string(),
+            config_name: "GE-Proton10-3".to_string(),
+            path: std::path::PathBuf::from("/nonexistent/GE-Proton10-3"),
+            is_steam_proton: false,
+            is_experimental: false,
+        };
+        assert_eq!(
+            missing_extensions(&proton, &extensions),
+            [
+                "VK_EXT_transform_feedback",
+                "VK_EXT_mutable_descriptor_type or VK_VALVE_mutable_descriptor_type"
+            ]
+        );
+    }
+}
@@ -8,6 +8,7 @@ pub mod dxvk;
 pub mod game_finder;
 pub mod logging;
 pub mod paths;
+pub mod report;
 pub mod runtime_wrap;
 pub mod steam;
 pub mod utils;
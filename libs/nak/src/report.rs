@@ -0,0 +1,168 @@
+//! Shareable setup report
+//!
+//! Collects detected games, Protons, platform details and diagnostics into
+//! one JSON document users can paste into a bug report. With redaction on,
+//! the home directory and username are stripped from every path.
+
+use std::fs;
+
+use serde_json::{json, Value};
+
+use crate::game_finder::detect_all_games;
+use crate::installers::check_multilib;
+use crate::runtime_wrap;
+use crate::steam::{diagnose_steam, download_region, find_steam_protons, steam_play_enabled};
+
+/// Build a pretty-printed JSON report of the current setup.
+pub fn generate_report(redact: bool) -> String {
+    let redactor = Redactor::new(redact);
+
+    let scan = detect_all_games();
+    let games: Vec<Value> = scan
+        .games
+        .iter()
+        .map(|g| {
+            json!({
+                "name": g.name,
+                "app_id": g.app_id,
+                "launcher": g.launcher.display_name(),
+                "install_path": redactor.path(&g.install_path.to_string_lossy()),
+                "prefix_path": g.prefix_path.as_ref().map(|p| redactor.path(&p.to_string_lossy())),
+                "prefix_shared": g.prefix_shared,
+                "prefix_redirected": g.prefix_redirected,
+                "family_shared": g.family_shared,
+            })
+        })
+        .collect();
+
+    let protons: Vec<Value> = find_steam_protons()
+        .iter()
+        .map(|p| {
+            json!({
+                "name": p.name,
+                "path": redactor.path(&p.path.to_string_lossy()),
+                "is_steam_proton": p.is_steam_proton,
+            })
+        })
+        .collect();
+
+    let steam = diagnose_steam();
+    let multilib = check_multilib();
+    let steam_play = steam_play_enabled();
+
+    let mut warnings: Vec<String> = scan.warnings.iter().map(|w| redactor.path(w)).collect();
+    if steam.steam_path.is_none() {
+        warnings.push(redactor.path(&steam.guidance));
+    }
+    if steam.steam_path.is_some() && !steam_play.enabled_for_all {
+        warnings.push(
+            "Steam Play is not enabled for all titles (Steam > Settings > Compatibility)".to_string(),
+        );
+    }
+    if multilib.detected && !multilib.missing.is_empty() {
+        warnings.push(format!(
+            "Missing 32-bit libraries: {}",
+            multilib.missing.join(", ")
+        ));
+    }
+
+    let report = json!({
+        "nak_version": env!("CARGO_PKG_VERSION"),
+        "platform": {
+            "os": os_name(),
+            "kernel": fs::read_to_string("/proc/sys/kernel/osrelease")
+                .map(|s| s.trim().to_string())
+                .ok(),
+            "flatpak": runtime_wrap::is_flatpak(),
+        },
+        "steam": {
+            "path": steam.steam_path.as_ref().map(|p| redactor.path(&p.to_string_lossy())),
+            "flatpak_installed": steam.flatpak_steam_installed,
+            "snap_installed": steam.snap_steam_installed,
+            "steam_play_enabled": steam_play.enabled_for_all,
+            "steam_play_default_tool": steam_play.default_tool,
+            "download_region": download_region(),
+        },
+        "games": {
+            "steam": scan.steam_count,
+            "heroic": scan.heroic_count,
+            "bottles": scan.bottles_count,
+            "lutris": scan.lutris_count,
+            "detected": games,
+        },
+        "protons": protons,
+        "warnings": warnings,
+        "redacted": redact,
+    });
+
+    serde_json::to_string_pretty(&report).unwrap_or_default()
+}
+
+/// PRETTY_NAME from os-release
+fn os_name() -> Option<String> {
+    let content = fs::read_to_string("/etc/os-release")
+        .or_else(|_| fs::read_to_string("/usr/lib/os-release"))
+        .ok()?;
+    content.lines().find_map(|line| {
+        line.strip_prefix("PRETTY_NAME=")
+            .map(|v| v.trim_matches('"').to_string())
+    })
+}
+
+/// Strips the home directory and username from strings when enabled
+struct Redactor {
+    home: Option<String>,
+    user: Option<String>,
+}
+
+impl Redactor {
+    fn new(enabled: bool) -> Self {
+        if !enabled {
+            return Self {
+                home: None,
+                user: None,
+            };
+        }
+        let home = std::env::var("HOME").ok().filter(|h| h.len() > 1);
+        let user = std::env::var("USER")
+            .ok()
+            .or_else(|| {
+                home.as_deref()
+                    .and_then(|h| h.rsplit('/').next())
+                    .map(str::to_string)
+            })
+            .filter(|u| !u.is_empty());
+        Self { home, user }
+    }
+
+    fn path(&self, s: &str) -> String {
+        let mut out = s.to_string();
+        if let Some(home) = &self.home {
+            out = out.replace(home.as_str(), "~");
+        }
+        if let Some(user) = &self.user {
+            out = out.replace(&format!("/{}/", user), "/<user>/");
+            if out.ends_with(&format!("/{}", user)) {
+                let cut = out.len() - user.len();
+                out.replace_range(cut.., "<user>");
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redactor() {
+        let r = Redactor {
+            home: Some("/home/alice".to_string()),
+            user: Some("alice".to_string()),
+        };
+        assert_eq!(r.path("/home/alice/.steam/steam"), "~/.steam/steam");
+        assert_eq!(r.path("/run/media/alice/Games"), "/run/media/<user>/Games");
+        assert_eq!(r.path("/mnt/users/alice"), "/mnt/users/<user>");
+    }
+}
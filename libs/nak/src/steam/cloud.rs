@@ -0,0 +1,105 @@
+//! Steam Cloud sync detection
+//!
+//! Reads the active account's localconfig.vdf and per-game remotecache.vdf
+//! to tell whether Steam Cloud will sync a game's saves.
+
+use std::fs;
+use std::path::Path;
+
+use super::find_userdata_path;
+use crate::game_finder::vdf::{parse_vdf, VdfValue};
+
+/// Check whether Steam Cloud sync is enabled for a game.
+///
+/// Returns None when neither the global nor the per-app setting can be found.
+#[must_use]
+pub fn cloud_sync_enabled(app_id: u32) -> Option<bool> {
+    let userdata = find_userdata_path()?;
+    cloud_sync_enabled_in(&userdata, app_id)
+}
+
+fn cloud_sync_enabled_in(userdata: &Path, app_id: u32) -> Option<bool> {
+    let setting = fs::read_to_string(userdata.join("config/localconfig.vdf"))
+        .ok()
+        .and_then(|content| parse_cloud_setting(&content, app_id));
+    if setting.is_some() {
+        return setting;
+    }
+
+    // A remotecache.vdf means Steam has been syncing this game (default on)
+    if userdata.join(app_id.to_string()).join("remotecache.vdf").exists() {
+        return Some(true);
+    }
+
+    None
+}
+
+/// Cloud setting for one app from localconfig.vdf content, if it has one
+fn parse_cloud_setting(localconfig: &str, app_id: u32) -> Option<bool> {
+    let root = parse_vdf(localconfig)?;
+    let steam = steam_section(&root)?;
+
+    // Cloud disabled globally overrides any per-app setting
+    if steam.get_ignore_case("CloudEnabled").and_then(VdfValue::as_str) == Some("0") {
+        return Some(false);
+    }
+
+    steam
+        .get_ignore_case("apps")
+        .and_then(|apps| apps.get_ignore_case(&app_id.to_string()))
+        .and_then(|app| app.get_ignore_case("cloudenabled"))
+        .and_then(VdfValue::as_str)
+        .map(|value| value != "0")
+}
+
+/// Navigate to UserLocalConfigStore/Software/Valve/Steam
+fn steam_section(root: &VdfValue) -> Option<&VdfValue> {
+    root.get_ignore_case("UserLocalConfigStore")?
+        .get_ignore_case("Software")?
+        .get_ignore_case("Valve")?
+        .get_ignore_case("Steam")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cloud_setting() {
+        let content = r#"
+"UserLocalConfigStore"
+{
+    "Software"
+    {
+        "Valve"
+        {
+            "Steam"
+            {
+                "apps"
+                {
+                    "489830"
+                    {
+                        "cloudenabled"      "0"
+                    }
+                    "377160"
+                    {
+                        "cloudenabled"      "1"
+                    }
+                    "22380"
+                    {
+                        "LastPlayed"        "1712345678"
+                    }
+                }
+            }
+        }
+    }
+}
+"#;
+        assert_eq!(parse_cloud_setting(content, 489830), Some(false));
+        assert_eq!(parse_cloud_setting(content, 377160), Some(true));
+        assert_eq!(parse_cloud_setting(content, 22380), None);
+
+        let disabled = content.replace("\"apps\"", "\"CloudEnabled\"  \"0\"\n                \"apps\"");
+        assert_eq!(parse_cloud_setting(&disabled, 377160), Some(false));
+    }
+}
@@ -0,0 +1,91 @@
+//! Steam compatdata storage
+//!
+//! Reports how much space each game's `steamapps/compatdata/<appid>` prefix
+//! takes across all Steam libraries, for storage management alongside
+//! shader caches. Walks are depth-bounded, never follow symlinks and can be
+//! cancelled.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::game_finder::find_library_folders;
+use crate::utils::dir_size_bounded;
+
+/// Deep enough for any real prefix, shallow enough to stop runaway trees
+const MAX_WALK_DEPTH: usize = 64;
+
+/// compatdata size per app ID, summed across libraries, largest first.
+pub fn compatdata_sizes() -> Vec<(u32, u64)> {
+    compatdata_sizes_cancellable(&AtomicBool::new(false)).unwrap_or_default()
+}
+
+/// [`compatdata_sizes`] that stops early when `cancel` is set.
+///
+/// Returns None if cancelled.
+pub fn compatdata_sizes_cancellable(cancel: &AtomicBool) -> Option<Vec<(u32, u64)>> {
+    library_compatdata_sizes(&find_library_folders(), cancel)
+}
+
+fn library_compatdata_sizes(libraries: &[PathBuf], cancel: &AtomicBool) -> Option<Vec<(u32, u64)>> {
+    let mut sizes: Vec<(u32, u64)> = Vec::new();
+
+    for library in libraries {
+        let Ok(entries) = fs::read_dir(library.join("steamapps/compatdata")) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            if cancel.load(Ordering::Relaxed) {
+                return None;
+            }
+            let Ok(app_id) = entry.file_name().to_string_lossy().parse::<u32>() else {
+                continue;
+            };
+            // A symlinked compatdata dir points at another library; count it there
+            if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+                continue;
+            }
+
+            let size = dir_size_bounded(&entry.path(), MAX_WALK_DEPTH, cancel)?;
+            match sizes.iter_mut().find(|(id, _)| *id == app_id) {
+                Some((_, total)) => *total += size,
+                None => sizes.push((app_id, size)),
+            }
+        }
+    }
+
+    sizes.sort_by_key(|&(_, size)| std::cmp::Reverse(size));
+    Some(sizes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+
+    #[test]
+    fn test_library_compatdata_sizes() {
+        let tmp = TempDir::new("compatdata");
+        let libraries = [tmp.path().join("lib1"), tmp.path().join("lib2")];
+        let write = |library: &PathBuf, app: &str, size: usize| {
+            let dir = library.join("steamapps/compatdata").join(app).join("pfx");
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("system.reg"), vec![b'x'; size]).unwrap();
+        };
+        write(&libraries[0], "489830", 100);
+        write(&libraries[1], "489830", 50);
+        write(&libraries[1], "377160", 300);
+        write(&libraries[1], "tmp", 1000);
+        // Counted where it really lives, not through the link
+        std::os::unix::fs::symlink(
+            libraries[1].join("steamapps/compatdata/377160"),
+            libraries[0].join("steamapps/compatdata/377160"),
+        )
+        .unwrap();
+
+        let sizes = library_compatdata_sizes(&libraries, &AtomicBool::new(false)).unwrap();
+        assert_eq!(sizes, [(377160, 300), (489830, 150)]);
+        assert_eq!(library_compatdata_sizes(&libraries, &AtomicBool::new(true)), None);
+    }
+}
@@ -0,0 +1,72 @@
+//! Steam download region
+//!
+//! Steam records the content server cell it downloads from in config.vdf:
+//! `CellIDServerOverride` when the user picked a region under Settings >
+//! Downloads, `CurrentCellID` otherwise. Cell IDs are opaque, but two
+//! machines with the same cell are close to each other, which makes the
+//! value a usable hint when choosing a download mirror.
+
+use std::fs;
+
+use super::find_steam_path;
+use crate::game_finder::vdf::{parse_vdf, VdfValue};
+
+/// Keys holding the cell ID, in order of preference
+const CELL_ID_KEYS: &[&str] = &["CellIDServerOverride", "CurrentCellID"];
+
+/// Steam's download region (cell ID), if Steam has recorded one.
+#[must_use]
+pub fn download_region() -> Option<u32> {
+    let steam_path = find_steam_path()?;
+    let content = fs::read_to_string(steam_path.join("config/config.vdf")).ok()?;
+    parse_download_region(&content)
+}
+
+/// Cell ID from config.vdf content
+fn parse_download_region(config: &str) -> Option<u32> {
+    let root = parse_vdf(config)?;
+    let steam = ["InstallConfigStore", "Software", "Valve", "Steam"]
+        .iter()
+        .try_fold(&root, |node, key| node.get_ignore_case(key))?;
+
+    CELL_ID_KEYS.iter().find_map(|key| {
+        steam
+            .get_ignore_case(key)
+            .and_then(VdfValue::as_str)
+            .and_then(|v| v.trim().parse().ok())
+            .filter(|&cell: &u32| cell != 0)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_download_region() {
+        let content = r#"
+"InstallConfigStore"
+{
+    "Software"
+    {
+        "Valve"
+        {
+            "Steam"
+            {
+                "CellIDServerOverride"      "0"
+                "CurrentCellID"     "52"
+            }
+        }
+    }
+}
+"#;
+        // An override of 0 means "automatic"
+        assert_eq!(parse_download_region(content), Some(52));
+
+        let overridden = content.replace("\"CellIDServerOverride\"      \"0\"", "\"CellIDServerOverride\"      \"116\"");
+        assert_eq!(parse_download_region(&overridden), Some(116));
+
+        let unset = content.replace("\"52\"", "\"0\"");
+        assert_eq!(parse_download_region(&unset), None);
+    }
+}
@@ -0,0 +1,171 @@
+//! Steam Input configuration lookup
+//!
+//! Reports whether a game has Steam Input forced on or off in the active
+//! account's localconfig.vdf, and which controller configurations Steam
+//! has saved for it. Read-only.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::find_userdata_path;
+use crate::game_finder::vdf::{parse_vdf, VdfValue};
+
+/// App ID of Steam's controller configurator, whose remote storage holds
+/// the per-game configs
+const CONTROLLER_CONFIG_APP_ID: &str = "241100";
+
+/// Per-game Steam Input override (`UseSteamControllerConfig`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SteamInputSetting {
+    /// Forced off for this game
+    Disabled,
+    /// Follows the per-controller defaults
+    Default,
+    /// Forced on for this game
+    Enabled,
+}
+
+/// A saved controller configuration for one controller type
+#[derive(Debug, Clone)]
+pub struct ControllerConfig {
+    /// Controller type from the file name (e.g. "controller_ps5", "controller_neptune")
+    pub controller_type: String,
+    /// Configuration title, if the file has one
+    pub name: Option<String>,
+    pub path: PathBuf,
+}
+
+/// Steam Input setup for a game
+#[derive(Debug, Clone)]
+pub struct InputConfigInfo {
+    /// None when the game has no per-game override
+    pub setting: Option<SteamInputSetting>,
+    pub configs: Vec<ControllerConfig>,
+}
+
+/// Look up a game's Steam Input setting and saved controller configs.
+///
+/// Returns None when Steam has neither an override nor a config for it.
+#[must_use]
+pub fn game_input_config(app_id: u32) -> Option<InputConfigInfo> {
+    let userdata = find_userdata_path()?;
+    let setting = input_setting(&userdata, app_id);
+
+    let app = app_id.to_string();
+    let mut dirs = vec![userdata
+        .join(CONTROLLER_CONFIG_APP_ID)
+        .join("remote/controller_config")
+        .join(&app)];
+    // Older clients kept configs under steamapps/common/Steam Controller Configs/<account>
+    if let (Some(account), Some(steam_root)) = (
+        userdata.file_name(),
+        userdata.parent().and_then(Path::parent),
+    ) {
+        dirs.push(
+            steam_root
+                .join("steamapps/common/Steam Controller Configs")
+                .join(account)
+                .join("config")
+                .join(&app),
+        );
+    }
+
+    let mut configs: Vec<ControllerConfig> = Vec::new();
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("vdf") {
+                continue;
+            }
+            let Some(stem) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+                continue;
+            };
+            if configs.iter().any(|c| c.controller_type == stem) {
+                continue;
+            }
+            configs.push(ControllerConfig {
+                controller_type: stem,
+                name: fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|content| config_title(&content)),
+                path,
+            });
+        }
+    }
+    configs.sort_by(|a, b| a.controller_type.cmp(&b.controller_type));
+
+    if setting.is_none() && configs.is_empty() {
+        return None;
+    }
+    Some(InputConfigInfo { setting, configs })
+}
+
+/// Read `UseSteamControllerConfig` for an app from localconfig.vdf
+fn input_setting(userdata: &Path, app_id: u32) -> Option<SteamInputSetting> {
+    let content = fs::read_to_string(userdata.join("config/localconfig.vdf")).ok()?;
+    let root = parse_vdf(&content)?;
+    let value = root
+        .get_ignore_case("UserLocalConfigStore")?
+        .get_ignore_case("Apps")?
+        .get_ignore_case(&app_id.to_string())?
+        .get_ignore_case("UseSteamControllerConfig")?
+        .as_str()?;
+
+    match value {
+        "0" => Some(SteamInputSetting::Disabled),
+        "1" => Some(SteamInputSetting::Default),
+        "2" => Some(SteamInputSetting::Enabled),
+        _ => None,
+    }
+}
+
+/// Title of a controller_mappings file, resolving "#Token" titles through
+/// its English localization block
+fn config_title(content: &str) -> Option<String> {
+    let root = parse_vdf(content)?;
+    let mappings = root.get_ignore_case("controller_mappings")?;
+    let title = mappings.get_ignore_case("title").and_then(VdfValue::as_str)?;
+
+    let Some(token) = title.strip_prefix('#') else {
+        return Some(title.to_string()).filter(|t| !t.is_empty());
+    };
+    mappings
+        .get_ignore_case("localization")
+        .and_then(|l| l.get_ignore_case("english"))
+        .and_then(|l| l.get_ignore_case(token))
+        .and_then(VdfValue::as_str)
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_title() {
+        let content = r##""controller_mappings"
+{
+	"version"		"3"
+	"title"		"#Title"
+	"localization"
+	{
+		"english"
+		{
+			"title"		"Gamepad with Mouse Trackpad"
+		}
+	}
+}
+"##;
+        assert_eq!(
+            config_title(content).as_deref(),
+            Some("Gamepad with Mouse Trackpad")
+        );
+        assert_eq!(
+            config_title("\"controller_mappings\" { \"title\" \"My Layout\" }").as_deref(),
+            Some("My Layout")
+        );
+    }
+}
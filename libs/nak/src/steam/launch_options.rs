@@ -0,0 +1,290 @@
+//! Per-game launch options
+//!
+//! Reads and writes the launch options Steam stores in the active account's
+//! localconfig.vdf, and extracts or edits environment overrides in them.
+//!
+//! Steam rewrites localconfig.vdf on exit, so it should be closed before
+//! writing launch options.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use super::find_userdata_path;
+use crate::game_finder::vdf::{parse_vdf, set_string_value, VdfValue};
+
+/// Path from the localconfig.vdf root to the per-app settings
+const APPS_PATH: &[&str] = &["UserLocalConfigStore", "Software", "Valve", "Steam", "apps"];
+
+fn localconfig_path() -> Option<PathBuf> {
+    Some(find_userdata_path()?.join("config/localconfig.vdf"))
+}
+
+/// Read the launch options of every app for the active Steam account.
+///
+/// Returns a map of app ID -> launch options (only apps that have some set).
+#[must_use]
+pub fn read_all_launch_options() -> HashMap<String, String> {
+    let mut options = HashMap::new();
+
+    let Some(path) = localconfig_path() else {
+        return options;
+    };
+    let Ok(content) = fs::read_to_string(path) else {
+        return options;
+    };
+    let Some(root) = parse_vdf(&content) else {
+        return options;
+    };
+
+    let apps = APPS_PATH
+        .iter()
+        .try_fold(&root, |node, key| node.get_ignore_case(key))
+        .and_then(VdfValue::as_object);
+
+    for (app_id, app) in apps.into_iter().flatten() {
+        if let Some(launch) = app.get_ignore_case("LaunchOptions").and_then(VdfValue::as_str) {
+            if !launch.trim().is_empty() {
+                options.insert(app_id.clone(), launch.to_string());
+            }
+        }
+    }
+
+    options
+}
+
+/// Get the launch options for a single app.
+#[must_use]
+pub fn get_launch_options(app_id: u32) -> Option<String> {
+    read_all_launch_options().remove(&app_id.to_string())
+}
+
+/// Write launch options for several apps in one pass over localconfig.vdf.
+///
+/// Fails while Steam is running, since it would overwrite the change on exit.
+pub fn write_launch_options(updates: &[(u32, String)]) -> Result<(), Box<dyn Error>> {
+    if super::is_running() {
+        return Err("Close Steam before changing launch options; it rewrites localconfig.vdf on exit".into());
+    }
+    let path = localconfig_path().ok_or("Could not find Steam userdata directory")?;
+    let mut content = fs::read_to_string(&path)?;
+
+    for (app_id, options) in updates {
+        let app_key = app_id.to_string();
+        let mut app_path = APPS_PATH.to_vec();
+        app_path.push(&app_key);
+        content = set_string_value(&content, &app_path, "LaunchOptions", options)
+            .ok_or("localconfig.vdf has no apps section")?;
+    }
+
+    // Write atomically so a crash can't leave Steam with a truncated config
+    let tmp = path.with_extension("vdf.nak-tmp");
+    fs::write(&tmp, &content)?;
+    fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+/// Extract the value of an environment assignment (`VAR=value`) from launch
+/// options. Only assignments before `%command%` are considered.
+#[must_use]
+pub fn parse_env_assignment(launch_options: &str, var: &str) -> Option<String> {
+    let env_part = launch_options
+        .split("%command%")
+        .next()
+        .unwrap_or(launch_options);
+
+    for token in split_shell_words(env_part) {
+        if let Some(value) = token.strip_prefix(var).and_then(|r| r.strip_prefix('=')) {
+            return Some(value.to_string());
+        }
+    }
+
+    None
+}
+
+/// compatdata directory named by a `STEAM_COMPAT_DATA_PATH` override in
+/// launch options, with `~`, `$HOME` and `${HOME}` expanded.
+///
+/// Relative paths are ignored since Steam's working directory is undefined.
+#[must_use]
+pub fn compat_data_override(launch_options: &str) -> Option<PathBuf> {
+    let value = parse_env_assignment(launch_options, "STEAM_COMPAT_DATA_PATH")?;
+    let home = std::env::var("HOME").unwrap_or_default();
+
+    let expanded = if let Some(rest) = value.strip_prefix("~/") {
+        format!("{}/{}", home, rest)
+    } else if let Some(rest) = value.strip_prefix("${HOME}") {
+        format!("{}{}", home, rest)
+    } else if let Some(rest) = value.strip_prefix("$HOME") {
+        format!("{}{}", home, rest)
+    } else {
+        value
+    };
+
+    let path = PathBuf::from(expanded.trim_end_matches('/'));
+    path.is_absolute().then_some(path)
+}
+
+/// Set (or remove, with `None`) an environment assignment in launch options,
+/// leaving every other option untouched.
+#[must_use]
+pub fn set_env_assignment(launch_options: &str, var: &str, value: Option<&str>) -> String {
+    let assignment = value.map(|v| {
+        if v.chars().any(char::is_whitespace) {
+            format!("{}=\"{}\"", var, v)
+        } else {
+            format!("{}={}", var, v)
+        }
+    });
+
+    let trimmed = launch_options.trim();
+    let (env_part, rest) = match trimmed.find("%command%") {
+        Some(pos) => (&trimmed[..pos], &trimmed[pos..]),
+        // No %command% - existing options are plain game arguments
+        None if trimmed.is_empty() => ("", "%command%"),
+        None => ("", trimmed),
+    };
+    let rest = if rest.starts_with("%command%") {
+        rest.to_string()
+    } else {
+        format!("%command% {}", rest)
+    };
+
+    let mut words: Vec<String> = split_shell_words_raw(env_part)
+        .into_iter()
+        .filter(|w| {
+            let unquoted: String = w.chars().filter(|c| *c != '"' && *c != '\'').collect();
+            !unquoted.starts_with(&format!("{}=", var))
+        })
+        .collect();
+    if let Some(assignment) = assignment {
+        words.push(assignment);
+    }
+
+    if words.is_empty() {
+        rest
+    } else {
+        format!("{} {}", words.join(" "), rest)
+    }
+}
+
+/// Split on whitespace, honouring quotes but keeping them in the words
+fn split_shell_words_raw(s: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+
+    for c in s.chars() {
+        match quote {
+            Some(q) if c == q => {
+                quote = None;
+                current.push(c);
+            }
+            Some(_) => current.push(c),
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                current.push(c);
+            }
+            None if c.is_whitespace() => {
+                if !current.is_empty() {
+                    words.push(std::mem::take(&mut current));
+                }
+            }
+            None => current.push(c),
+        }
+    }
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// Split on whitespace, honouring single and double quotes (quotes are removed)
+fn split_shell_words(s: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut in_word = false;
+
+    for c in s.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if in_word {
+        words.push(current);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_env_assignment() {
+        let opts = r#"DXVK_CONFIG_FILE="/home/u/dxvk.conf" STEAM_COMPAT_DATA_PATH="/mnt/My Prefix" %command% -x"#;
+        assert_eq!(
+            parse_env_assignment(opts, "STEAM_COMPAT_DATA_PATH").as_deref(),
+            Some("/mnt/My Prefix")
+        );
+        assert_eq!(
+            parse_env_assignment(opts, "DXVK_CONFIG_FILE").as_deref(),
+            Some("/home/u/dxvk.conf")
+        );
+        assert_eq!(parse_env_assignment(opts, "STEAM_COMPAT_MOUNTS"), None);
+    }
+
+    #[test]
+    fn test_set_env_assignment() {
+        let opts = r#"DXVK_CONFIG_FILE="/a b/dxvk.conf" STEAM_COMPAT_MOUNTS=/old %command% -skipintro"#;
+        assert_eq!(
+            set_env_assignment(opts, "STEAM_COMPAT_MOUNTS", Some("/mnt:/games")),
+            r#"DXVK_CONFIG_FILE="/a b/dxvk.conf" STEAM_COMPAT_MOUNTS=/mnt:/games %command% -skipintro"#
+        );
+        assert_eq!(
+            set_env_assignment(opts, "STEAM_COMPAT_MOUNTS", None),
+            r#"DXVK_CONFIG_FILE="/a b/dxvk.conf" %command% -skipintro"#
+        );
+        assert_eq!(set_env_assignment("", "X", Some("1")), "X=1 %command%");
+        assert_eq!(set_env_assignment("-windowed", "X", Some("1")), "X=1 %command% -windowed");
+    }
+
+    #[test]
+    fn test_compat_data_override() {
+        let home = std::env::var("HOME").unwrap_or_default();
+        assert_eq!(
+            compat_data_override("STEAM_COMPAT_DATA_PATH=$HOME/prefixes/skyrim/ %command%"),
+            Some(PathBuf::from(format!("{}/prefixes/skyrim", home)))
+        );
+        assert_eq!(
+            compat_data_override("STEAM_COMPAT_DATA_PATH=/mnt/pfx %command%"),
+            Some(PathBuf::from("/mnt/pfx"))
+        );
+        assert_eq!(compat_data_override("STEAM_COMPAT_DATA_PATH=prefixes %command%"), None);
+    }
+
+    #[test]
+    fn test_parse_env_assignment_ignores_game_args() {
+        let opts = "%command% STEAM_COMPAT_DATA_PATH=/tmp/x";
+        assert_eq!(parse_env_assignment(opts, "STEAM_COMPAT_DATA_PATH"), None);
+    }
+}
@@ -3,49 +3,203 @@
 //! Handles Proton detection, Steam path detection, and mount point discovery.
 //! Shortcuts and config.vdf manipulation removed (handled by C++ side).
 
+mod cloud;
+mod compatdata;
+mod download_region;
+mod input_config;
+mod launch_options;
 mod paths;
 mod proton;
+mod runtime;
+mod shadercache;
+mod steam_play;
+mod vulkan;
 
 // Re-export path detection utilities
 pub use paths::{
-    detect_steam_path_checked, find_steam_path, find_userdata_path,
-    get_steam_accounts,
+    detect_steam_path_checked, diagnose_steam, find_steam_path, find_steam_roots, find_userdata_path,
+    get_library_folders, get_steam_accounts, resolve_steam_root, selected_account_name, steam_install_kind,
+    SteamAccount, SteamDiagnosis, SteamInstallKind, SteamPathState,
+};
+
+// Re-export Steam Cloud detection
+pub use cloud::cloud_sync_enabled;
+
+// Re-export compatdata storage reporting
+pub use compatdata::{compatdata_sizes, compatdata_sizes_cancellable};
+
+// Re-export download region lookup
+pub use download_region::download_region;
+
+// Re-export Steam Input config lookup
+pub use input_config::{game_input_config, ControllerConfig, InputConfigInfo, SteamInputSetting};
+
+// Re-export launch option helpers
+pub use launch_options::{
+    compat_data_override, get_launch_options, parse_env_assignment, read_all_launch_options,
+    set_env_assignment, write_launch_options,
 };
 
 // Re-export Proton detection
-pub use proton::{find_steam_protons, SteamProton};
+pub use proton::{
+    find_duplicate_tools, find_steam_protons, install_proton_ge, verify_archive,
+    verify_proton_install, SteamProton,
+};
+
+// Re-export Steam Linux Runtime detection
+pub use runtime::{runtime_versions, RuntimeInfo};
+
+// Re-export shader cache management
+pub use shadercache::{
+    clear_fossilize_cache, clear_shadercache, fossilize_cache_size, shadercache_sizes,
+};
+
+// Re-export Steam Play settings
+pub(crate) use steam_play::read_compat_tool_mapping;
+pub use steam_play::{steam_play_enabled, SteamPlayStatus};
+
+// Re-export Vulkan device enumeration
+pub use vulkan::{vulkan_devices, VulkanDevice, VulkanDeviceType};
 
 use std::fs;
 
-/// Kill Steam process gracefully, then force if needed
+/// Whether the Steam client is running for the current user.
+///
+/// Matches the client binary's process name exactly, so helpers like
+/// steamwebhelper or games launched through Steam don't count on their own.
+pub fn is_running() -> bool {
+    !steam_pids().is_empty()
+}
+
+/// PIDs of the current user's Steam client processes
+fn steam_pids() -> Vec<u32> {
+    user_pids_named(&["steam"])
+}
+
+/// Process names that make up a running Steam, including its web helper and
+/// the launch scripts/wrapper that respawn the client
+const STEAM_PROCESS_NAMES: &[&str] = &["steam", "steamwebhelper", "steam.sh", "steam-wrapper"];
+
+/// PIDs of the current user's processes whose name is one of `names`
+fn user_pids_named(names: &[&str]) -> Vec<u32> {
+    use std::os::unix::fs::MetadataExt;
+
+    // SAFETY: geteuid has no preconditions
+    let uid = unsafe { libc::geteuid() };
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+            if entry.metadata().ok()?.uid() != uid {
+                return None;
+            }
+            let comm = fs::read_to_string(entry.path().join("comm")).ok()?;
+            names.contains(&comm.trim_end()).then_some(pid)
+        })
+        .collect()
+}
+
+/// How long Steam gets to exit after `-shutdown` before it is killed
+const GRACEFUL_SHUTDOWN_WAIT: std::time::Duration = std::time::Duration::from_secs(10);
+/// How long killed Steam processes get to disappear
+const FORCED_SHUTDOWN_WAIT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Poll until no Steam process is left, up to `timeout`. True once gone.
+fn wait_for_steam_exit(timeout: std::time::Duration) -> bool {
+    let deadline = std::time::Instant::now() + timeout;
+    loop {
+        if user_pids_named(STEAM_PROCESS_NAMES).is_empty() {
+            return true;
+        }
+        if std::time::Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(200));
+    }
+}
+
+/// Program and arguments that run the Steam client for an install kind
+fn steam_launcher(kind: SteamInstallKind) -> (&'static str, &'static [&'static str]) {
+    match kind {
+        SteamInstallKind::Native => ("steam", &[]),
+        SteamInstallKind::Flatpak => ("flatpak", &["run", "com.valvesoftware.Steam"]),
+        SteamInstallKind::Snap => ("snap", &["run", "steam"]),
+    }
+}
+
+/// The command that runs the Steam client for an install kind
+fn steam_command(kind: SteamInstallKind) -> std::process::Command {
+    let (exe, args) = steam_launcher(kind);
+    let mut cmd = crate::runtime_wrap::command_for(exe);
+    cmd.args(args);
+    cmd
+}
+
+/// Shut Steam down: ask it to exit, then kill what is left.
+///
+/// `-shutdown` goes through the same launcher Steam was installed with
+/// (native, `flatpak run`, `snap run`). Leftovers, including steamwebhelper
+/// and the Flatpak wrapper, get SIGKILL, and a Flatpak Steam's sandbox is
+/// torn down with `flatpak kill`. Fails if Steam is still running afterwards.
 pub fn kill_steam() -> Result<(), Box<dyn std::error::Error>> {
-    use std::process::Command;
+    shutdown_steam(GRACEFUL_SHUTDOWN_WAIT)
+}
 
-    // Try steam -shutdown first (graceful)
-    let _ = Command::new("steam")
-        .arg("-shutdown")
-        .status();
+/// [`kill_steam`] giving Steam up to `graceful_wait` to exit on its own
+fn shutdown_steam(graceful_wait: std::time::Duration) -> Result<(), Box<dyn std::error::Error>> {
+    use std::process::Stdio;
 
-    std::thread::sleep(std::time::Duration::from_secs(2));
+    if user_pids_named(STEAM_PROCESS_NAMES).is_empty() {
+        return Ok(());
+    }
+    let kind = steam_install_kind().unwrap_or(SteamInstallKind::Native);
 
-    // Then force kill if still running
-    let _ = Command::new("pkill")
-        .arg("-9")
-        .arg("steam")
+    // Graceful first
+    let _ = steam_command(kind)
+        .arg("-shutdown")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
         .status();
+    if wait_for_steam_exit(graceful_wait) {
+        return Ok(());
+    }
 
-    // Brief wait for Steam to fully exit
-    std::thread::sleep(std::time::Duration::from_secs(2));
+    // Then force
+    crate::logging::log_warning("Steam did not shut down in time, killing it");
+    if kind == SteamInstallKind::Flatpak {
+        let _ = crate::runtime_wrap::command_for("flatpak")
+            .args(["kill", "com.valvesoftware.Steam"])
+            .stderr(Stdio::null())
+            .status();
+    }
+    for pid in user_pids_named(STEAM_PROCESS_NAMES) {
+        // SAFETY: kill has no memory preconditions; the pid belongs to this user
+        unsafe { libc::kill(pid as libc::pid_t, libc::SIGKILL) };
+    }
+    if wait_for_steam_exit(FORCED_SHUTDOWN_WAIT) {
+        return Ok(());
+    }
 
-    Ok(())
+    Err(format!(
+        "Steam is still running after {} seconds (pids: {:?})",
+        (graceful_wait + FORCED_SHUTDOWN_WAIT).as_secs(),
+        user_pids_named(STEAM_PROCESS_NAMES)
+    )
+    .into())
 }
 
-/// Start Steam in background
+/// Start Steam in background, through the launcher it was installed with
 pub fn start_steam() -> Result<(), Box<dyn std::error::Error>> {
-    use std::process::{Command, Stdio};
+    use std::process::Stdio;
 
-    Command::new("setsid")
-        .arg("steam")
+    let (exe, args) = steam_launcher(steam_install_kind().unwrap_or(SteamInstallKind::Native));
+    crate::runtime_wrap::command_for("setsid")
+        .arg(exe)
+        .args(args)
         .arg("-silent")
         .stdout(Stdio::null())
         .stderr(Stdio::null())
@@ -54,9 +208,22 @@ pub fn start_steam() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// How long [`restart_steam`] waits for the old Steam to exit on its own
+const RESTART_WAIT: std::time::Duration = std::time::Duration::from_secs(20);
+
 /// Restart Steam (kill then start)
 pub fn restart_steam() -> Result<(), Box<dyn std::error::Error>> {
-    kill_steam()?;
+    restart_steam_with_timeout(RESTART_WAIT)
+}
+
+/// Restart Steam, giving the old client up to `max_wait` to shut down.
+///
+/// The new Steam is only started once every old Steam process is gone;
+/// anything still running after `max_wait` is killed, and if even that
+/// fails the restart is abandoned with an error rather than starting a
+/// second Steam next to the old one.
+pub fn restart_steam_with_timeout(max_wait: std::time::Duration) -> Result<(), Box<dyn std::error::Error>> {
+    shutdown_steam(max_wait)?;
     start_steam()?;
     Ok(())
 }
@@ -108,17 +275,115 @@ pub fn detect_extra_mounts() -> Vec<String> {
     mounts
 }
 
-/// Generate launch options string with DXVK config file and STEAM_COMPAT_MOUNTS
-pub fn generate_launch_options(dxvk_conf_path: Option<&std::path::Path>, is_electron_app: bool) -> String {
+/// Union `detected` with `extra` minus `excluded`, sorted and deduplicated
+fn merge_mounts(detected: Vec<String>, extra: &[String], excluded: &[String]) -> Vec<String> {
+    let normalize = |m: &str| match m.trim().trim_end_matches('/') {
+        "" => None,
+        m => Some(m.to_string()),
+    };
+    let excluded: Vec<String> = excluded.iter().filter_map(|m| normalize(m)).collect();
+
+    let mut mounts: Vec<String> = detected
+        .iter()
+        .chain(extra)
+        .filter_map(|m| normalize(m))
+        .filter(|m| !excluded.contains(m))
+        .collect();
+    mounts.sort();
+    mounts.dedup();
+    mounts
+}
+
+/// Recompute STEAM_COMPAT_MOUNTS and write it into each app's launch options.
+///
+/// Other launch options are preserved; apps with no extra mounts have the
+/// variable removed. Fails while Steam is running, since it would overwrite
+/// the change on exit. Returns the number of apps whose options changed.
+pub fn sync_compat_mounts(app_ids: &[u32]) -> Result<usize, Box<dyn std::error::Error>> {
     let mounts = detect_extra_mounts();
+    let mounts_value = if mounts.is_empty() {
+        None
+    } else {
+        Some(mounts.join(":"))
+    };
+
+    let current = read_all_launch_options();
+    let updates: Vec<(u32, String)> = app_ids
+        .iter()
+        .filter_map(|app_id| {
+            let existing = current.get(&app_id.to_string()).map(String::as_str).unwrap_or("");
+            // Compare values, not strings: rewriting normalizes the options
+            // (adding %command% to empty ones) even when nothing changes
+            if parse_env_assignment(existing, "STEAM_COMPAT_MOUNTS").as_deref() == mounts_value.as_deref() {
+                return None;
+            }
+            let updated = set_env_assignment(existing, "STEAM_COMPAT_MOUNTS", mounts_value.as_deref());
+            Some((*app_id, updated))
+        })
+        .collect();
+
+    if updates.is_empty() {
+        return Ok(0);
+    }
+
+    write_launch_options(&updates)?;
+    crate::logging::log_info(&format!(
+        "Updated STEAM_COMPAT_MOUNTS for {} game(s): {}",
+        updates.len(),
+        mounts_value.as_deref().unwrap_or("(none)")
+    ));
+    Ok(updates.len())
+}
+
+/// Generate launch options string with DXVK config file and STEAM_COMPAT_MOUNTS,
+/// optionally pinning the game to a specific GPU and adding per-game env vars
+/// (e.g. from [`crate::game_finder::controller_env_for`])
+pub fn generate_launch_options(
+    dxvk_conf_path: Option<&std::path::Path>,
+    is_electron_app: bool,
+    gpu: Option<&VulkanDevice>,
+    extra_env: &[(String, String)],
+) -> String {
+    generate_launch_options_with_mounts(dxvk_conf_path, is_electron_app, gpu, extra_env, &[], &[])
+}
 
-    let dxvk_part = match dxvk_conf_path {
-        Some(path) => format!(
+/// [`generate_launch_options`] with user adjustments to STEAM_COMPAT_MOUNTS:
+/// `extra_mounts` are added to the detected directories and `excluded`
+/// ones removed. Trailing slashes are ignored when comparing.
+pub fn generate_launch_options_with_mounts(
+    dxvk_conf_path: Option<&std::path::Path>,
+    is_electron_app: bool,
+    gpu: Option<&VulkanDevice>,
+    extra_env: &[(String, String)],
+    extra_mounts: &[String],
+    excluded: &[String],
+) -> String {
+    let mounts = merge_mounts(detect_extra_mounts(), extra_mounts, excluded);
+    let mut env_parts: Vec<String> = Vec::new();
+
+    if let Some(path) = dxvk_conf_path {
+        env_parts.push(format!(
             "DXVK_CONFIG_FILE=\"{}\"",
             crate::config::normalize_path_for_steam(&path.to_string_lossy())
-        ),
-        None => String::new(),
-    };
+        ));
+    }
+
+    if !mounts.is_empty() {
+        env_parts.push(format!("STEAM_COMPAT_MOUNTS={}", mounts.join(":")));
+    }
+
+    let gpu_env = gpu.map(VulkanDevice::selection_env).unwrap_or_default();
+    let env = gpu_env
+        .iter()
+        .map(|(k, v)| (*k, v.as_str()))
+        .chain(extra_env.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+    for (key, value) in env {
+        if value.chars().any(char::is_whitespace) {
+            env_parts.push(format!("{}=\"{}\"", key, value));
+        } else {
+            env_parts.push(format!("{}={}", key, value));
+        }
+    }
 
     let electron_flags = if is_electron_app {
         " --disable-gpu --no-sandbox"
@@ -126,10 +391,9 @@ pub fn generate_launch_options(dxvk_conf_path: Option<&std::path::Path>, is_elec
         ""
     };
 
-    match (dxvk_part.is_empty(), mounts.is_empty()) {
-        (true, true) => format!("%command%{}", electron_flags),
-        (true, false) => format!("STEAM_COMPAT_MOUNTS={} %command%{}", mounts.join(":"), electron_flags),
-        (false, true) => format!("{} %command%{}", dxvk_part, electron_flags),
-        (false, false) => format!("{} STEAM_COMPAT_MOUNTS={} %command%{}", dxvk_part, mounts.join(":"), electron_flags),
+    if env_parts.is_empty() {
+        format!("%command%{}", electron_flags)
+    } else {
+        format!("{} %command%{}", env_parts.join(" "), electron_flags)
     }
 }
@@ -1,7 +1,7 @@
 //! Steam path detection utilities
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::logging::{log_info, log_warning};
 
@@ -10,21 +10,268 @@ use crate::logging::{log_info, log_warning};
 // ============================================================================
 
 /// Find the Steam installation path.
+///
+/// The result is canonical, so symlink chains (common under Snap) never
+/// leak into later path joins.
 #[must_use]
 pub fn find_steam_path() -> Option<PathBuf> {
     let home = std::env::var("HOME").ok()?;
+    find_steam_path_in(Path::new(&home))
+}
 
-    let steam_paths = [
-        format!("{}/.steam/steam", home),
-        format!("{}/.local/share/Steam", home),
-        format!("{}/.var/app/com.valvesoftware.Steam/.steam/steam", home),
-        format!("{}/snap/steam/common/.steam/steam", home),
-    ];
+/// Candidate Steam roots, relative to $HOME, in order of preference
+const STEAM_PATH_CANDIDATES: &[&str] = &[
+    ".steam/steam",
+    ".local/share/Steam",
+    ".var/app/com.valvesoftware.Steam/.steam/steam",
+    "snap/steam/common/.steam/steam",
+];
 
-    steam_paths
+fn find_steam_path_in(home: &Path) -> Option<PathBuf> {
+    STEAM_PATH_CANDIDATES
         .iter()
-        .map(PathBuf::from)
-        .find(|p| p.exists())
+        .find_map(|p| resolve_steam_root(&home.join(p)))
+}
+
+/// How a Steam client was installed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SteamInstallKind {
+    Native,
+    /// com.valvesoftware.Steam
+    Flatpak,
+    Snap,
+}
+
+/// How the Steam install [`find_steam_path`] picks was installed, or None
+/// if Steam isn't found.
+#[must_use]
+pub fn steam_install_kind() -> Option<SteamInstallKind> {
+    find_steam_path().map(|root| install_kind_of(&root))
+}
+
+/// Classify a canonical Steam root by where it lives
+fn install_kind_of(root: &Path) -> SteamInstallKind {
+    let root = root.to_string_lossy();
+    if root.contains("/.var/app/com.valvesoftware.Steam/") {
+        SteamInstallKind::Flatpak
+    } else if root.contains("/snap/steam/") {
+        SteamInstallKind::Snap
+    } else {
+        SteamInstallKind::Native
+    }
+}
+
+/// Every Steam installation root (native, Flatpak, Snap), in order of
+/// preference. Canonical and deduplicated, so the usual `~/.steam/steam`
+/// symlink doesn't list the same install twice.
+#[must_use]
+pub fn find_steam_roots() -> Vec<PathBuf> {
+    let Ok(home) = std::env::var("HOME") else {
+        return Vec::new();
+    };
+    let mut roots: Vec<PathBuf> = Vec::new();
+    for root in STEAM_PATH_CANDIDATES
+        .iter()
+        .filter_map(|p| resolve_steam_root(&Path::new(&home).join(p)))
+    {
+        if !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+    roots
+}
+
+/// Every Steam library root on the system, from each installation's
+/// libraryfolders.vdf (current and old flat formats) plus the installation
+/// itself.
+///
+/// Paths are canonical and deduplicated; entries on mounts that aren't
+/// present are skipped.
+#[must_use]
+pub fn get_library_folders() -> Vec<PathBuf> {
+    crate::game_finder::find_library_folders()
+}
+
+/// Maximum symlinks followed while resolving a Steam root
+const MAX_SYMLINK_HOPS: usize = 32;
+
+/// Resolve a candidate Steam root through any symlinks to its canonical path.
+///
+/// Returns None for dangling links, symlink loops, or directories that are
+/// not a real Steam root (no `steamapps` inside).
+#[must_use]
+pub fn resolve_steam_root(path: &Path) -> Option<PathBuf> {
+    match check_steam_root(path) {
+        SteamPathState::Valid(canonical) => Some(canonical),
+        SteamPathState::SymlinkLoop => {
+            log_warning(&format!("Symlink loop while resolving Steam path {}", path.display()));
+            None
+        }
+        _ => None,
+    }
+}
+
+/// What was found at a candidate Steam root
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SteamPathState {
+    /// Nothing exists at the path
+    Missing,
+    /// A symlink whose target does not exist
+    DanglingSymlink,
+    /// A symlink chain that loops back on itself
+    SymlinkLoop,
+    /// A directory exists but has no `steamapps` folder
+    NoSteamapps,
+    /// A usable Steam root (canonical path)
+    Valid(PathBuf),
+}
+
+fn check_steam_root(path: &Path) -> SteamPathState {
+    if fs::symlink_metadata(path).is_err() {
+        return SteamPathState::Missing;
+    }
+
+    // Walk the link chain ourselves so a loop is reported rather than spun on
+    let mut current = path.to_path_buf();
+    let mut seen: Vec<PathBuf> = Vec::new();
+    while let Ok(target) = fs::read_link(&current) {
+        if seen.contains(&current) || seen.len() >= MAX_SYMLINK_HOPS {
+            return SteamPathState::SymlinkLoop;
+        }
+        seen.push(current.clone());
+        current = match current.parent() {
+            Some(parent) => parent.join(target),
+            None => target,
+        };
+    }
+
+    let Ok(canonical) = current.canonicalize() else {
+        return SteamPathState::DanglingSymlink;
+    };
+    if !canonical.join("steamapps").is_dir() {
+        return SteamPathState::NoSteamapps;
+    }
+    SteamPathState::Valid(canonical)
+}
+
+// ============================================================================
+// Detection Diagnostics
+// ============================================================================
+
+/// Why Steam was (or wasn't) found, for showing the user something actionable
+#[derive(Debug, Clone)]
+pub struct SteamDiagnosis {
+    /// The Steam root NaK will use, if any
+    pub steam_path: Option<PathBuf>,
+    /// Every candidate path checked, with what was found there
+    pub candidates: Vec<(PathBuf, SteamPathState)>,
+    /// Flatpak Steam (com.valvesoftware.Steam) appears to be installed
+    pub flatpak_steam_installed: bool,
+    /// Snap Steam appears to be installed
+    pub snap_steam_installed: bool,
+    /// NaK itself is running inside a Flatpak sandbox
+    pub nak_sandboxed: bool,
+    /// Human-readable explanation and next step
+    pub guidance: String,
+}
+
+/// Explain the outcome of Steam path detection.
+#[must_use]
+pub fn diagnose_steam() -> SteamDiagnosis {
+    let home = PathBuf::from(std::env::var("HOME").unwrap_or_default());
+
+    let candidates: Vec<(PathBuf, SteamPathState)> = STEAM_PATH_CANDIDATES
+        .iter()
+        .map(|p| {
+            let path = home.join(p);
+            let state = check_steam_root(&path);
+            (path, state)
+        })
+        .collect();
+
+    let steam_path = candidates.iter().find_map(|(_, state)| match state {
+        SteamPathState::Valid(p) => Some(p.clone()),
+        _ => None,
+    });
+
+    let flatpak_steam_installed = [
+        home.join(".var/app/com.valvesoftware.Steam"),
+        home.join(".local/share/flatpak/app/com.valvesoftware.Steam"),
+        PathBuf::from("/var/lib/flatpak/app/com.valvesoftware.Steam"),
+    ]
+    .iter()
+    .any(|p| p.exists());
+    let snap_steam_installed = Path::new("/snap/steam").exists() || home.join("snap/steam").exists();
+    let nak_sandboxed = crate::runtime_wrap::is_flatpak();
+
+    let guidance = steam_guidance(
+        steam_path.as_deref(),
+        &candidates,
+        flatpak_steam_installed,
+        snap_steam_installed,
+        nak_sandboxed,
+    );
+
+    SteamDiagnosis {
+        steam_path,
+        candidates,
+        flatpak_steam_installed,
+        snap_steam_installed,
+        nak_sandboxed,
+        guidance,
+    }
+}
+
+fn steam_guidance(
+    steam_path: Option<&Path>,
+    candidates: &[(PathBuf, SteamPathState)],
+    flatpak_steam_installed: bool,
+    snap_steam_installed: bool,
+    nak_sandboxed: bool,
+) -> String {
+    if let Some(path) = steam_path {
+        return format!("Steam found at {}.", path.display());
+    }
+
+    let nothing_visible = candidates.iter().all(|(_, s)| *s == SteamPathState::Missing);
+    if nak_sandboxed && nothing_visible {
+        let app_id = std::env::var("FLATPAK_ID").unwrap_or_else(|_| "<app id>".to_string());
+        return format!(
+            "NaK is running as a Flatpak and cannot see Steam's files. Grant access with: \
+             flatpak override --user --filesystem=~/.steam --filesystem=~/.local/share/Steam \
+             --filesystem=~/.var/app/com.valvesoftware.Steam {}",
+            app_id
+        );
+    }
+
+    if let Some((path, _)) = candidates
+        .iter()
+        .find(|(_, s)| matches!(s, SteamPathState::DanglingSymlink | SteamPathState::SymlinkLoop))
+    {
+        return format!(
+            "{} is a broken symlink. Remove it (or reinstall Steam) so it no longer points to a missing location.",
+            path.display()
+        );
+    }
+
+    if let Some((path, _)) = candidates
+        .iter()
+        .find(|(_, s)| *s == SteamPathState::NoSteamapps)
+    {
+        return format!(
+            "Steam exists at {} but has no steamapps folder. Start Steam once and sign in so it can finish setting up.",
+            path.display()
+        );
+    }
+
+    if flatpak_steam_installed {
+        return "Flatpak Steam is installed but has not been set up yet. Launch it once and sign in.".to_string();
+    }
+    if snap_steam_installed {
+        return "Snap Steam is installed but has not been set up yet. Launch it once and sign in.".to_string();
+    }
+
+    "Steam does not appear to be installed. Install it from your distribution's package manager or Flathub, then launch it once.".to_string()
 }
 
 /// Find the Steam userdata directory.
@@ -57,19 +304,21 @@ pub fn find_userdata_path() -> Option<PathBuf> {
         }
     }
 
-    if let Some(first_account) = accounts.first() {
-        let path = userdata.join(&first_account.account_id);
-        if path.exists() {
+    // Newest login Timestamp, unless several accounts share it
+    let stamped: Vec<&SteamAccount> = accounts
+        .iter()
+        .filter(|a| a.timestamp > 0 && userdata.join(&a.account_id).exists())
+        .collect();
+    if let Some(newest) = stamped.first() {
+        if stamped.get(1).is_none_or(|next| next.timestamp < newest.timestamp) {
             log_info(&format!(
-                "Using Steam account from loginusers.vdf (most recent timestamp): {} ({})",
-                first_account.persona_name, first_account.account_id
+                "Using Steam account from loginusers.vdf (newest Timestamp): {} ({})",
+                newest.persona_name, newest.account_id
             ));
-            return Some(path);
+            return Some(userdata.join(&newest.account_id));
         }
     }
 
-    log_warning("Could not determine active Steam account from loginusers.vdf, falling back to directory modification time");
-
     let mut user_dirs: Vec<PathBuf> = Vec::new();
 
     if let Ok(entries) = fs::read_dir(&userdata) {
@@ -86,13 +335,39 @@ pub fn find_userdata_path() -> Option<PathBuf> {
         }
     }
 
+    // Steam rewrites localconfig.vdf for the signed-in account; backups and
+    // file managers don't touch it the way they touch the directory itself
+    let by_localconfig = user_dirs
+        .iter()
+        .filter_map(|dir| {
+            let modified = fs::metadata(dir.join("config/localconfig.vdf"))
+                .and_then(|m| m.modified())
+                .ok()?;
+            Some((dir, modified))
+        })
+        .max_by_key(|&(_, modified)| modified);
+    if let Some((dir, _)) = by_localconfig {
+        log_info(&format!(
+            "Using Steam account with the most recently updated localconfig.vdf: {}",
+            dir.display()
+        ));
+        return Some(dir.clone());
+    }
+
+    log_warning("Could not determine active Steam account from loginusers.vdf or localconfig.vdf, falling back to directory modification time");
+
     user_dirs.sort_by(|a, b| {
         let a_time = fs::metadata(a).and_then(|m| m.modified()).ok();
         let b_time = fs::metadata(b).and_then(|m| m.modified()).ok();
         b_time.cmp(&a_time)
     });
 
-    user_dirs.into_iter().next()
+    let chosen = user_dirs.into_iter().next()?;
+    log_info(&format!(
+        "Using Steam account with the newest userdata directory: {}",
+        chosen.display()
+    ));
+    Some(chosen)
 }
 
 // ============================================================================
@@ -162,11 +437,24 @@ pub fn get_steam_accounts() -> Vec<SteamAccount> {
         }
     }
 
-    accounts.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    accounts.sort_by_key(|a| std::cmp::Reverse(a.timestamp));
 
     accounts
 }
 
+/// Persona name of the account NaK is using (the configured
+/// `selected_steam_account`, or the auto-detected one).
+#[must_use]
+pub fn selected_account_name() -> Option<String> {
+    let userdata = find_userdata_path()?;
+    let account_id = userdata.file_name()?.to_string_lossy().into_owned();
+
+    get_steam_accounts()
+        .into_iter()
+        .find(|a| a.account_id == account_id)
+        .map(|a| a.persona_name)
+}
+
 #[derive(Default)]
 struct SteamAccountBuilder {
     account_name: Option<String>,
@@ -257,3 +545,53 @@ pub fn detect_steam_path_checked() -> Option<String> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+
+    /// Build the Snap layout: the real install lives under .local/share and
+    /// ~/snap/steam/common/.steam/steam is a relative symlink chain to it
+    fn snap_fixture(name: &str) -> PathBuf {
+        let home = std::env::temp_dir().join(format!("nak-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&home);
+        let common = home.join("snap/steam/common");
+        fs::create_dir_all(common.join(".local/share/Steam/steamapps")).unwrap();
+        fs::create_dir_all(common.join(".steam")).unwrap();
+        symlink("../.local/share/Steam", common.join(".steam/root")).unwrap();
+        symlink("root", common.join(".steam/steam")).unwrap();
+        home
+    }
+
+    #[test]
+    fn test_resolve_snap_steam_root() {
+        let home = snap_fixture("snap");
+        let expected = home
+            .join("snap/steam/common/.local/share/Steam")
+            .canonicalize()
+            .unwrap();
+        assert_eq!(find_steam_path_in(&home), Some(expected));
+
+        // A dangling ~/.steam/steam must not win over the working Snap path
+        fs::create_dir_all(home.join(".steam")).unwrap();
+        symlink(home.join("missing"), home.join(".steam/steam")).unwrap();
+        assert!(resolve_steam_root(&home.join(".steam/steam")).is_none());
+        assert!(find_steam_path_in(&home).unwrap().ends_with(".local/share/Steam"));
+
+        let _ = fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn test_resolve_steam_root_loop() {
+        let home = snap_fixture("loop");
+        let steam = home.join("snap/steam/common/.steam");
+        symlink("loop_b", steam.join("loop_a")).unwrap();
+        symlink("loop_a", steam.join("loop_b")).unwrap();
+        assert!(resolve_steam_root(&steam.join("loop_a")).is_none());
+        assert_eq!(check_steam_root(&steam.join("loop_a")), SteamPathState::SymlinkLoop);
+        assert_eq!(check_steam_root(&steam.join("missing")), SteamPathState::Missing);
+
+        let _ = fs::remove_dir_all(&home);
+    }
+}
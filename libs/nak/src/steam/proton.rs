@@ -1,12 +1,21 @@
 //! Steam Proton detection and management
 //!
 //! Finds Protons that Steam can see and use for non-Steam games.
-//! This includes Steam's built-in Protons and custom Protons in compatibilitytools.d.
+//! This includes Steam's built-in Protons and custom Protons in compatibilitytools.d,
+//! and installing downloaded builds into the latter.
 
+use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
 
-use super::find_steam_path;
+use flate2::read::GzDecoder;
+use sha2::{Digest, Sha256};
+
+use super::{find_steam_path, find_steam_roots};
+use crate::game_finder::vdf::{parse_vdf, VdfValue};
+use crate::logging::log_info;
+use crate::runtime_wrap;
 
 /// Information about an installed Proton version
 #[derive(Debug, Clone)]
@@ -48,6 +57,78 @@ impl SteamProton {
     }
 }
 
+/// Check that a Proton install has everything NaK runs.
+///
+/// Needs the `proton` script and an executable `wine` and `wineserver` in
+/// `files/bin` (Proton 5.13+) or `dist/bin`. The error says what is missing
+/// and, when `path` is one level off (its `files` folder, or a
+/// compatibilitytools.d full of builds), which folder to pick instead.
+pub fn verify_proton_install(path: &Path) -> Result<(), String> {
+    if !path.exists() {
+        return Err(format!("{} does not exist", path.display()));
+    }
+    if !path.is_dir() {
+        return Err(format!("{} is not a directory", path.display()));
+    }
+
+    let script = path.join("proton");
+    if !script.is_file() {
+        if let Some(root) = path.ancestors().skip(1).take(2).find(|a| a.join("proton").is_file()) {
+            return Err(format!(
+                "{} is inside a Proton install; select {} instead",
+                path.display(),
+                root.display()
+            ));
+        }
+        let has_builds = fs::read_dir(path)
+            .is_ok_and(|entries| entries.flatten().any(|e| e.path().join("proton").is_file()));
+        if has_builds {
+            return Err(format!(
+                "{} holds several Proton builds; select one of its folders",
+                path.display()
+            ));
+        }
+        return Err(format!("{} has no proton script, so it is not a Proton install", path.display()));
+    }
+    if !is_executable(&script) {
+        return Err(format!("{} is not executable", script.display()));
+    }
+
+    let Some(bin_dir) = ["files/bin", "dist/bin"]
+        .into_iter()
+        .map(|bin| path.join(bin))
+        .find(|bin| bin.join("wine").exists())
+    else {
+        // Older builds ship their files packed until Steam first runs them
+        if ["proton_dist.tar", "dist.tar"].iter().any(|tar| path.join(tar).exists()) {
+            return Err(format!(
+                "{} has not been unpacked yet; run a game with it from Steam once",
+                path.display()
+            ));
+        }
+        return Err(format!(
+            "{} has no wine binary (checked files/bin/wine and dist/bin/wine)",
+            path.display()
+        ));
+    };
+
+    for exe in ["wine", "wineserver"] {
+        let exe_path = bin_dir.join(exe);
+        if !exe_path.exists() {
+            return Err(format!("{} is missing", exe_path.display()));
+        }
+        if !is_executable(&exe_path) {
+            return Err(format!("{} is not executable", exe_path.display()));
+        }
+    }
+    Ok(())
+}
+
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
 /// Find all Protons that Steam can use (Proton 10+ only)
 pub fn find_steam_protons() -> Vec<SteamProton> {
     let mut protons = Vec::new();
@@ -59,12 +140,23 @@ pub fn find_steam_protons() -> Vec<SteamProton> {
     // 1. Steam's built-in Protons (steamapps/common/Proton*)
     protons.extend(find_builtin_protons(&steam_path));
 
-    // 2. Custom Protons in user's compatibilitytools.d
-    protons.extend(find_custom_protons(&steam_path));
+    // 2. Custom Protons in compatibilitytools.d of every Steam install
+    //    (a native and a Flatpak Steam each keep their own)
+    for root in find_steam_roots() {
+        for proton in find_custom_protons(&root) {
+            if !protons.iter().any(|p| p.path == proton.path) {
+                protons.push(proton);
+            }
+        }
+    }
 
     // 3. System-level Protons in /usr/share/steam/compatibilitytools.d/
     //    (Arch packages Proton here; Flatpak has --filesystem=/usr/share/steam:ro)
-    protons.extend(find_system_protons());
+    for proton in find_system_protons() {
+        if !protons.iter().any(|p| p.path == proton.path) {
+            protons.push(proton);
+        }
+    }
 
     // Filter to only include Proton 10+ (required for Steam-native integration)
     protons.retain(is_proton_10_or_newer);
@@ -92,6 +184,89 @@ pub fn find_steam_protons() -> Vec<SteamProton> {
     protons
 }
 
+/// Group custom compatibility tools that are builds of the same family
+/// (e.g. GE-Proton9-20 and GE-Proton10-3), so older copies can be cleaned up.
+///
+/// Only tools in the user's compatibilitytools.d are considered, and old
+/// builds are kept (unlike [`find_steam_protons`]) since those are the ones
+/// worth removing. Each group is sorted newest first; only groups with more
+/// than one member are returned.
+pub fn find_duplicate_tools() -> Vec<Vec<SteamProton>> {
+    let Some(steam_path) = find_steam_path() else {
+        return Vec::new();
+    };
+    group_duplicate_tools(find_custom_protons(&steam_path))
+}
+
+/// A tool with its parsed version, for sorting within a family
+type VersionedTool = (Vec<u32>, SteamProton);
+
+fn group_duplicate_tools(tools: Vec<SteamProton>) -> Vec<Vec<SteamProton>> {
+    let mut groups: Vec<(String, Vec<VersionedTool>)> = Vec::new();
+
+    for tool in tools {
+        let Some((family, version)) = tool_family(&tool.name) else {
+            continue;
+        };
+        match groups.iter_mut().find(|(f, _)| *f == family) {
+            Some((_, members)) => members.push((version, tool)),
+            None => groups.push((family, vec![(version, tool)])),
+        }
+    }
+
+    let mut result: Vec<Vec<SteamProton>> = groups
+        .into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .map(|(_, mut members)| {
+            members.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| b.1.name.cmp(&a.1.name)));
+            members.into_iter().map(|(_, tool)| tool).collect()
+        })
+        .collect();
+    result.sort_by(|a, b| a[0].name.to_lowercase().cmp(&b[0].name.to_lowercase()));
+    result
+}
+
+/// Split a tool name into a family key and its numeric version parts.
+///
+/// "GE-Proton9-20" -> ("ge-proton", [9, 20]),
+/// "proton-cachyos-10.0-20250101-slr" -> ("proton-cachyos-slr", [10, 0, 20250101]).
+/// Names without any version number have no family.
+fn tool_family(name: &str) -> Option<(String, Vec<u32>)> {
+    let mut family = String::new();
+    let mut version = Vec::new();
+    let mut number = String::new();
+
+    for c in name.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let after_number = !number.is_empty();
+        if after_number {
+            version.push(number.parse().unwrap_or(u32::MAX));
+            number.clear();
+        }
+        if c.is_alphabetic() {
+            if after_number && !family.is_empty() && !family.ends_with('-') {
+                family.push('-');
+            }
+            family.extend(c.to_lowercase());
+        } else if !family.is_empty() && !family.ends_with('-') {
+            // Separators collapse into one, so "Proton-GE" and "Proton_GE" match
+            family.push('-');
+        }
+    }
+    if !number.is_empty() {
+        version.push(number.parse().unwrap_or(u32::MAX));
+    }
+
+    let family = family.trim_end_matches('-').to_string();
+    if version.is_empty() || family.is_empty() {
+        return None;
+    }
+    Some((family, version))
+}
+
 /// Check if a Proton version is 10 or newer
 fn is_proton_10_or_newer(proton: &SteamProton) -> bool {
     let name = &proton.name;
@@ -185,28 +360,54 @@ fn find_builtin_protons(steam_path: &std::path::Path) -> Vec<SteamProton> {
 
 /// Find custom Protons in compatibilitytools.d
 fn find_custom_protons(steam_path: &std::path::Path) -> Vec<SteamProton> {
-    let mut found = Vec::new();
-    let compat_dir = steam_path.join("compatibilitytools.d");
+    find_compat_tools(&steam_path.join("compatibilitytools.d"))
+}
 
-    let Ok(entries) = fs::read_dir(&compat_dir) else {
+/// Find system-level Protons in /usr/share/steam/compatibilitytools.d/
+fn find_system_protons() -> Vec<SteamProton> {
+    find_compat_tools(Path::new("/usr/share/steam/compatibilitytools.d"))
+}
+
+/// Proton-based tools in a compatibilitytools.d directory.
+///
+/// Each tool folder's compatibilitytool.vdf supplies the internal and
+/// display names and the install path; folders without one count if they
+/// hold a `proton` script. Tools whose toolmanifest.vdf runs something other
+/// than Proton (Luxtorpeda, Boxtron) are skipped.
+fn find_compat_tools(compat_dir: &Path) -> Vec<SteamProton> {
+    let mut found: Vec<SteamProton> = Vec::new();
+
+    let Ok(entries) = fs::read_dir(compat_dir) else {
         return found;
     };
 
     for entry in entries.flatten() {
-        let path = entry.path();
-        if !path.is_dir() {
+        let dir = entry.path();
+        if !dir.is_dir() {
             continue;
         }
 
-        let name = entry.file_name().to_string_lossy().to_string();
-
-        let has_proton = path.join("proton").exists();
-        let has_vdf = path.join("compatibilitytool.vdf").exists();
-
-        if has_proton || has_vdf {
+        let dir_name = entry.file_name().to_string_lossy().to_string();
+        let tools = match fs::read_to_string(dir.join("compatibilitytool.vdf")) {
+            Ok(content) => parse_compat_tools(&content),
+            Err(_) if dir.join("proton").exists() => vec![CompatToolEntry {
+                config_name: dir_name.clone(),
+                display_name: None,
+                install_path: ".".to_string(),
+            }],
+            Err(_) => continue,
+        };
+
+        for tool in tools {
+            let Ok(path) = dir.join(&tool.install_path).canonicalize() else {
+                continue;
+            };
+            if !runs_proton(&path) || found.iter().any(|p| p.path == path) {
+                continue;
+            }
             found.push(SteamProton {
-                name: name.clone(),
-                config_name: name.clone(),
+                name: tool.display_name.unwrap_or_else(|| tool.config_name.clone()),
+                config_name: tool.config_name,
                 path,
                 is_steam_proton: false,
                 is_experimental: false,
@@ -217,36 +418,304 @@ fn find_custom_protons(steam_path: &std::path::Path) -> Vec<SteamProton> {
     found
 }
 
-/// Find system-level Protons in /usr/share/steam/compatibilitytools.d/
-fn find_system_protons() -> Vec<SteamProton> {
-    let mut found = Vec::new();
-    let system_compat_dir = PathBuf::from("/usr/share/steam/compatibilitytools.d");
+/// A tool declared in compatibilitytool.vdf
+#[derive(Debug, PartialEq, Eq)]
+struct CompatToolEntry {
+    /// Key Steam stores in config.vdf
+    config_name: String,
+    display_name: Option<String>,
+    /// Relative to the vdf's folder unless absolute
+    install_path: String,
+}
 
-    let Ok(entries) = fs::read_dir(&system_compat_dir) else {
-        return found;
+/// Tools listed under `compatibilitytools/compat_tools`
+fn parse_compat_tools(content: &str) -> Vec<CompatToolEntry> {
+    let Some(tools) = parse_vdf(content)
+        .as_ref()
+        .and_then(|root| root.get_ignore_case("compatibilitytools"))
+        .and_then(|c| c.get_ignore_case("compat_tools"))
+        .and_then(VdfValue::as_object)
+        .cloned()
+    else {
+        return Vec::new();
     };
 
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if !path.is_dir() {
-            continue;
+    let mut entries: Vec<CompatToolEntry> = tools
+        .into_iter()
+        .filter(|(_, tool)| tool.as_object().is_some())
+        .map(|(config_name, tool)| CompatToolEntry {
+            display_name: tool.get_str("display_name").map(String::from),
+            install_path: tool.get_str("install_path").unwrap_or(".").to_string(),
+            config_name,
+        })
+        .collect();
+    entries.sort_by(|a, b| a.config_name.cmp(&b.config_name));
+    entries
+}
+
+/// Whether a tool runs through a `proton` script. Without a
+/// toolmanifest.vdf the script itself has to be there.
+fn runs_proton(tool_path: &Path) -> bool {
+    let Ok(content) = fs::read_to_string(tool_path.join("toolmanifest.vdf")) else {
+        return tool_path.join("proton").exists();
+    };
+    parse_vdf(&content)
+        .as_ref()
+        .and_then(|root| root.get_ignore_case("manifest"))
+        .and_then(|manifest| manifest.get_ignore_case("commandline"))
+        .and_then(VdfValue::as_str)
+        .is_some_and(|cmd| cmd.split_whitespace().next().is_some_and(|exe| exe.ends_with("/proton")))
+}
+
+// ============================================================================
+// Installing Proton builds
+// ============================================================================
+
+/// Check a downloaded Proton tarball before extracting it.
+///
+/// Verifies the SHA-256 checksum (hex, case-insensitive) when one is given,
+/// then that the file is a gzip/xz/zstd/plain tar. For gzip archives the first
+/// tar header is decompressed and its checksum checked as well.
+pub fn verify_archive(path: &Path, expected_sha256: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let mut file = fs::File::open(path)
+        .map_err(|e| format!("Cannot open {}: {}", path.display(), e))?;
+
+    if let Some(expected) = expected_sha256.map(str::trim).filter(|s| !s.is_empty()) {
+        let mut hasher = Sha256::new();
+        io::copy(&mut file, &mut hasher)?;
+        let actual: String = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+        if !actual.eq_ignore_ascii_case(expected) {
+            return Err(format!(
+                "Checksum mismatch for {}: expected {}, got {}",
+                path.display(),
+                expected,
+                actual
+            )
+            .into());
         }
+        file.seek(SeekFrom::Start(0))?;
+    }
 
-        let name = entry.file_name().to_string_lossy().to_string();
+    let mut magic = [0u8; 6];
+    let read = file.read(&mut magic)?;
+    file.seek(SeekFrom::Start(0))?;
+    let magic = &magic[..read];
+
+    if magic.starts_with(GZIP_MAGIC) {
+        let mut header = [0u8; TAR_BLOCK];
+        GzDecoder::new(file)
+            .read_exact(&mut header)
+            .map_err(|e| format!("{} is not a valid gzip archive: {}", path.display(), e))?;
+        if !is_tar_header(&header) {
+            return Err(format!("{} does not contain a tar archive", path.display()).into());
+        }
+    } else if magic.starts_with(XZ_MAGIC) || magic.starts_with(ZSTD_MAGIC) {
+        // No decoder for these; the magic is all that can be checked up front
+    } else {
+        let mut header = [0u8; TAR_BLOCK];
+        if file.read_exact(&mut header).is_err() || !is_tar_header(&header) {
+            return Err(format!("{} is not a tar archive", path.display()).into());
+        }
+    }
 
-        let has_proton = path.join("proton").exists();
-        let has_vdf = path.join("compatibilitytool.vdf").exists();
+    Ok(())
+}
 
-        if has_proton || has_vdf {
-            found.push(SteamProton {
-                name: name.clone(),
-                config_name: name.clone(),
-                path,
-                is_steam_proton: false,
-                is_experimental: false,
-            });
+/// Install a Proton build from a downloaded tarball into the user's
+/// compatibilitytools.d and return the installed tool.
+///
+/// The archive is verified, extracted into a staging directory next to the
+/// destination and renamed into place only once extraction succeeded, so a
+/// failure never leaves a half-written tool behind. An existing install of
+/// the same build is replaced.
+pub fn install_proton_ge(
+    archive: &Path,
+    expected_sha256: Option<&str>,
+) -> Result<SteamProton, Box<dyn Error>> {
+    verify_archive(archive, expected_sha256)?;
+
+    let steam_path = find_steam_path().ok_or("Steam installation not found")?;
+    let compat_dir = steam_path.join("compatibilitytools.d");
+    fs::create_dir_all(&compat_dir)?;
+
+    let staging = compat_dir.join(format!(".nak-extract-{}", std::process::id()));
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    fs::create_dir_all(&staging)?;
+
+    let result = extract_into(archive, &staging, &compat_dir);
+    let _ = fs::remove_dir_all(&staging);
+    let dest = result?;
+
+    let name = dest.file_name().unwrap_or_default().to_string_lossy().to_string();
+    log_info(&format!("Installed {} to {}", name, dest.display()));
+    Ok(SteamProton {
+        name: name.clone(),
+        config_name: name,
+        path: dest,
+        is_steam_proton: false,
+        is_experimental: false,
+    })
+}
+
+/// Extract `archive` into `staging` and move its single top-level tool
+/// directory into `compat_dir`
+fn extract_into(archive: &Path, staging: &Path, compat_dir: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let output = runtime_wrap::command_for("tar")
+        .arg("-xf")
+        .arg(archive)
+        .arg("-C")
+        .arg(staging)
+        .output()?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to extract {}: {}",
+            archive.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+
+    let entries: Vec<PathBuf> = fs::read_dir(staging)?
+        .flatten()
+        .map(|e| e.path())
+        .collect();
+    let [tool] = entries.as_slice() else {
+        return Err(format!(
+            "{} should contain a single tool directory, found {} entries",
+            archive.display(),
+            entries.len()
+        )
+        .into());
+    };
+    if !tool.join("proton").exists() && !tool.join("compatibilitytool.vdf").exists() {
+        return Err(format!("{} does not contain a Proton build", archive.display()).into());
+    }
+
+    let name = tool.file_name().ok_or("Invalid tool directory name")?;
+    let dest = compat_dir.join(name);
+    if dest.exists() {
+        let old = compat_dir.join(format!(".nak-old-{}", name.to_string_lossy()));
+        fs::rename(&dest, &old)?;
+        if let Err(e) = fs::rename(tool, &dest) {
+            let _ = fs::rename(&old, &dest);
+            return Err(e.into());
         }
+        let _ = fs::remove_dir_all(&old);
+    } else {
+        fs::rename(tool, &dest)?;
     }
+    Ok(dest)
+}
 
-    found
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const XZ_MAGIC: &[u8] = &[0xfd, b'7', b'z', b'X', b'Z', 0x00];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+const TAR_BLOCK: usize = 512;
+
+/// Whether a 512-byte block is a tar header with a valid checksum
+fn is_tar_header(block: &[u8; TAR_BLOCK]) -> bool {
+    // Checksum field (148..156) is octal, computed with itself as spaces
+    let field = String::from_utf8_lossy(&block[148..156]);
+    let Ok(stored) = u32::from_str_radix(field.trim_matches(|c: char| c == '\0' || c == ' '), 8) else {
+        return false;
+    };
+    let sum: u32 = block
+        .iter()
+        .enumerate()
+        .map(|(i, &b)| if (148..156).contains(&i) { b' ' as u32 } else { b as u32 })
+        .sum();
+    sum == stored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(name: &str) -> SteamProton {
+        SteamProton {
+            name: name.to_string(),
+            config_name: name.to_string(),
+            path: PathBuf::from("/tmp").join(name),
+            is_steam_proton: false,
+            is_experimental: false,
+        }
+    }
+
+    #[test]
+    fn test_group_duplicate_tools() {
+        assert_eq!(
+            tool_family("GE-Proton9-20"),
+            Some(("ge-proton".to_string(), vec![9, 20]))
+        );
+        assert_eq!(
+            tool_family("proton-cachyos-10.0-20250101-slr"),
+            Some(("proton-cachyos-slr".to_string(), vec![10, 0, 20250101]))
+        );
+        assert_eq!(tool_family("Luxtorpeda"), None);
+
+        let groups = group_duplicate_tools(vec![
+            tool("GE-Proton9-20"),
+            tool("Luxtorpeda"),
+            tool("GE-Proton10-3"),
+            tool("GE-Proton9-7"),
+            tool("proton-cachyos-10.0-20250101-slr"),
+        ]);
+        assert_eq!(groups.len(), 1);
+        let names: Vec<&str> = groups[0].iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["GE-Proton10-3", "GE-Proton9-20", "GE-Proton9-7"]);
+    }
+    #[test]
+    fn test_parse_compat_tools() {
+        let content = r#"
+"compatibilitytools"
+{
+  "compat_tools"
+  {
+    "GE-Proton10-3" // Internal name of this tool
+    {
+      "install_path" "."
+      "display_name" "GE-Proton10-3"
+      "from_oslist"  "windows"
+      "to_oslist"    "linux"
+    }
+    "proton_cachyos"
+    {
+      "install_path" "/opt/proton-cachyos"
+    }
+  }
+}
+"#;
+        assert_eq!(
+            parse_compat_tools(content),
+            [
+                CompatToolEntry {
+                    config_name: "GE-Proton10-3".to_string(),
+                    display_name: Some("GE-Proton10-3".to_string()),
+                    install_path: ".".to_string(),
+                },
+                CompatToolEntry {
+                    config_name: "proton_cachyos".to_string(),
+                    display_name: None,
+                    install_path: "/opt/proton-cachyos".to_string(),
+                },
+            ]
+        );
+        assert!(parse_compat_tools("\"compatibilitytools\" {}").is_empty());
+    }
+    #[test]
+    fn test_is_tar_header() {
+        let mut block = [0u8; TAR_BLOCK];
+        block[..13].copy_from_slice(b"GE-Proton10-3");
+        block[257..263].copy_from_slice(b"ustar\0");
+        let sum: u32 = block.iter().map(|&b| b as u32).sum::<u32>() + 8 * b' ' as u32;
+        block[148..155].copy_from_slice(format!("{:06o}\0", sum).as_bytes());
+        block[155] = b' ';
+        assert!(is_tar_header(&block));
+
+        block[0] = b'X';
+        assert!(!is_tar_header(&block));
+        assert!(!is_tar_header(&[0u8; TAR_BLOCK]));
+    }
 }
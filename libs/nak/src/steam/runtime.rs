@@ -0,0 +1,111 @@
+//! Steam Linux Runtime (pressure-vessel) detection
+//!
+//! Enumerates the installed `SteamLinuxRuntime*` tools across all Steam
+//! libraries and reads their versions from `VERSIONS.txt`. Read-only.
+
+use std::fs;
+use std::path::PathBuf;
+
+use crate::game_finder::find_library_folders;
+
+/// An installed Steam Linux Runtime tool
+#[derive(Debug, Clone)]
+pub struct RuntimeInfo {
+    /// Tool directory name (e.g. "SteamLinuxRuntime_sniper")
+    pub name: String,
+    /// Runtime codename (e.g. "sniper", "soldier", "scout")
+    pub codename: String,
+    pub path: PathBuf,
+    /// Runtime version (e.g. "0.20240806.98232")
+    pub version: Option<String>,
+    /// Bundled pressure-vessel version
+    pub pressure_vessel_version: Option<String>,
+}
+
+/// List installed Steam Linux Runtime tools across all libraries.
+pub fn runtime_versions() -> Vec<RuntimeInfo> {
+    let mut runtimes: Vec<RuntimeInfo> = Vec::new();
+
+    for library in find_library_folders() {
+        let Ok(entries) = fs::read_dir(library.join("steamapps/common")) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !name.starts_with("SteamLinuxRuntime") || runtimes.iter().any(|r| r.name == name) {
+                continue;
+            }
+
+            // The unsuffixed tool is the scout LD_LIBRARY_PATH runtime
+            let codename = name
+                .strip_prefix("SteamLinuxRuntime_")
+                .unwrap_or("scout")
+                .to_string();
+
+            let path = entry.path();
+            let versions = fs::read_to_string(path.join("VERSIONS.txt")).unwrap_or_default();
+            let (version, pressure_vessel_version) = parse_versions_txt(&versions, &codename);
+
+            runtimes.push(RuntimeInfo {
+                name,
+                codename,
+                path,
+                version,
+                pressure_vessel_version,
+            });
+        }
+    }
+
+    runtimes.sort_by(|a, b| a.name.cmp(&b.name));
+    runtimes
+}
+
+/// Parse a runtime's VERSIONS.txt into (runtime version, pressure-vessel version).
+///
+/// Rows are tab-separated `name version runtime comment`; the runtime's own
+/// row is named after its codename, falling back to the `depot` row.
+fn parse_versions_txt(content: &str, codename: &str) -> (Option<String>, Option<String>) {
+    let rows: Vec<(&str, &str)> = content
+        .lines()
+        .filter(|l| !l.starts_with('#'))
+        .filter_map(|l| {
+            let mut cols = l.split_whitespace();
+            Some((cols.next()?, cols.next()?))
+        })
+        .collect();
+
+    let find = |name: &str| {
+        rows.iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| v.to_string())
+    };
+
+    (
+        find(codename).or_else(|| find("depot")),
+        find("pressure-vessel"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_versions_txt() {
+        let content = "\
+#Name\tVersion\t(Runtime)\tComment
+depot\t0.20240806.98232\t-\t# steamapps/common/SteamLinuxRuntime_sniper
+pressure-vessel\t0.20240708.0\tscout\t# pressure-vessel/
+sniper\t0.20240806.98230\tsniper\t# sniper_platform_0.20240806.98230/
+";
+        assert_eq!(
+            parse_versions_txt(content, "sniper"),
+            (Some("0.20240806.98230".to_string()), Some("0.20240708.0".to_string()))
+        );
+        assert_eq!(
+            parse_versions_txt(content, "soldier").0.as_deref(),
+            Some("0.20240806.98232")
+        );
+    }
+}
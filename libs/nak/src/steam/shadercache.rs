@@ -0,0 +1,198 @@
+//! Steam shader cache management
+//!
+//! Reports and cleans per-game shader caches in `steamapps/shadercache/<appid>`
+//! across all Steam libraries. Only the shadercache directory is ever removed.
+//!
+//! Fossilize (Vulkan pipeline) caches can be cleared on their own: the
+//! pipeline subdirectories of shadercache plus any `.foz` archives under the
+//! game's compatdata. Nothing else is touched.
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::game_finder::find_library_folders;
+use crate::logging::log_info;
+use crate::utils::dir_size;
+
+/// Shader cache size per app ID, summed across libraries, largest first.
+pub fn shadercache_sizes() -> Vec<(u32, u64)> {
+    let mut sizes: Vec<(u32, u64)> = Vec::new();
+
+    for library in find_library_folders() {
+        let Ok(entries) = fs::read_dir(library.join("steamapps/shadercache")) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let Ok(app_id) = entry.file_name().to_string_lossy().parse::<u32>() else {
+                continue;
+            };
+            if !entry.path().is_dir() {
+                continue;
+            }
+
+            let size = dir_size(&entry.path());
+            match sizes.iter_mut().find(|(id, _)| *id == app_id) {
+                Some((_, total)) => *total += size,
+                None => sizes.push((app_id, size)),
+            }
+        }
+    }
+
+    sizes.sort_by_key(|&(_, size)| std::cmp::Reverse(size));
+    sizes
+}
+
+/// Remove one game's shader cache from every library.
+///
+/// Returns the number of bytes freed.
+pub fn clear_shadercache(app_id: u32) -> Result<u64, Box<dyn Error>> {
+    let mut freed = 0u64;
+
+    for library in find_library_folders() {
+        let cache_dir = library
+            .join("steamapps/shadercache")
+            .join(app_id.to_string());
+        freed += remove_cache_dir(&cache_dir)?;
+    }
+
+    log_info(&format!(
+        "Cleared shader cache for app {} ({} bytes freed)",
+        app_id, freed
+    ));
+    Ok(freed)
+}
+
+/// Remove one `shadercache/<appid>` entry, returning the bytes freed
+fn remove_cache_dir(cache_dir: &Path) -> Result<u64, Box<dyn Error>> {
+    let Ok(meta) = fs::symlink_metadata(cache_dir) else {
+        return Ok(0);
+    };
+
+    if meta.is_dir() {
+        let size = dir_size(cache_dir);
+        fs::remove_dir_all(cache_dir)?;
+        Ok(size)
+    } else {
+        // Don't follow a symlinked cache dir - just drop the link
+        fs::remove_file(cache_dir)?;
+        Ok(0)
+    }
+}
+
+/// Pipeline cache subdirectories of `shadercache/<appid>` (DXVK's state
+/// cache is managed separately)
+const PIPELINE_CACHE_DIRS: &[&str] = &[
+    "fozpipelinesv6",
+    "fozmediav1",
+    "mesa_shader_cache",
+    "mesa_shader_cache_sf",
+    "mesa_shader_cache_db",
+    "nvidiav1",
+];
+
+/// How deep to look for `.foz` files inside compatdata
+const FOZ_SEARCH_DEPTH: usize = 6;
+
+/// Total size of a game's Fossilize/Vulkan pipeline caches across libraries.
+pub fn fossilize_cache_size(app_id: u32) -> u64 {
+    fossilize_cache_entries(app_id)
+        .iter()
+        .map(|path| {
+            if path.is_dir() {
+                dir_size(path)
+            } else {
+                fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+            }
+        })
+        .sum()
+}
+
+/// Remove a game's Fossilize/Vulkan pipeline caches from every library.
+///
+/// Returns the number of bytes freed.
+pub fn clear_fossilize_cache(app_id: u32) -> Result<u64, Box<dyn Error>> {
+    let mut freed = 0u64;
+
+    for path in fossilize_cache_entries(app_id) {
+        let meta = fs::symlink_metadata(&path)?;
+        if meta.is_dir() {
+            freed += dir_size(&path);
+            fs::remove_dir_all(&path)?;
+        } else {
+            freed += meta.len();
+            fs::remove_file(&path)?;
+        }
+    }
+
+    log_info(&format!(
+        "Cleared Fossilize cache for app {} ({} bytes freed)",
+        app_id, freed
+    ));
+    Ok(freed)
+}
+
+/// Pipeline cache directories and `.foz` files belonging to one app
+fn fossilize_cache_entries(app_id: u32) -> Vec<PathBuf> {
+    let mut entries = Vec::new();
+
+    for library in find_library_folders() {
+        let steamapps = library.join("steamapps");
+
+        let cache_dir = steamapps.join("shadercache").join(app_id.to_string());
+        for name in PIPELINE_CACHE_DIRS {
+            let dir = cache_dir.join(name);
+            // Never follow a symlinked cache dir out of shadercache
+            if fs::symlink_metadata(&dir).is_ok_and(|m| m.is_dir()) {
+                entries.push(dir);
+            }
+        }
+
+        entries.extend(foz_files(&steamapps.join("compatdata").join(app_id.to_string())));
+    }
+
+    entries
+}
+
+/// `.foz` archives under a directory (symlinks are not followed)
+fn foz_files(dir: &Path) -> Vec<PathBuf> {
+    walkdir::WalkDir::new(dir)
+        .max_depth(FOZ_SEARCH_DEPTH)
+        .into_iter()
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("foz")))
+        .map(|e| e.into_path())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempDir;
+
+    #[test]
+    fn test_remove_cache_dir() {
+        let tmp = TempDir::new("shadercache");
+        let shadercache = tmp.path().join("steamapps/shadercache");
+
+        let real = shadercache.join("489830");
+        fs::create_dir_all(real.join("fozpipelinesv6")).unwrap();
+        fs::write(real.join("fozpipelinesv6/steamapprun_pipeline_cache.foz"), b"1234").unwrap();
+        assert_eq!(remove_cache_dir(&real).unwrap(), 4);
+        assert!(!real.exists());
+
+        // A symlinked cache dir only loses the link, its target survives
+        let target = tmp.path().join("elsewhere");
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("keep.foz"), b"data").unwrap();
+        let link = shadercache.join("377160");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        assert_eq!(remove_cache_dir(&link).unwrap(), 0);
+        assert!(fs::symlink_metadata(&link).is_err());
+        assert!(target.join("keep.foz").exists());
+
+        assert_eq!(remove_cache_dir(&shadercache.join("22380")).unwrap(), 0);
+    }
+}
@@ -0,0 +1,117 @@
+//! Steam Play (Proton) settings
+//!
+//! Steam stores per-app compatibility tool choices in config.vdf's
+//! `CompatToolMapping`. App ID "0" is the global entry written by
+//! "Enable Steam Play for all other titles", naming the default tool.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use super::find_steam_path;
+use crate::game_finder::vdf::parse_vdf;
+
+/// Global Steam Play state
+#[derive(Debug, Clone, Default)]
+pub struct SteamPlayStatus {
+    /// "Enable Steam Play for all other titles" is on
+    pub enabled_for_all: bool,
+    /// Internal name of the default tool (e.g. "proton_experimental")
+    pub default_tool: Option<String>,
+}
+
+/// Read whether Steam Play is enabled for all titles.
+///
+/// Reports disabled when Steam or its config.vdf can't be found.
+#[must_use]
+pub fn steam_play_enabled() -> SteamPlayStatus {
+    let Some(steam_path) = find_steam_path() else {
+        return SteamPlayStatus::default();
+    };
+
+    let default_tool = read_compat_tool_mapping(&steam_path).remove("0");
+    SteamPlayStatus {
+        enabled_for_all: default_tool.is_some(),
+        default_tool,
+    }
+}
+
+/// Per-app compatibility tool names from config.vdf's CompatToolMapping
+/// (app ID -> tool name; entries with an empty name are skipped)
+pub(crate) fn read_compat_tool_mapping(steam_path: &Path) -> HashMap<String, String> {
+    fs::read_to_string(steam_path.join("config/config.vdf"))
+        .map(|content| parse_compat_tool_mapping(&content))
+        .unwrap_or_default()
+}
+
+/// CompatToolMapping entries from config.vdf content
+fn parse_compat_tool_mapping(config: &str) -> HashMap<String, String> {
+    let Some(root) = parse_vdf(config) else {
+        return HashMap::new();
+    };
+
+    let mapping = ["InstallConfigStore", "Software", "Valve", "Steam", "CompatToolMapping"]
+        .iter()
+        .try_fold(&root, |node, key| node.get_ignore_case(key))
+        .and_then(|m| m.as_object());
+    let Some(mapping) = mapping else {
+        return HashMap::new();
+    };
+
+    mapping
+        .iter()
+        .filter_map(|(app_id, entry)| {
+            let name = entry.get_str("name")?;
+            (!name.is_empty()).then(|| (app_id.clone(), name.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_compat_tool_mapping() {
+        let content = r#"
+"InstallConfigStore"
+{
+    "Software"
+    {
+        "Valve"
+        {
+            "Steam"
+            {
+                "CompatToolMapping"
+                {
+                    "0"
+                    {
+                        "name"      "proton_experimental"
+                        "config"        ""
+                        "priority"      "75"
+                    }
+                    "489830"
+                    {
+                        "name"      "GE-Proton9-20"
+                        "config"        ""
+                        "priority"      "250"
+                    }
+                    "377160"
+                    {
+                        "name"      ""
+                        "config"        ""
+                        "priority"      "250"
+                    }
+                }
+            }
+        }
+    }
+}
+"#;
+        let mapping = parse_compat_tool_mapping(content);
+        assert_eq!(mapping.len(), 2);
+        assert_eq!(mapping.get("0").map(String::as_str), Some("proton_experimental"));
+        assert_eq!(mapping.get("489830").map(String::as_str), Some("GE-Proton9-20"));
+        assert!(parse_compat_tool_mapping("\"InstallConfigStore\" {}").is_empty());
+    }
+}
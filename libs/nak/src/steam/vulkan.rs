@@ -0,0 +1,172 @@
+//! Vulkan GPU enumeration
+//!
+//! Lists the Vulkan-capable GPUs (via `vulkaninfo --summary`) so multi-GPU
+//! users can pin DXVK to a specific device through launch options.
+
+use crate::runtime_wrap;
+
+const VENDOR_NVIDIA: u32 = 0x10de;
+
+/// Kind of Vulkan physical device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VulkanDeviceType {
+    Discrete,
+    Integrated,
+    Virtual,
+    Other,
+}
+
+/// A Vulkan-capable GPU
+#[derive(Debug, Clone)]
+pub struct VulkanDevice {
+    /// Device name as Vulkan reports it (what DXVK_FILTER_DEVICE_NAME matches)
+    pub name: String,
+    pub vendor_id: u32,
+    pub device_id: u32,
+    pub device_type: VulkanDeviceType,
+}
+
+impl VulkanDevice {
+    /// Environment variables that make DXVK (and GL fallbacks) use this device
+    pub fn selection_env(&self) -> Vec<(&'static str, String)> {
+        let mut env = vec![("DXVK_FILTER_DEVICE_NAME", self.name.clone())];
+
+        if self.vendor_id == VENDOR_NVIDIA {
+            // NVIDIA PRIME render offload
+            env.push(("__NV_PRIME_RENDER_OFFLOAD", "1".to_string()));
+            env.push(("__VK_LAYER_NV_optimus", "NVIDIA_only".to_string()));
+        } else {
+            let id = format!("{:04x}:{:04x}", self.vendor_id, self.device_id);
+            env.push(("MESA_VK_DEVICE_SELECT", id.clone()));
+            env.push(("DRI_PRIME", id));
+        }
+
+        env
+    }
+}
+
+/// List Vulkan-capable GPUs, discrete first. CPU (software) devices are skipped.
+///
+/// Returns an empty list if `vulkaninfo` is not installed.
+pub fn vulkan_devices() -> Vec<VulkanDevice> {
+    let Ok(output) = runtime_wrap::command_for("vulkaninfo")
+        .arg("--summary")
+        .output()
+    else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+
+    let mut devices = parse_vulkaninfo_summary(&String::from_utf8_lossy(&output.stdout));
+    devices.sort_by_key(|d| d.device_type != VulkanDeviceType::Discrete);
+    devices
+}
+
+/// Fields collected for one `GPUn:` block
+#[derive(Default)]
+struct DeviceBlock {
+    name: Option<String>,
+    vendor_id: u32,
+    device_id: u32,
+    device_type: Option<VulkanDeviceType>,
+}
+
+/// Parse the `GPUn:` blocks of `vulkaninfo --summary`
+fn parse_vulkaninfo_summary(output: &str) -> Vec<VulkanDevice> {
+    let mut blocks: Vec<DeviceBlock> = Vec::new();
+
+    for line in output.lines() {
+        let line = line.trim();
+        if line.starts_with("GPU") && line.ends_with(':') {
+            blocks.push(DeviceBlock::default());
+            continue;
+        }
+
+        let Some(block) = blocks.last_mut() else {
+            continue;
+        };
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+
+        match key.trim() {
+            "deviceName" => block.name = Some(value.to_string()),
+            "vendorID" => block.vendor_id = parse_hex(value),
+            "deviceID" => block.device_id = parse_hex(value),
+            "deviceType" => {
+                block.device_type = match value {
+                    "PHYSICAL_DEVICE_TYPE_DISCRETE_GPU" => Some(VulkanDeviceType::Discrete),
+                    "PHYSICAL_DEVICE_TYPE_INTEGRATED_GPU" => Some(VulkanDeviceType::Integrated),
+                    "PHYSICAL_DEVICE_TYPE_VIRTUAL_GPU" => Some(VulkanDeviceType::Virtual),
+                    // Software rasterizers are no use for DXVK
+                    "PHYSICAL_DEVICE_TYPE_CPU" => None,
+                    _ => Some(VulkanDeviceType::Other),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut devices: Vec<VulkanDevice> = Vec::new();
+    for block in blocks {
+        let (Some(name), Some(device_type)) = (block.name, block.device_type) else {
+            continue;
+        };
+        // The same GPU shows up once per driver on some setups
+        if devices.iter().any(|d| d.name == name && d.device_id == block.device_id) {
+            continue;
+        }
+        devices.push(VulkanDevice {
+            name,
+            vendor_id: block.vendor_id,
+            device_id: block.device_id,
+            device_type,
+        });
+    }
+    devices
+}
+
+fn parse_hex(value: &str) -> u32 {
+    u32::from_str_radix(value.trim_start_matches("0x"), 16).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vulkaninfo_summary() {
+        let output = "\
+Devices:
+========
+GPU0:
+\tapiVersion         = 1.3.274
+\tvendorID           = 0x1002
+\tdeviceID           = 0x1681
+\tdeviceType         = PHYSICAL_DEVICE_TYPE_INTEGRATED_GPU
+\tdeviceName         = AMD Radeon 680M (RADV REMBRANDT)
+GPU1:
+\tvendorID           = 0x10de
+\tdeviceID           = 0x2520
+\tdeviceType         = PHYSICAL_DEVICE_TYPE_DISCRETE_GPU
+\tdeviceName         = NVIDIA GeForce RTX 3060 Laptop GPU
+GPU2:
+\tvendorID           = 0x10005
+\tdeviceID           = 0x0000
+\tdeviceType         = PHYSICAL_DEVICE_TYPE_CPU
+\tdeviceName         = llvmpipe (LLVM 17.0.6, 256 bits)
+";
+        let devices = parse_vulkaninfo_summary(output);
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].name, "AMD Radeon 680M (RADV REMBRANDT)");
+        assert_eq!(devices[0].device_type, VulkanDeviceType::Integrated);
+        assert_eq!(devices[1].vendor_id, 0x10de);
+        assert_eq!(devices[1].device_type, VulkanDeviceType::Discrete);
+        assert!(devices[0]
+            .selection_env()
+            .contains(&("DRI_PRIME", "1002:1681".to_string())));
+    }
+}
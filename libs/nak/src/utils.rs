@@ -2,7 +2,8 @@
 
 use std::error::Error;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Download a file from URL to the specified path
 pub fn download_file(url: &str, path: &Path) -> Result<(), Box<dyn Error>> {
@@ -17,3 +18,229 @@ pub fn download_file(url: &str, path: &Path) -> Result<(), Box<dyn Error>> {
     std::io::copy(&mut reader, &mut file)?;
     Ok(())
 }
+
+/// Free space (in bytes) available to the current user on the filesystem
+/// containing `path`. Returns None if the path doesn't exist.
+pub fn free_space(path: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+
+    // SAFETY: c_path is a valid NUL-terminated string and stat is a valid out-pointer
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+/// Whether two paths live on the same filesystem, comparing the device ids
+/// of their nearest existing ancestors. None if either can't be resolved.
+pub fn same_filesystem(a: &Path, b: &Path) -> Option<bool> {
+    use std::os::unix::fs::MetadataExt;
+
+    let device = |path: &Path| {
+        path.ancestors()
+            .find_map(|p| fs::metadata(p).ok())
+            .map(|m| m.dev())
+    };
+    Some(device(a)? == device(b)?)
+}
+
+/// Check that a path is a directory with at least one entry
+pub fn is_populated_dir(path: &Path) -> bool {
+    fs::read_dir(path).is_ok_and(|mut entries| entries.next().is_some())
+}
+
+/// Total size in bytes of all files under `path` (symlinks are not followed).
+pub fn dir_size(path: &Path) -> u64 {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Like [`dir_size`], but descends at most `max_depth` levels and gives up
+/// (returning None) as soon as `cancel` is set.
+pub fn dir_size_bounded(path: &Path, max_depth: usize, cancel: &AtomicBool) -> Option<u64> {
+    let mut total = 0u64;
+    for entry in walkdir::WalkDir::new(path).max_depth(max_depth).into_iter().flatten() {
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        if entry.file_type().is_file() {
+            total += entry.metadata().map(|m| m.len()).unwrap_or(0);
+        }
+    }
+    Some(total)
+}
+
+/// Mount points listed in /proc/mounts, longest first.
+pub fn mount_points() -> Vec<PathBuf> {
+    fs::read_to_string("/proc/mounts")
+        .map(|content| parse_mounts(&content))
+        .unwrap_or_default()
+}
+
+/// Mount point column of /proc/mounts content (octal escapes decoded),
+/// longest first so the first prefix match is the innermost mount
+fn parse_mounts(content: &str) -> Vec<PathBuf> {
+    let mut mounts: Vec<PathBuf> = content
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(|field| PathBuf::from(decode_mount_escapes(field)))
+        .collect();
+    mounts.sort_by_key(|m| std::cmp::Reverse(m.as_os_str().len()));
+    mounts.dedup();
+    mounts
+}
+
+/// Decode the `\040`-style escapes the kernel uses for spaces etc.
+fn decode_mount_escapes(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 3 < bytes.len() {
+            if let Ok(byte) = u8::from_str_radix(&field[i + 1..i + 4], 8) {
+                out.push(byte);
+                i += 4;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Mount point holding `path` (symlinks resolved), or None if the path
+/// doesn't exist.
+pub fn mount_point_of(path: &Path, mounts: &[PathBuf]) -> Option<PathBuf> {
+    let canonical = path.canonicalize().ok()?;
+    mounts.iter().find(|m| canonical.starts_with(m)).cloned()
+}
+
+/// Group items by the mount point of their path, sorted by mount point.
+///
+/// Items whose path can't be resolved are grouped last under an empty path.
+pub fn group_by_mount<T>(
+    items: impl IntoIterator<Item = T>,
+    path_of: impl Fn(&T) -> &Path,
+) -> Vec<(PathBuf, Vec<T>)> {
+    let mounts = mount_points();
+    let mut groups: Vec<(PathBuf, Vec<T>)> = Vec::new();
+
+    for item in items {
+        let mount = mount_point_of(path_of(&item), &mounts).unwrap_or_default();
+        match groups.iter_mut().find(|(m, _)| *m == mount) {
+            Some((_, members)) => members.push(item),
+            None => groups.push((mount, vec![item])),
+        }
+    }
+
+    // Empty (unknown) sorts first by path; move it to the end instead
+    groups.sort_by(|(a, _), (b, _)| {
+        (a.as_os_str().is_empty(), a).cmp(&(b.as_os_str().is_empty(), b))
+    });
+    groups
+}
+
+/// Group items whose paths are the same directory or nested inside one
+/// another, comparing canonicalized paths.
+///
+/// Each group is keyed by its outermost directory; only groups with more
+/// than one member are returned, sorted by directory. Empty paths are skipped.
+pub fn group_overlapping_paths<T>(
+    items: impl IntoIterator<Item = T>,
+    path_of: impl Fn(&T) -> &Path,
+) -> Vec<(PathBuf, Vec<T>)> {
+    let mut resolved: Vec<(PathBuf, T)> = items
+        .into_iter()
+        .filter(|item| !path_of(item).as_os_str().is_empty())
+        .map(|item| {
+            let path = path_of(&item);
+            (fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()), item)
+        })
+        .collect();
+    // Component-wise ordering puts every directory right before its children
+    resolved.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut groups: Vec<(PathBuf, Vec<T>)> = Vec::new();
+    for (path, item) in resolved {
+        match groups.last_mut() {
+            Some((root, members)) if path.starts_with(&*root) => members.push(item),
+            _ => groups.push((path, vec![item])),
+        }
+    }
+    groups.retain(|(_, members)| members.len() > 1);
+    groups
+}
+
+/// A fresh directory under the system temp dir for a test, removed again
+/// when dropped (also when the test fails)
+#[cfg(test)]
+pub(crate) struct TempDir(PathBuf);
+
+#[cfg(test)]
+impl TempDir {
+    pub(crate) fn new(name: &str) -> Self {
+        static COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!("nak_{}_{}_{}", name, std::process::id(), n));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mounts() {
+        let content = "\
+/dev/nvme0n1p2 / btrfs rw,relatime 0 0
+/dev/sda1 /run/media/u/Game\\040Drive ext4 rw 0 0
+/dev/nvme0n1p2 /home btrfs rw,relatime 0 0
+";
+        let mounts = parse_mounts(content);
+        assert_eq!(mounts[0], PathBuf::from("/run/media/u/Game Drive"));
+        assert_eq!(mounts.last(), Some(&PathBuf::from("/")));
+    }
+    #[test]
+    fn test_group_overlapping_paths() {
+        let paths = [
+            "/nonexistent/common/Skyrim Special Edition",
+            "/nonexistent/common/Fallout 4",
+            "/nonexistent/common/Skyrim Special Edition/Anniversary",
+            "",
+            "/nonexistent/common/Skyrim Special Edition",
+            "/nonexistent/common/Skyrim",
+        ];
+        let groups = group_overlapping_paths(paths.iter(), |p| Path::new(p));
+        assert_eq!(groups.len(), 1);
+        assert_eq!(
+            groups[0].0,
+            PathBuf::from("/nonexistent/common/Skyrim Special Edition")
+        );
+        assert_eq!(groups[0].1.len(), 3);
+    }
+}
@@ -12,6 +12,12 @@ extern "C" {
  * Tier 1: Game Detection
  * ======================================================================== */
 
+/** List of owned strings (free with nak_string_list_free) */
+typedef struct {
+    char **items;
+    size_t count;
+} NakStringList;
+
 /** A detected game installation */
 typedef struct {
     char *name;
@@ -24,6 +30,19 @@ typedef struct {
     char *appdata_roaming_folder;  /* NULL if not applicable */
     char *registry_path;           /* NULL if not applicable */
     char *registry_value;          /* NULL if not applicable */
+    int64_t prefix_free_space;     /* bytes free on the prefix filesystem, -1 if unknown */
+    int prefix_shared;             /* 1 if shared with other games or redirected */
+    char *my_games_path;           /* resolved path in the prefix, NULL if not applicable */
+    char *appdata_local_path;      /* resolved path in the prefix, NULL if not applicable */
+    char *appdata_roaming_path;    /* resolved path in the prefix, NULL if not applicable */
+    char *bundled_runtime_path;    /* Proton/Wine shipped with the game, NULL if none;
+                                      accepted wherever a proton_path is */
+    int prefix_redirected;         /* 1 if set by STEAM_COMPAT_DATA_PATH in launch options */
+    int64_t prefix_created_at;     /* unix timestamp, -1 if unknown or no prefix */
+    int family_shared;             /* 1 if borrowed through Steam Family Sharing */
+    int app_type;                  /* 0 = game, 1 = tool, 2 = application, 3 = demo */
+    int install_state;             /* 0 = installed, 1 = update required,
+                                      2 = downloading, 3 = not installed */
 } NakGame;
 
 /** List of detected games */
@@ -33,14 +52,94 @@ typedef struct {
     size_t steam_count;
     size_t heroic_count;
     size_t bottles_count;
+    size_t lutris_count;
 } NakGameList;
 
-/** Detect all installed games across all launchers */
+/** Detect all installed games across all launchers.
+ *  Steam tools (Proton, Steam Linux Runtime, redistributables) are left out. */
 NakGameList nak_detect_all_games(void);
 
+/** Detect installed games, including Steam tools when include_tools != 0.
+ *  With tools included the scan bypasses the game cache.
+ *  Free with nak_game_list_free. */
+NakGameList nak_detect_all_games_with_tools(int include_tools);
+
+/** Run a full scan (ignoring the cache) and return it as a JSON object:
+ *  {"games": [...], "steam_count", "heroic_count", "bottles_count",
+ *  "lutris_count", "warnings": [...]}. Each game carries every field, with
+ *  "launcher" as {"kind": "steam"|"heroic"|"bottles"|"lutris", ...}.
+ *  Returns NULL on failure; otherwise free with nak_string_free. */
+char *nak_detect_all_games_json(void);
+
+/** Install size of one launcher's games */
+typedef struct {
+    char *launcher;         /* "steam", "heroic", "bottles" or "lutris" */
+    uint64_t bytes;
+    size_t unknown_count;   /* games whose launcher records no size */
+} NakLauncherSize;
+
+/** Install sizes per launcher */
+typedef struct {
+    NakLauncherSize *sizes;
+    size_t count;
+} NakLauncherSizeList;
+
+/** Total install size of detected games per launcher. Only launchers with
+ *  games are listed. */
+NakLauncherSizeList nak_size_by_launcher(void);
+
+/** Free a NakLauncherSizeList */
+void nak_launcher_size_list_free(NakLauncherSizeList list);
+
+/** Drop the cached game scan so the next detection call rescans.
+ *  Lists returned earlier stay valid and must still be freed with
+ *  nak_game_list_free. */
+void nak_invalidate_game_cache(void);
+
+/** Rescan on the next detection call once the cached scan is older than
+ *  `secs`. 0 (the default) keeps the scan until it is invalidated. */
+void nak_set_game_cache_ttl_secs(uint64_t secs);
+
+/** Extra folders scanned for GOG games installed outside Heroic
+ *  (goggame-*.info in the folder or one level down). */
+NakStringList nak_get_gog_library_roots(void);
+
+/** Replace the extra GOG library folders with `count` paths from `roots`.
+ *  Drops the cached game scan so the next detection picks them up. */
+void nak_set_gog_library_roots(const char *const *roots, size_t count);
+
+/** Rescan all launchers, replacing the cached scan, and return the fresh list.
+ *  Lists returned earlier stay valid and must still be freed with
+ *  nak_game_list_free. */
+NakGameList nak_refresh_game_cache(void);
+
+/** Detected games from one launcher: "steam", "heroic", "bottles" or "lutris".
+ *  Unknown launchers give an empty list. The per-launcher counts are still
+ *  those of the whole scan. Free with nak_game_list_free. */
+NakGameList nak_detect_games_by_launcher(const char *launcher);
+
+/** Detected games that have a Wine prefix. The per-launcher counts are
+ *  those of the returned games. Free with nak_game_list_free. */
+NakGameList nak_detect_games_with_prefixes(void);
+
 /** Free a NakGameList returned by nak_detect_all_games */
 void nak_game_list_free(NakGameList list);
 
+/** Look up one installed Steam game by App ID without a full detection scan.
+ *  Returns NULL if not installed. Free with nak_game_free. */
+NakGame *nak_find_game_by_app_id(const char *app_id);
+
+/** Free a NakGame returned by nak_find_game_by_app_id */
+void nak_game_free(NakGame *game);
+
+/** Folders inside a game's prefix, resolved at call time (the Documents
+ *  folder follows a user.reg redirection). Return NULL if the game has no
+ *  prefix or no such folder; otherwise free with nak_string_free. */
+char *nak_game_prefix_my_games_path(const NakGame *game);
+char *nak_game_prefix_appdata_local_path(const NakGame *game);
+char *nak_game_prefix_appdata_roaming_path(const NakGame *game);
+char *nak_game_prefix_documents_path(const NakGame *game);
+
 /** A known game definition (static data, do NOT free) */
 typedef struct {
     const char *name;
@@ -52,12 +151,94 @@ typedef struct {
     const char *registry_path;
     const char *registry_value;
     const char *steam_folder;
+    const char *needs_vcredist;          /* comma-separated winetricks verbs, NULL if none */
 } NakKnownGame;
 
 /** Get the list of all known games (static data, do NOT free).
  *  Returns pointer to array; writes count to *out_count. */
 const NakKnownGame *nak_get_known_games(size_t *out_count);
 
+/** Look up a known game by name (static data, do NOT free). Case,
+ *  punctuation and common abbreviations are ignored ("skyrim se" finds
+ *  "Skyrim Special Edition"). Returns a pointer into the
+ *  nak_get_known_games array, or NULL if no game matches. */
+const NakKnownGame *nak_find_known_game_by_name(const char *name);
+
+/** A detected prefix for a known game */
+typedef struct {
+    char *launcher;     /* display name string */
+    char *prefix_path;
+} NakKnownGamePrefix;
+
+/** List of prefixes for a known game */
+typedef struct {
+    NakKnownGamePrefix *prefixes;
+    size_t count;
+} NakKnownGamePrefixList;
+
+/** Every detected prefix (across launchers) for the known game with this
+ *  Steam app id or name, e.g. when it is owned on both Steam and GOG.
+ *  Empty if the game isn't known. */
+NakKnownGamePrefixList nak_prefixes_for_known_game(const char *app_id_or_name);
+
+/** Free a NakKnownGamePrefixList */
+void nak_known_game_prefix_list_free(NakKnownGamePrefixList list);
+
+/** Detected games on one mount point */
+typedef struct {
+    char *mount_point;      /* NULL for games whose install path is missing */
+    NakStringList app_ids;
+    NakStringList names;
+} NakMountGroup;
+
+/** Detected games grouped by mount point */
+typedef struct {
+    NakMountGroup *groups;
+    size_t count;
+} NakMountGroupList;
+
+/** Group detected games by the mount point of their install directory,
+ *  sorted by mount point (unknown last). */
+NakMountGroupList nak_games_by_mount(void);
+
+/** Free a NakMountGroupList */
+void nak_mount_group_list_free(NakMountGroupList list);
+
+/** Detected games sharing one install directory */
+typedef struct {
+    char *install_dir;      /* outermost directory of the group */
+    NakStringList app_ids;
+    NakStringList names;
+} NakInstallDirGroup;
+
+/** Groups of detected games sharing an install directory */
+typedef struct {
+    NakInstallDirGroup *groups;
+    size_t count;
+} NakInstallDirGroupList;
+
+/** Group detected games whose install directories are the same or nested
+ *  inside one another (bundles, region variants). Only groups of two or more,
+ *  sorted by directory. */
+NakInstallDirGroupList nak_shared_install_dirs(void);
+
+/** Free a NakInstallDirGroupList */
+void nak_install_dir_group_list_free(NakInstallDirGroupList list);
+
+/** Find a Steam game's Wine prefix, honouring a STEAM_COMPAT_DATA_PATH
+ *  override in its launch options. Writes 1 to *out_redirected (may be NULL)
+ *  if the override was used.
+ *  Returns newly allocated string (free with nak_string_free), or NULL. */
+char *nak_find_game_prefix_path(const char *app_id, int *out_redirected);
+
+/** Locally stored compatibility note for an app id (never hits the network).
+ *  Returns newly allocated string (free with nak_string_free), or NULL. */
+char *nak_compat_note_for(const char *app_id);
+
+/** Store a compatibility note for an app id. NULL or "" removes it.
+ *  Returns NULL on success, or error message (free with nak_string_free). */
+char *nak_set_compat_note(const char *app_id, const char *note);
+
 /* ========================================================================
  * Tier 2: Proton Detection
  * ======================================================================== */
@@ -83,6 +264,79 @@ NakProtonList nak_find_steam_protons(void);
 /** Free a NakProtonList */
 void nak_proton_list_free(NakProtonList list);
 
+/** Check that a Proton install is intact: the proton script plus an
+ *  executable wine and wineserver under files/bin or dist/bin.
+ *  Returns NULL if usable, or an error saying what is missing or which
+ *  folder to select instead (free with nak_string_free). */
+char *nak_verify_proton(const char *proton_path);
+
+/** Custom compatibility tools of the same family */
+typedef struct {
+    NakProtonList protons;  /* newest first */
+} NakProtonGroup;
+
+/** List of duplicate tool groups */
+typedef struct {
+    NakProtonGroup *groups;
+    size_t count;
+} NakProtonGroupList;
+
+/**
+ * Group custom tools in compatibilitytools.d that are builds of the same
+ * family (e.g. several GE-Proton releases). Only groups with more than one
+ * member are returned.
+ */
+NakProtonGroupList nak_find_duplicate_tools(void);
+
+/** Free a NakProtonGroupList */
+void nak_proton_group_list_free(NakProtonGroupList list);
+
+/** A Wine/Proton runner downloaded by Heroic */
+typedef struct {
+    char *name;
+    char *runner_type;  /* e.g. "Proton-GE", "Wine-GE" */
+    char *path;
+} NakHeroicRunner;
+
+/** List of Heroic runners */
+typedef struct {
+    NakHeroicRunner *runners;
+    size_t count;
+} NakHeroicRunnerList;
+
+/** List the Wine/Proton runners managed by Heroic */
+NakHeroicRunnerList nak_list_heroic_runners(void);
+
+/** Free a NakHeroicRunnerList */
+void nak_heroic_runner_list_free(NakHeroicRunnerList list);
+
+/** An installed Steam Linux Runtime tool */
+typedef struct {
+    char *name;                     /* e.g. "SteamLinuxRuntime_sniper" */
+    char *codename;                 /* e.g. "sniper" */
+    char *path;
+    char *version;                  /* NULL if unknown */
+    char *pressure_vessel_version;  /* NULL if unknown */
+} NakRuntimeInfo;
+
+/** List of Steam Linux Runtime tools */
+typedef struct {
+    NakRuntimeInfo *runtimes;
+    size_t count;
+} NakRuntimeInfoList;
+
+/** List installed Steam Linux Runtime (pressure-vessel) tools and their versions */
+NakRuntimeInfoList nak_runtime_versions(void);
+
+/** Free a NakRuntimeInfoList */
+void nak_runtime_info_list_free(NakRuntimeInfoList list);
+
+/** Verify a downloaded Proton tarball before extraction: its SHA-256 (hex;
+ *  skipped when expected_sha256 is NULL or empty) and that it is a
+ *  gzip/xz/zstd/plain tar.
+ *  Returns NULL if the archive is usable, or error message (free with nak_string_free). */
+char *nak_verify_proton_archive(const char *archive_path, const char *expected_sha256);
+
 /* ========================================================================
  * Tier 3: Steam Paths
  * ======================================================================== */
@@ -91,6 +345,212 @@ void nak_proton_list_free(NakProtonList list);
  *  Returns newly allocated string (free with nak_string_free), or NULL. */
 char *nak_find_steam_path(void);
 
+/** Whether the Steam client is running: 1 yes, 0 no. Config writers refuse
+ *  to touch Steam's VDF files while it is. */
+int nak_steam_is_running(void);
+
+/** The Steam account (userdata folder name) chosen in NaK's config.
+ *  Returns newly allocated string (free with nak_string_free), or NULL when
+ *  none is selected and the account is auto-detected. */
+char *nak_get_selected_steam_account(void);
+
+/** Select the Steam account whose userdata NaK uses and save the config.
+ *  NULL or "" clears the selection (auto-detect again). */
+void nak_set_selected_steam_account(const char *account_id);
+
+/** A Steam account from loginusers.vdf */
+typedef struct {
+    char *account_id;    /* userdata folder name (SteamID3) */
+    char *persona_name;
+    int most_recent;     /* 1 if it was the last account to log in */
+    uint64_t timestamp;  /* last login, unix seconds */
+} NakSteamAccount;
+
+typedef struct {
+    NakSteamAccount *accounts;
+    size_t count;
+} NakSteamAccountList;
+
+/** List the Steam accounts that have logged in on this machine, most
+ *  recently used first. Free with nak_steam_account_list_free. */
+NakSteamAccountList nak_get_steam_accounts(void);
+void nak_steam_account_list_free(NakSteamAccountList list);
+
+/** A candidate Steam root and what was found there */
+typedef struct {
+    char *path;
+    int state;  /* 0 = missing, 1 = dangling symlink, 2 = symlink loop,
+                   3 = no steamapps, 4 = valid */
+} NakSteamPathCheck;
+
+/** Explanation of Steam path detection */
+typedef struct {
+    char *steam_path;              /* NULL if Steam was not found */
+    char *guidance;                /* human-readable explanation and next step */
+    int flatpak_steam_installed;
+    int snap_steam_installed;
+    int nak_sandboxed;             /* 1 if NaK runs inside Flatpak */
+    NakSteamPathCheck *candidates;
+    size_t candidate_count;
+} NakSteamDiagnosis;
+
+/** Explain why Steam was (or wasn't) found, with actionable guidance.
+ *  Free with nak_steam_diagnosis_free. */
+NakSteamDiagnosis nak_diagnose_steam(void);
+
+/** Free a NakSteamDiagnosis */
+void nak_steam_diagnosis_free(NakSteamDiagnosis diagnosis);
+
+/** Persona name of the Steam account NaK is using (configured or auto-detected).
+ *  Returns newly allocated string (free with nak_string_free), or NULL. */
+char *nak_selected_account_name(void);
+
+/** Whether "Enable Steam Play for all other titles" is on: 1 yes, 0 no.
+ *  If out_default_tool is non-NULL it receives the default tool's internal
+ *  name (free with nak_string_free), or NULL when disabled. */
+int nak_steam_play_enabled(char **out_default_tool);
+
+/** Steam's download region (cell ID) from config.vdf, or 0 if unknown.
+ *  Usable as a hint for picking a nearby download mirror. */
+uint32_t nak_download_region(void);
+
+/** Check whether Steam Cloud sync is enabled for a game.
+ *  Returns 1 if enabled, 0 if disabled, -1 if unknown. */
+int nak_cloud_sync_enabled(uint32_t app_id);
+
+/** A saved Steam Input controller configuration */
+typedef struct {
+    char *controller_type;  /* e.g. "controller_ps5" */
+    char *name;             /* NULL if the config has no title */
+    char *path;
+} NakControllerConfig;
+
+/** Steam Input setup for a game */
+typedef struct {
+    int found;
+    int steam_input;  /* -1 no per-game override, 0 forced off, 1 controller defaults, 2 forced on */
+    NakControllerConfig *configs;
+    size_t count;
+} NakInputConfig;
+
+/** Look up a game's Steam Input override and saved controller configs.
+ *  found is 0 when Steam has neither. Free with nak_input_config_free. */
+NakInputConfig nak_game_input_config(uint32_t app_id);
+
+/** Free a NakInputConfig */
+void nak_input_config_free(NakInputConfig info);
+
+/** Shader cache size for one app */
+typedef struct {
+    uint32_t app_id;
+    uint64_t size;
+} NakShaderCache;
+
+/** List of per-app shader cache sizes */
+typedef struct {
+    NakShaderCache *caches;
+    size_t count;
+} NakShaderCacheList;
+
+/** List shader cache sizes per app across all Steam libraries (largest first) */
+NakShaderCacheList nak_shadercache_sizes(void);
+
+/** Free a NakShaderCacheList */
+void nak_shadercache_list_free(NakShaderCacheList list);
+
+/** Size of one game's Fossilize/Vulkan pipeline caches across all libraries */
+uint64_t nak_fossilize_cache_size(uint32_t app_id);
+
+/** Remove one game's Fossilize/Vulkan pipeline caches (shadercache pipeline
+ *  dirs and .foz files in compatdata; never saves or game data).
+ *  Writes bytes freed to *out_freed (may be NULL).
+ *  Returns NULL on success, or error message (free with nak_string_free). */
+char *nak_clear_fossilize_cache(uint32_t app_id, uint64_t *out_freed);
+
+/** compatdata (prefix) size for one app */
+typedef struct {
+    uint32_t app_id;
+    uint64_t size;
+} NakCompatdataSize;
+
+/** List of per-app compatdata sizes */
+typedef struct {
+    NakCompatdataSize *sizes;
+    size_t count;
+} NakCompatdataSizeList;
+
+/** List compatdata sizes per app across all Steam libraries (largest first).
+ *  cancel_flag: pointer to int, set non-zero to stop early (may be NULL);
+ *  a cancelled walk returns an empty list. */
+NakCompatdataSizeList nak_compatdata_sizes(const int *cancel_flag);
+
+/** Free a NakCompatdataSizeList */
+void nak_compatdata_size_list_free(NakCompatdataSizeList list);
+
+/** Remove one game's steamapps/shadercache/<app_id> from every library.
+ *  Writes bytes freed to *out_freed (may be NULL).
+ *  Returns NULL on success, or error message (free with nak_string_free). */
+char *nak_clear_shadercache(uint32_t app_id, uint64_t *out_freed);
+
+/** Write the current STEAM_COMPAT_MOUNTS into each app's launch options,
+ *  preserving other options. Steam should be closed first.
+ *  Writes number of apps changed to *out_updated (may be NULL).
+ *  Returns NULL on success, or error message (free with nak_string_free). */
+char *nak_sync_compat_mounts(const uint32_t *app_ids, size_t count, size_t *out_updated);
+
+/** A Vulkan-capable GPU */
+typedef struct {
+    char *name;         /* as matched by DXVK_FILTER_DEVICE_NAME */
+    uint32_t vendor_id;
+    uint32_t device_id;
+    int device_type;    /* 0 = discrete, 1 = integrated, 2 = virtual, 3 = other */
+} NakVulkanDevice;
+
+/** List of Vulkan GPUs */
+typedef struct {
+    NakVulkanDevice *devices;
+    size_t count;
+} NakVulkanDeviceList;
+
+/** List Vulkan-capable GPUs (discrete first). Empty if vulkaninfo is missing. */
+NakVulkanDeviceList nak_vulkan_devices(void);
+
+/** Free a NakVulkanDeviceList */
+void nak_vulkan_device_list_free(NakVulkanDeviceList list);
+
+/** Generate Steam launch options: DXVK config, STEAM_COMPAT_MOUNTS and, if
+ *  gpu_name names a Vulkan device, the env vars selecting that GPU.
+ *  dxvk_conf_path and gpu_name may be NULL.
+ *  Returns newly allocated string (free with nak_string_free). */
+char *nak_generate_launch_options(const char *dxvk_conf_path, int is_electron_app,
+                                  const char *gpu_name);
+
+/** nak_generate_launch_options plus the recommended controller env vars for
+ *  app_id if it is a known game. dxvk_conf_path, gpu_name and app_id may be
+ *  NULL. Returns newly allocated string (free with nak_string_free). */
+char *nak_generate_launch_options_for_app(const char *dxvk_conf_path, int is_electron_app,
+                                          const char *gpu_name, const char *app_id);
+
+/** nak_generate_launch_options_for_app with STEAM_COMPAT_MOUNTS adjusted: the
+ *  extra_count paths in extra_mounts are added to the detected directories
+ *  (see nak_detect_extra_mounts) and the excluded_count paths in excluded
+ *  removed. Either array may be NULL.
+ *  Returns newly allocated string (free with nak_string_free). */
+char *nak_generate_launch_options_with_mounts(const char *dxvk_conf_path, int is_electron_app,
+                                              const char *gpu_name, const char *app_id,
+                                              const char *const *extra_mounts, size_t extra_count,
+                                              const char *const *excluded, size_t excluded_count);
+
+/** Root directories nak_generate_launch_options puts in STEAM_COMPAT_MOUNTS
+ *  (e.g. "/mnt"), sorted. *out_count (may be NULL) receives the number.
+ *  Returns NULL when there are none; otherwise free with
+ *  nak_string_array_free(arr, count). */
+char **nak_detect_extra_mounts(size_t *out_count);
+
+/** Recommended controller-related env vars for a known game, as "KEY=VALUE"
+ *  items. Empty for unknown games. */
+NakStringList nak_controller_env_for(const char *app_id);
+
 /* ========================================================================
  * Tier 4: Dependency Installation (callback-based)
  * ======================================================================== */
@@ -102,10 +562,56 @@ typedef void (*NakLogCallback)(const char *message);
 /** Callback for progress updates (0.0 to 1.0) */
 typedef void (*NakProgressCallback)(float progress);
 
+/** Cause of an installer error, reported through an optional out_code */
+typedef enum {
+    NAK_SUCCESS = 0,
+    NAK_NETWORK_ERROR = 1,
+    NAK_IO_ERROR = 2,
+    NAK_CANCELLED = 3,
+    NAK_PROTON_NOT_FOUND = 4,
+    NAK_PREFIX_INVALID = 5,
+    NAK_UNKNOWN = 6,
+} NakErrorCode;
+
 /** Install all Wine prefix dependencies (blocking call).
  *  cancel_flag: pointer to int, set non-zero to cancel.
- *  Returns NULL on success, or error message (free with nak_string_free). */
+ *  Returns NULL on success, or error message (free with nak_string_free).
+ *  out_code (may be NULL) receives the cause, NAK_SUCCESS on success. */
 char *nak_install_all_dependencies(
+    const char *prefix_path,
+    const char *proton_name,
+    const char *proton_path,
+    NakStatusCallback status_cb,
+    NakLogCallback log_cb,
+    NakProgressCallback progress_cb,
+    const int *cancel_flag,
+    uint32_t app_id,
+    NakErrorCode *out_code
+);
+
+/** Install individual winetricks verbs (e.g. "vcrun2022") into a prefix
+ *  (blocking call). verbs: array of verb_count supported verb names.
+ *  Each step is reported through log_cb; the first failing verb stops the
+ *  install. cancel_flag: pointer to int, set non-zero to cancel.
+ *  Returns NULL on success, or an error naming the failed verb (free with
+ *  nak_string_free). out_code (may be NULL) receives the cause. */
+char *nak_install_winetricks_verbs(
+    const char *prefix_path,
+    const char *proton_path,
+    const char *const *verbs,
+    size_t verb_count,
+    NakLogCallback log_cb,
+    const int *cancel_flag,
+    NakErrorCode *out_code
+);
+
+/** Opaque handle of a background dependency install */
+typedef struct NakInstallHandle NakInstallHandle;
+
+/** Start nak_install_all_dependencies on a background thread and return at once.
+ *  Strings are copied; cancel_flag must stay valid until the handle is freed.
+ *  Callbacks run on the worker thread. Never returns NULL. */
+NakInstallHandle *nak_install_all_dependencies_async(
     const char *prefix_path,
     const char *proton_name,
     const char *proton_path,
@@ -116,28 +622,78 @@ char *nak_install_all_dependencies(
     uint32_t app_id
 );
 
+/** Check a background install: 0 = running, 1 = done, -1 = error */
+int nak_install_handle_poll(NakInstallHandle *handle);
+
+/** Take the error of a failed background install. Returns NULL while running,
+ *  on success, or once taken; otherwise free with nak_string_free.
+ *  out_code (may be NULL) receives the cause, NAK_SUCCESS when NULL is returned. */
+char *nak_install_handle_take_error(NakInstallHandle *handle, NakErrorCode *out_code);
+
+/** Wait for a background install to finish and release the handle.
+ *  Blocks while it runs; set the cancel flag first to stop it early. */
+void nak_install_handle_free(NakInstallHandle *handle);
+
 /** Apply Wine registry settings to a prefix.
- *  Returns NULL on success, or error message (free with nak_string_free). */
+ *  Returns NULL on success, or error message (free with nak_string_free).
+ *  out_code (may be NULL) receives the cause, NAK_SUCCESS on success. */
 char *nak_apply_wine_registry_settings(
     const char *prefix_path,
     const char *proton_name,
     const char *proton_path,
     NakLogCallback log_cb,
-    uint32_t app_id
+    uint32_t app_id,
+    NakErrorCode *out_code
 );
 
 /** Apply a game's registry entry with a custom install path.
  *  Looks up game_name in KNOWN_GAMES and writes registry pointing to install_path.
- *  Returns NULL on success, or an error message (free with nak_string_free). */
+ *  Returns NULL on success, or an error message (free with nak_string_free).
+ *  out_code (may be NULL) receives the cause, NAK_SUCCESS on success. */
 char *nak_apply_registry_for_game_path(
     const char *prefix_path,
     const char *proton_name,
     const char *proton_path,
     const char *game_name,
     const char *install_path,
-    NakLogCallback log_cb
+    NakLogCallback log_cb,
+    NakErrorCode *out_code
 );
 
+/** Flush pending registry changes in a prefix to user.reg/system.reg by
+ *  waiting for its wineserver to exit. Fails at once, without waiting, if
+ *  programs are still running in the prefix, and gives up if the wineserver
+ *  has not exited after 15 seconds.
+ *  Returns NULL on success, or error message (free with nak_string_free).
+ *  out_code (may be NULL) receives the cause, NAK_SUCCESS on success. */
+char *nak_flush_prefix_registry(const char *prefix_path, const char *proton_path,
+                                NakErrorCode *out_code);
+
+/** Read a value from a prefix's system.reg, falling back to user.reg.
+ *  registry_path is a key like "Software\\Bethesda Softworks\\Skyrim";
+ *  Wow6432Node is checked too in 64-bit prefixes. Reads the files on disk,
+ *  so flush with nak_flush_prefix_registry first if Wine may be running.
+ *  Returns newly allocated string (free with nak_string_free), or NULL if
+ *  the value isn't there. */
+char *nak_read_registry_value(const char *prefix_path, const char *registry_path,
+                              const char *registry_value);
+
+/** Validate a download mirror URL (http, https, file, or an absolute path).
+ *  Remote mirrors get a HEAD request; local ones must exist.
+ *  Returns NULL if usable, or error message (free with nak_string_free). */
+char *nak_validate_mirror(const char *url);
+
+/** Estimated disk space of a dependency install, in bytes */
+typedef struct {
+    uint64_t download;       /* downloaded into the NaK cache */
+    uint64_t extracted;      /* unpacked into the prefix */
+    uint64_t total_required; /* download + extracted + prefix overhead */
+} NakSetupFootprint;
+
+/** Estimate the disk space nak_install_all_dependencies needs for a prefix
+ *  and game, leaving out installed verbs and cached downloads. */
+NakSetupFootprint nak_estimated_setup_footprint(const char *prefix_path, uint32_t app_id);
+
 /* ========================================================================
  * Tier 5: Prefix Symlinks
  * ======================================================================== */
@@ -146,10 +702,40 @@ char *nak_apply_registry_for_game_path(
  *  Call during prefix creation. */
 void nak_ensure_temp_directory(const char *prefix_path);
 
+/** Ensure both AppData/Local/Temp and drive_c/windows/temp exist.
+ *  Returns the directories that were missing and got created; empty if
+ *  Wine hasn't initialized the prefix yet. */
+NakStringList nak_ensure_all_temp_dirs(const char *prefix_path);
+
 /** Detect games and create symlinks from the prefix to game prefixes.
  *  Call during prefix creation. */
 void nak_create_game_symlinks_auto(const char *prefix_path);
 
+/** A game symlink nak_create_game_symlinks_auto would create */
+typedef struct {
+    char *link;      /* path inside the prefix */
+    char *target;    /* folder in the game's prefix */
+    char *game_name;
+    int status;      /* 0 = will be created, 1 = already linked,
+                        2 = broken link, 3 = path taken (left alone) */
+} NakPlannedSymlink;
+
+typedef struct {
+    NakPlannedSymlink *links;
+    size_t count;
+} NakSymlinkPlan;
+
+/** Preview nak_create_game_symlinks_auto without creating anything.
+ *  Free with nak_symlink_plan_free. */
+NakSymlinkPlan nak_plan_game_symlinks(const char *prefix_path);
+void nak_symlink_plan_free(NakSymlinkPlan plan);
+
+/** Make both "steamuser" and the prefix's real user directory name resolve
+ *  by linking one to the other. Never replaces an existing directory.
+ *  Writes 1 to *out_created if a link was created (may be NULL).
+ *  Returns NULL on success, or error message (free with nak_string_free). */
+char *nak_ensure_steamuser_symlink(const char *prefix_path, int *out_created);
+
 /* ========================================================================
  * Tier 6: Logging
  * ======================================================================== */
@@ -170,10 +756,354 @@ void nak_init_logging(NakLogLevelCallback cb);
  *  Returns NULL on success, or error message (free with nak_string_free). */
 char *nak_ensure_dxvk_conf(void);
 
+/** DXVK version the stored dxvk.conf was taken from (e.g. "2.5.3").
+ *  Returns newly allocated string (free with nak_string_free), or NULL if
+ *  there is no config yet or it carries no version. */
+char *nak_get_dxvk_version(void);
+
+/** Re-download the DXVK config if force is non-zero or the stored copy is
+ *  older than the version NaK bundles. A failed download keeps the existing
+ *  file. Returns NULL on success (also when already current), or error
+ *  message (free with nak_string_free). */
+char *nak_update_dxvk_conf(int force);
+
 /** Get the path to the DXVK config file.
  *  Returns newly allocated string (free with nak_string_free). */
 char *nak_get_dxvk_conf_path(void);
 
+/** Remove only the DXVK state cache (*.dxvk-cache) files for a detected game.
+ *  Writes bytes freed to *out_freed (may be NULL).
+ *  Returns NULL on success, or error message (free with nak_string_free). */
+char *nak_clear_dxvk_state_cache(const char *app_id, uint64_t *out_freed);
+
+/** Outcome of applying a DXVK config to one game */
+typedef struct {
+    char *app_id;
+    int method;   /* 0 = DXVK_CONFIG_FILE in launch options, 1 = copied to install dir, -1 = failed */
+    char *error;  /* NULL on success */
+} NakDxvkApplyResult;
+
+/** Per-game results of nak_apply_dxvk_conf */
+typedef struct {
+    NakDxvkApplyResult *results;
+    size_t count;
+} NakDxvkApplyResultList;
+
+/** Point several detected games (app_ids, count entries) at the same DXVK
+ *  config. Steam must be closed, since Steam games are configured through
+ *  their launch options. Free with nak_dxvk_apply_result_list_free. */
+NakDxvkApplyResultList nak_apply_dxvk_conf(const char *conf_path, const char *const *app_ids,
+                                           size_t count);
+
+/** Free a NakDxvkApplyResultList */
+void nak_dxvk_apply_result_list_free(NakDxvkApplyResultList list);
+
+/* ========================================================================
+ * Tier 8: Prefix Diagnostics
+ * ======================================================================== */
+
+/** List the VC++ redistributables installed in a prefix.
+ *  Items are winetricks verb names (e.g. "vcrun2022"), newest first. */
+NakStringList nak_installed_vcredist(const char *prefix_path);
+
+/** List the .NET Framework versions and desktop runtimes installed in a prefix.
+ *  Items are winetricks verbs: Framework first ("dotnet48"), then desktop
+ *  runtimes ("dotnetdesktop8"). */
+NakStringList nak_installed_dotnet(const char *prefix_path);
+
+/** Which kind of .NET a winetricks verb installs:
+ *  0 = .NET Framework, 1 = .NET desktop runtime, -1 = neither */
+int nak_dotnet_kind(const char *verb);
+
+/** Whether a prefix has native Media Foundation installed (1 yes, 0 no) */
+int nak_has_media_foundation(const char *prefix_path);
+
+/** Whether a prefix already has NaK's current dependency set (1 yes, 0 no):
+ *  the marker from a finished install plus the DLL overrides, .NET
+ *  runtimes and .NET 9 SDK it implies. Such prefixes skip the standard
+ *  verbs and .NET runtimes when dependencies are installed again. */
+int nak_dependencies_installed(const char *prefix_path);
+
+/** The marker the last finished dependency install left in a prefix, as a
+ *  JSON object: nak_version, deps_version (0 until an install completes
+ *  without failures), installed_at (Unix seconds) and verbs (winetricks
+ *  verbs plus NaK's own "dotnetsdk9"/"dotnetdesktop10", sorted).
+ *  Returns newly allocated string (free with nak_string_free), or NULL if
+ *  the prefix has no readable marker. */
+char *nak_read_deps_marker(const char *prefix_path);
+
+/** Whether Wine Gecko and Wine Mono are installed in a prefix.
+ *  Writes 1/0 to *out_gecko and *out_mono (each may be NULL). */
+void nak_has_gecko_mono(const char *prefix_path, int *out_gecko, int *out_mono);
+
+/** Install whichever of Wine Gecko / Wine Mono a prefix lacks from the
+ *  packages shipped with the Proton at proton_path (blocking call).
+ *  Returns NULL on success, or an error message (free with nak_string_free).
+ *  If out_code is non-NULL it receives the cause. */
+char *nak_install_gecko_mono(const char *prefix_path, const char *proton_path,
+                             NakLogCallback log_cb, NakErrorCode *out_code);
+
+/** Which tool created a prefix:
+ *  0 = Proton, 1 = plain Wine (Lutris, winetricks...), 2 = Bottles, 3 = unknown */
+int nak_prefix_kind(const char *prefix_path);
+
+/** Files Proton manages in a prefix, from compatdata's tracked_files.
+ *  Paths are relative to pfx/; empty if the file is absent. */
+NakStringList nak_prefix_tracked_files(const char *prefix_path);
+
+/** The directory holding a prefix's drive_c, given the prefix root, a
+ *  compatdata-style parent (<path>/pfx/drive_c) or drive_c itself.
+ *  Returns NULL if no drive_c is found; free with nak_string_free. */
+char *nak_resolve_prefix_root(const char *path);
+
+/** A prefix's Documents folder, following shell-folder redirections that
+ *  resolve inside the prefix. OneDrive redirections are ignored (with a
+ *  logged warning) in favour of the local Documents folder.
+ *  Caller must free with nak_string_free. */
+char *nak_prefix_documents_path(const char *prefix_path);
+
+/** Whether NaK's download cache is on the same filesystem as a prefix:
+ *  1 yes (or unknown), 0 no. Different filesystems make installs copy
+ *  across drives and need free space on both. */
+int nak_cache_prefix_same_fs(const char *prefix_path);
+
+/** Pick a writable temp directory with at least required_bytes free: NaK's
+ *  cache tmp, then the prefix's filesystem (if Wine has initialized it),
+ *  then $TMPDIR. On success writes
+ *  the path to *out_path (free with nak_string_free).
+ *  Returns NULL on success, or error message (free with nak_string_free). */
+char *nak_select_temp_dir(const char *prefix_path, uint64_t required_bytes, char **out_path);
+
+/** Delete NaK's downloads and install scratch files not modified for
+ *  older_than_secs (partial downloads, cached installers, the tmp dir).
+ *  Other cache contents, files still being written and the config directory
+ *  are left alone. Writes bytes freed to *out_bytes_freed (may be NULL).
+ *  Returns NULL on success, or error message (free with nak_string_free). */
+char *nak_clean_cache(uint64_t older_than_secs, uint64_t *out_bytes_freed);
+
+/** DPI (LogPixels) configured in a prefix, or 0 if unset (Wine uses 96) */
+uint32_t nak_get_prefix_dpi(const char *prefix_path);
+
+/** Set a prefix's DPI (72-480) by editing user.reg; the old file is kept as
+ *  user.reg.nak-bak. Nothing may be running in the prefix.
+ *  Returns NULL on success, or error message (free with nak_string_free). */
+char *nak_set_prefix_dpi(const char *prefix_path, uint32_t dpi);
+
+/** Audio driver pinned in a prefix ("pulse", "alsa", "oss", "coreaudio" or
+ *  "none"), or NULL when Wine picks one itself. Free with nak_string_free. */
+char *nak_get_audio_driver(const char *prefix_path);
+
+/** Pin a prefix's audio driver ("pulse", "alsa", "oss", "coreaudio", "none"),
+ *  or pass "" / NULL to let Wine choose. Edits user.reg, keeping the old file
+ *  as user.reg.nak-bak; nothing may be running in the prefix.
+ *  Returns NULL on success, or error message (free with nak_string_free). */
+char *nak_set_audio_driver(const char *prefix_path, const char *driver);
+
+/** Whether a prefix has PendingFileRenameOperations left over from an
+ *  interrupted installer: 1 yes, 0 no */
+int nak_has_pending_renames(const char *prefix_path);
+
+/** Clear leftover PendingFileRenameOperations by editing system.reg; the old
+ *  file is kept as system.reg.nak-bak. Nothing may be running in the prefix.
+ *  Writes 1 to *out_cleared (may be NULL) if anything was removed.
+ *  Returns NULL on success, or error message (free with nak_string_free). */
+char *nak_clear_pending_renames(const char *prefix_path, int *out_cleared);
+
+/** Architecture of a prefix: 64, 32, or 0 if it has no registry yet.
+ *  32-bit prefixes cannot be used with Proton; NaK refuses to install
+ *  dependencies into them. */
+int nak_prefix_arch(const char *prefix_path);
+
+/** A Start Menu shortcut inside a prefix */
+typedef struct {
+    char *name;
+    char *lnk_path;
+    char *target_path;        /* NULL if unknown */
+    char *target_linux_path;  /* NULL if not on C: or Z: */
+    char *arguments;          /* NULL if none */
+    char *working_dir;        /* NULL if none */
+} NakShortcut;
+
+/** List of Start Menu shortcuts */
+typedef struct {
+    NakShortcut *shortcuts;
+    size_t count;
+} NakShortcutList;
+
+/** List the Start Menu shortcuts (.lnk) installed in a prefix */
+NakShortcutList nak_list_start_menu_shortcuts(const char *prefix_path);
+
+/** Free a NakShortcutList */
+void nak_shortcut_list_free(NakShortcutList list);
+
+/** A permission problem on a prefix or cache path */
+typedef struct {
+    char *path;
+    char *problem;  /* human-readable description */
+} NakPermissionIssue;
+
+/** List of permission problems */
+typedef struct {
+    NakPermissionIssue *issues;
+    size_t count;
+} NakPermissionIssueList;
+
+/** Check that the current user can read/write the prefix, compatdata and cache.
+ *  An empty list means no problems were found. */
+NakPermissionIssueList nak_check_permissions(const char *prefix_path);
+
+/** Free a NakPermissionIssueList */
+void nak_permission_issue_list_free(NakPermissionIssueList list);
+
+/** A mod manager installation inside a prefix.
+ *  All fields are NULL when no mod manager was found. */
+typedef struct {
+    char *name;         /* "Mod Organizer 2" or "Vortex" */
+    char *exe_path;
+    char *install_dir;
+    char *version;      /* NULL if unknown */
+} NakModManagerInfo;
+
+/** Detect an installed MO2 or Vortex inside a prefix (MO2 preferred).
+ *  Free with nak_mod_manager_info_free. */
+NakModManagerInfo nak_detect_mod_manager(const char *prefix_path);
+
+/** Free a NakModManagerInfo */
+void nak_mod_manager_info_free(NakModManagerInfo info);
+
+/** A game whose prefix was created by a much older Proton */
+typedef struct {
+    char *name;
+    char *app_id;
+    char *prefix_path;
+    char *prefix_version;  /* as recorded in compatdata, e.g. "8.0-104" */
+    uint32_t prefix_major;
+    uint32_t current_major;
+} NakPrefixUpgrade;
+
+/** List of prefixes needing an upgrade */
+typedef struct {
+    NakPrefixUpgrade *prefixes;
+    size_t count;
+} NakPrefixUpgradeList;
+
+/** Find games whose prefix is two or more Proton major versions behind
+ *  current_proton (a Proton name, e.g. "GE-Proton10-4").
+ *  Free with nak_prefix_upgrade_list_free. */
+NakPrefixUpgradeList nak_prefixes_needing_upgrade(const char *current_proton);
+
+/** Free a NakPrefixUpgradeList */
+void nak_prefix_upgrade_list_free(NakPrefixUpgradeList list);
+
+/** One DXVK DLL vs. its DllOverrides entry */
+typedef struct {
+    char *dll;             /* e.g. "d3d11" */
+    int dll_present;       /* 1 if a native (non-Wine) DLL is installed */
+    char *override_value;  /* NULL if no override */
+    char *mismatch;        /* description, NULL if consistent */
+} NakDxvkDllCheck;
+
+/** DXVK setup check for a prefix */
+typedef struct {
+    int proton_managed;    /* 1 if Proton sets overrides at launch */
+    NakDxvkDllCheck *dlls;
+    size_t count;
+} NakDxvkSetupStatus;
+
+/** Cross-check DXVK DLLs in a prefix against its d3d/dxgi DllOverrides.
+ *  Free with nak_dxvk_setup_status_free. */
+NakDxvkSetupStatus nak_verify_dxvk_setup(const char *prefix_path);
+
+/** Free a NakDxvkSetupStatus */
+void nak_dxvk_setup_status_free(NakDxvkSetupStatus status);
+
+/** Reconcile DllOverrides with the installed DXVK DLLs. Fails while anything
+ *  runs in the prefix; the previous user.reg is kept as user.reg.nak-bak.
+ *  Writes number of overrides changed to *out_changed (may be NULL).
+ *  Returns NULL on success, or error message (free with nak_string_free). */
+char *nak_fix_dxvk_setup(const char *prefix_path, size_t *out_changed);
+
+/** DllOverrides health of one winetricks verb */
+typedef struct {
+    char *verb;
+    int ok;         /* 1 if every DLL the verb installs is overridden to native */
+} NakVerbOverride;
+
+typedef struct {
+    NakVerbOverride *verbs;
+    size_t count;
+} NakVerbOverrideList;
+
+/** Check the DllOverrides of installed winetricks verbs (per winetricks.log).
+ *  `verbs` is an array of `count` verb names; NULL checks the standard
+ *  dependency verbs. Verbs not installed or without overrides are left out.
+ *  Free with nak_verb_override_list_free. */
+NakVerbOverrideList nak_verify_verb_overrides(const char *prefix_path,
+                                              const char *const *verbs, size_t count);
+
+/** Free a NakVerbOverrideList */
+void nak_verb_override_list_free(NakVerbOverrideList list);
+
+/** Restore missing DllOverrides (as native,builtin) of installed verbs.
+ *  Nothing may be running in the prefix; user.reg is backed up first.
+ *  Writes number of overrides set to *out_fixed (may be NULL).
+ *  Returns NULL on success, or error message (free with nak_string_free). */
+char *nak_fix_verb_overrides(const char *prefix_path, const char *const *verbs,
+                             size_t count, size_t *out_fixed);
+
+/* ========================================================================
+ * Tier 9: System Diagnostics
+ * ======================================================================== */
+
+/** 32-bit library availability */
+typedef struct {
+    int detected;          /* 0 if detection wasn't possible (other fields unreliable) */
+    int vulkan;
+    int gl;
+    NakStringList missing; /* key 32-bit libraries not found */
+} NakMultilibStatus;
+
+/** Check for the 32-bit Vulkan/GL libraries Proton needs.
+ *  Free with nak_multilib_status_free. */
+NakMultilibStatus nak_check_multilib(void);
+
+/** Free a NakMultilibStatus */
+void nak_multilib_status_free(NakMultilibStatus status);
+
+/** Vulkan device extensions supported by any GPU (empty if vulkaninfo is unavailable) */
+NakStringList nak_vulkan_extensions(void);
+
+/** Vulkan extensions the Proton at proton_path needs (for its DXVK and
+ *  VKD3D-Proton) but the driver lacks; alternatives are joined with " or ".
+ *  Empty when nothing is missing or the driver can't be queried. */
+NakStringList nak_proton_vulkan_missing(const char *proton_path);
+
+/** Generate a JSON report of detected games, Protons, platform and warnings
+ *  for pasting into a bug report. Non-zero redact strips the home directory
+ *  and username from paths.
+ *  Returns newly allocated string (free with nak_string_free). */
+char *nak_generate_report(int redact);
+
+/* ========================================================================
+ * Tier 10: Instance Locking
+ * ======================================================================== */
+
+/** Take the app-wide NaK lock, failing fast if another instance holds it.
+ *  Calling again while already holding the lock succeeds.
+ *  Returns NULL on success, or error message (free with nak_string_free). */
+char *nak_acquire_lock(void);
+
+/** Release the app-wide NaK lock (no-op if not held) */
+void nak_release_lock(void);
+
+/** Whether any process is running with this Wine prefix: 1 yes, 0 no */
+int nak_prefix_in_use(const char *prefix_path);
+
+/** Find a mod manager running against a prefix, so the UI can ask the user
+ *  to close it before applying changes. Writes its pid to *out_pid (may be NULL).
+ *  Returns display name (free with nak_string_free), or NULL if none. */
+char *nak_running_mod_manager(const char *prefix_path, uint32_t *out_pid);
+
 /* ========================================================================
  * General
  * ======================================================================== */
@@ -181,6 +1111,12 @@ char *nak_get_dxvk_conf_path(void);
 /** Free a string returned by any nak_* function */
 void nak_string_free(char *s);
 
+/** Free a NakStringList returned by any nak_* function */
+void nak_string_list_free(NakStringList list);
+
+/** Free a bare array of count strings returned by a nak_* function */
+void nak_string_array_free(char **arr, size_t count);
+
 #ifdef __cplusplus
 }
 #endif
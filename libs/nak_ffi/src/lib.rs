@@ -7,10 +7,11 @@
 //! - `NakKnownGame` pointers are static data and must NOT be freed
 
 use std::ffi::{c_char, c_float, c_int, CStr, CString};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::ptr;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
 
 // ============================================================================
 // Helper functions
@@ -35,10 +36,39 @@ unsafe fn from_cstr<'a>(p: *const c_char) -> &'a str {
     }
 }
 
+/// Borrow `count` strings from a C array; empty if the array is null
+unsafe fn cstr_array<'a>(items: *const *const c_char, count: usize) -> Vec<&'a str> {
+    if items.is_null() {
+        return Vec::new();
+    }
+    unsafe { std::slice::from_raw_parts(items, count) }
+        .iter()
+        .map(|&item| unsafe { from_cstr(item) })
+        .collect()
+}
+
 fn error_to_cstring(e: Box<dyn std::error::Error>) -> *mut c_char {
     to_cstring(&e.to_string())
 }
 
+/// Look up a detected game by app ID (fresh scan, not the FFI cache)
+fn find_detected_game(app_id: &str) -> Option<nak_rust::game_finder::Game> {
+    nak_rust::game_finder::detect_all_games()
+        .games
+        .into_iter()
+        .find(|g| g.app_id == app_id)
+}
+
+fn to_string_list(items: Vec<String>) -> NakStringList {
+    let mut ptrs: Vec<*mut c_char> = items.iter().map(|s| to_cstring(s)).collect();
+    let list = NakStringList {
+        items: ptrs.as_mut_ptr(),
+        count: ptrs.len(),
+    };
+    std::mem::forget(ptrs);
+    list
+}
+
 // ============================================================================
 // Tier 1: Game Detection
 // ============================================================================
@@ -56,6 +86,17 @@ pub struct NakGame {
     pub appdata_roaming_folder: *mut c_char,
     pub registry_path: *mut c_char,
     pub registry_value: *mut c_char,
+    pub prefix_free_space: i64, // bytes free on the prefix filesystem, -1 if unknown
+    pub prefix_shared: c_int,   // 1 if the prefix is shared/redirected
+    pub my_games_path: *mut c_char,        // resolved path in the prefix, null if n/a
+    pub appdata_local_path: *mut c_char,   // resolved path in the prefix, null if n/a
+    pub appdata_roaming_path: *mut c_char, // resolved path in the prefix, null if n/a
+    pub bundled_runtime_path: *mut c_char, // Proton/Wine shipped with the game, null if none; usable as a proton_path
+    pub prefix_redirected: c_int,          // 1 if set by STEAM_COMPAT_DATA_PATH in launch options
+    pub prefix_created_at: i64,            // unix timestamp, -1 if unknown or no prefix
+    pub family_shared: c_int,              // 1 if borrowed through Steam Family Sharing
+    pub app_type: c_int,                   // 0 = game, 1 = tool, 2 = application, 3 = demo
+    pub install_state: c_int,              // 0 = installed, 1 = update required, 2 = downloading, 3 = not installed
 }
 
 /// List of detected games
@@ -66,6 +107,7 @@ pub struct NakGameList {
     pub steam_count: usize,
     pub heroic_count: usize,
     pub bottles_count: usize,
+    pub lutris_count: usize,
 }
 
 #[derive(Clone)]
@@ -75,11 +117,22 @@ struct CachedGame {
     install_path: String,
     prefix_path: Option<String>,
     launcher: String,
+    launcher_kind: &'static str,
     my_games_folder: Option<String>,
     appdata_local_folder: Option<String>,
     appdata_roaming_folder: Option<String>,
     registry_path: Option<String>,
     registry_value: Option<String>,
+    prefix_shared: bool,
+    my_games_path: Option<String>,
+    appdata_local_path: Option<String>,
+    appdata_roaming_path: Option<String>,
+    bundled_proton: Option<nak_rust::steam::SteamProton>,
+    prefix_redirected: bool,
+    prefix_created_at: Option<i64>,
+    family_shared: bool,
+    app_type: nak_rust::game_finder::AppType,
+    install_state: nak_rust::game_finder::InstallState,
 }
 
 #[derive(Clone, Default)]
@@ -88,84 +141,329 @@ struct CachedGameList {
     steam_count: usize,
     heroic_count: usize,
     bottles_count: usize,
+    lutris_count: usize,
+    /// (launcher kind, bytes, games of unknown size)
+    sizes: Vec<(String, u64, usize)>,
 }
 
-static DETECTED_GAMES_CACHE: LazyLock<Mutex<Option<CachedGameList>>> =
+static DETECTED_GAMES_CACHE: LazyLock<Mutex<Option<(Instant, CachedGameList)>>> =
     LazyLock::new(|| Mutex::new(None));
 
+/// Seconds a cached scan stays fresh; 0 keeps it for the process lifetime
+static GAME_CACHE_TTL_SECS: AtomicU64 = AtomicU64::new(0);
+
 fn detect_games_cached() -> CachedGameList {
     let mut cache = DETECTED_GAMES_CACHE.lock().unwrap();
-    if let Some(cached) = cache.as_ref() {
-        return cached.clone();
+    let ttl = GAME_CACHE_TTL_SECS.load(Ordering::Relaxed);
+    if let Some((scanned_at, cached)) = cache.as_ref() {
+        if ttl == 0 || scanned_at.elapsed() < Duration::from_secs(ttl) {
+            return cached.clone();
+        }
     }
 
-    let result = nak_rust::game_finder::detect_all_games();
-    let cached = CachedGameList {
-        games: result
-            .games
-            .iter()
-            .map(|g| CachedGame {
-                name: g.name.clone(),
-                app_id: g.app_id.clone(),
-                install_path: g.install_path.to_string_lossy().into_owned(),
-                prefix_path: g
-                    .prefix_path
-                    .as_ref()
-                    .map(|p| p.to_string_lossy().into_owned()),
-                launcher: g.launcher.display_name().to_string(),
-                my_games_folder: g.my_games_folder.clone(),
-                appdata_local_folder: g.appdata_local_folder.clone(),
-                appdata_roaming_folder: g.appdata_roaming_folder.clone(),
-                registry_path: g.registry_path.clone(),
-                registry_value: g.registry_value.clone(),
-            })
-            .collect(),
+    let cached = cached_list(&nak_rust::game_finder::detect_all_games());
+    *cache = Some((Instant::now(), cached.clone()));
+    cached
+}
+
+fn cached_list(result: &nak_rust::game_finder::GameScanResult) -> CachedGameList {
+    CachedGameList {
+        games: result.games.iter().map(cached_game).collect(),
         steam_count: result.steam_count,
         heroic_count: result.heroic_count,
         bottles_count: result.bottles_count,
-    };
+        lutris_count: result.lutris_count,
+        sizes: result.size_by_launcher(),
+    }
+}
 
-    *cache = Some(cached.clone());
-    cached
+fn cached_game(g: &nak_rust::game_finder::Game) -> CachedGame {
+    CachedGame {
+        name: g.name.clone(),
+        app_id: g.app_id.clone(),
+        install_path: g.install_path.to_string_lossy().into_owned(),
+        prefix_path: g
+            .prefix_path
+            .as_ref()
+            .map(|p| p.to_string_lossy().into_owned()),
+        launcher: g.launcher.display_name().to_string(),
+        launcher_kind: g.launcher.kind(),
+        my_games_folder: g.my_games_folder.clone(),
+        appdata_local_folder: g.appdata_local_folder.clone(),
+        appdata_roaming_folder: g.appdata_roaming_folder.clone(),
+        registry_path: g.registry_path.clone(),
+        registry_value: g.registry_value.clone(),
+        prefix_shared: g.prefix_shared,
+        my_games_path: g
+            .get_prefix_my_games_path()
+            .map(|p| p.to_string_lossy().into_owned()),
+        appdata_local_path: g
+            .get_prefix_appdata_local_path()
+            .map(|p| p.to_string_lossy().into_owned()),
+        appdata_roaming_path: g
+            .get_prefix_appdata_roaming_path()
+            .map(|p| p.to_string_lossy().into_owned()),
+        bundled_proton: g.bundled_proton(),
+        prefix_redirected: g.prefix_redirected,
+        prefix_created_at: g
+            .prefix_created_at()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64),
+        family_shared: g.family_shared,
+        app_type: g.app_type,
+        install_state: g.install_state,
+    }
 }
 
 /// Detect all installed games across all launchers
 #[no_mangle]
 pub extern "C" fn nak_detect_all_games() -> NakGameList {
     let result = detect_games_cached();
+    game_list(&result, result.games.iter())
+}
 
-    let mut games: Vec<NakGame> = result
-        .games
+/// Detect installed games, including Steam tools (Proton, Steam Linux
+/// Runtime, redistributables) when `include_tools` is non-zero.
+///
+/// With tools included the scan bypasses the game cache. Free with
+/// nak_game_list_free.
+#[no_mangle]
+pub extern "C" fn nak_detect_all_games_with_tools(include_tools: c_int) -> NakGameList {
+    if include_tools == 0 {
+        return nak_detect_all_games();
+    }
+    let result = cached_list(&nak_rust::game_finder::detect_all_games_with(true));
+    game_list(&result, result.games.iter())
+}
+
+/// Run a full scan and return it as one JSON object: `games` (every field
+/// of each game), the per-launcher counts and `warnings`.
+///
+/// Always rescans and leaves the game cache untouched. Returns null if the
+/// result can't be serialized; otherwise free with nak_string_free.
+#[no_mangle]
+pub extern "C" fn nak_detect_all_games_json() -> *mut c_char {
+    match nak_rust::game_finder::detect_all_games().to_json() {
+        Ok(json) => to_cstring(&json),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Install size of one launcher's games (C-compatible)
+#[repr(C)]
+pub struct NakLauncherSize {
+    pub launcher: *mut c_char, // "steam", "heroic", "bottles" or "lutris"
+    pub bytes: u64,
+    pub unknown_count: usize, // games whose launcher records no size
+}
+
+/// Install sizes per launcher
+#[repr(C)]
+pub struct NakLauncherSizeList {
+    pub sizes: *mut NakLauncherSize,
+    pub count: usize,
+}
+
+/// Total install size of detected games per launcher. Only launchers with
+/// games are listed.
+#[no_mangle]
+pub extern "C" fn nak_size_by_launcher() -> NakLauncherSizeList {
+    let result = detect_games_cached();
+    let mut sizes: Vec<NakLauncherSize> = result
+        .sizes
         .iter()
-        .map(|g| NakGame {
-            name: to_cstring(&g.name),
-            app_id: to_cstring(&g.app_id),
-            install_path: to_cstring(&g.install_path),
-            prefix_path: match &g.prefix_path {
-                Some(p) => to_cstring(p),
-                None => ptr::null_mut(),
-            },
-            launcher: to_cstring(&g.launcher),
-            my_games_folder: to_cstring_opt(g.my_games_folder.as_deref()),
-            appdata_local_folder: to_cstring_opt(g.appdata_local_folder.as_deref()),
-            appdata_roaming_folder: to_cstring_opt(g.appdata_roaming_folder.as_deref()),
-            registry_path: to_cstring_opt(g.registry_path.as_deref()),
-            registry_value: to_cstring_opt(g.registry_value.as_deref()),
+        .map(|(launcher, bytes, unknown)| NakLauncherSize {
+            launcher: to_cstring(launcher),
+            bytes: *bytes,
+            unknown_count: *unknown,
         })
         .collect();
 
+    let list = NakLauncherSizeList {
+        sizes: sizes.as_mut_ptr(),
+        count: sizes.len(),
+    };
+    std::mem::forget(sizes);
+    list
+}
+
+/// Free a NakLauncherSizeList
+///
+/// # Safety
+/// `list` must be exactly as returned by NaK and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_launcher_size_list_free(list: NakLauncherSizeList) {
+    if list.sizes.is_null() {
+        return;
+    }
+    let sizes = unsafe { Vec::from_raw_parts(list.sizes, list.count, list.count) };
+    for size in sizes {
+        free_if_nonnull(size.launcher);
+    }
+}
+
+/// Drop the cached game scan so the next detection call rescans.
+///
+/// Lists returned earlier stay valid and must still be freed with
+/// nak_game_list_free.
+#[no_mangle]
+pub extern "C" fn nak_invalidate_game_cache() {
+    *DETECTED_GAMES_CACHE.lock().unwrap() = None;
+}
+
+/// Rescan on the next detection call once the cached scan is older than
+/// `secs`. 0 (the default) keeps the scan until it is invalidated.
+#[no_mangle]
+pub extern "C" fn nak_set_game_cache_ttl_secs(secs: u64) {
+    GAME_CACHE_TTL_SECS.store(secs, Ordering::Relaxed);
+}
+
+/// Extra folders scanned for GOG games installed outside Heroic.
+///
+/// Free with nak_string_list_free.
+#[no_mangle]
+pub extern "C" fn nak_get_gog_library_roots() -> NakStringList {
+    to_string_list(nak_rust::config::AppConfig::load().gog_library_roots)
+}
+
+/// Replace the extra GOG library folders with `count` paths from `roots`.
+///
+/// Drops the cached game scan so the next detection picks them up.
+///
+/// # Safety
+/// `roots` must be null or point to `count` strings, each null or NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn nak_set_gog_library_roots(roots: *const *const c_char, count: usize) {
+    let roots: Vec<String> = unsafe { cstr_array(roots, count) }
+        .into_iter()
+        .filter(|root| !root.is_empty())
+        .map(String::from)
+        .collect();
+
+    let mut config = nak_rust::config::AppConfig::load();
+    config.gog_library_roots = roots;
+    config.save();
+    nak_invalidate_game_cache();
+}
+
+/// Rescan all launchers, replacing the cached scan, and return the fresh list.
+///
+/// Lists returned earlier stay valid and must still be freed with
+/// nak_game_list_free.
+#[no_mangle]
+pub extern "C" fn nak_refresh_game_cache() -> NakGameList {
+    nak_invalidate_game_cache();
+    nak_detect_all_games()
+}
+
+/// Detected games from one launcher: "steam", "heroic", "bottles" or "lutris".
+///
+/// Unknown launchers give an empty list. The per-launcher counts are still
+/// those of the whole scan. Free with nak_game_list_free.
+///
+/// # Safety
+/// `launcher` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_detect_games_by_launcher(launcher: *const c_char) -> NakGameList {
+    let launcher = unsafe { from_cstr(launcher) };
+    let result = detect_games_cached();
+    game_list(&result, result.games.iter().filter(|g| g.launcher_kind == launcher))
+}
+
+/// Detected games that have a Wine prefix, the ones mods and registry
+/// entries can be applied to.
+///
+/// Unlike the other filtered lists, the per-launcher counts are those of the
+/// returned games. Free with nak_game_list_free.
+#[no_mangle]
+pub extern "C" fn nak_detect_games_with_prefixes() -> NakGameList {
+    let result = detect_games_cached();
+    // Same test as GameScanResult::games_with_prefixes, on the cached scan
+    let with_prefix: Vec<&CachedGame> = result.games.iter().filter(|g| g.prefix_path.is_some()).collect();
+    let count = |kind: &str| with_prefix.iter().filter(|g| g.launcher_kind == kind).count();
+    let filtered = CachedGameList {
+        steam_count: count("steam"),
+        heroic_count: count("heroic"),
+        bottles_count: count("bottles"),
+        lutris_count: count("lutris"),
+        ..Default::default()
+    };
+    game_list(&filtered, with_prefix.into_iter())
+}
+
+fn game_list<'a>(
+    result: &CachedGameList,
+    selected: impl Iterator<Item = &'a CachedGame>,
+) -> NakGameList {
+    let mut games: Box<[NakGame]> = selected
+        .map(nak_game)
+        .collect();
+
     let list = NakGameList {
         games: games.as_mut_ptr(),
         count: games.len(),
         steam_count: result.steam_count,
         heroic_count: result.heroic_count,
         bottles_count: result.bottles_count,
+        lutris_count: result.lutris_count,
     };
     std::mem::forget(games);
     list
 }
 
+fn nak_game(g: &CachedGame) -> NakGame {
+    use nak_rust::game_finder::AppType;
+
+    NakGame {
+        name: to_cstring(&g.name),
+        app_id: to_cstring(&g.app_id),
+        install_path: to_cstring(&g.install_path),
+        prefix_path: match &g.prefix_path {
+            Some(p) => to_cstring(p),
+            None => ptr::null_mut(),
+        },
+        launcher: to_cstring(&g.launcher),
+        my_games_folder: to_cstring_opt(g.my_games_folder.as_deref()),
+        appdata_local_folder: to_cstring_opt(g.appdata_local_folder.as_deref()),
+        appdata_roaming_folder: to_cstring_opt(g.appdata_roaming_folder.as_deref()),
+        registry_path: to_cstring_opt(g.registry_path.as_deref()),
+        registry_value: to_cstring_opt(g.registry_value.as_deref()),
+        prefix_free_space: g
+            .prefix_path
+            .as_deref()
+            .and_then(|p| nak_rust::utils::free_space(Path::new(p)))
+            .map(|b| b as i64)
+            .unwrap_or(-1),
+        prefix_shared: g.prefix_shared as c_int,
+        my_games_path: to_cstring_opt(g.my_games_path.as_deref()),
+        appdata_local_path: to_cstring_opt(g.appdata_local_path.as_deref()),
+        appdata_roaming_path: to_cstring_opt(g.appdata_roaming_path.as_deref()),
+        bundled_runtime_path: g
+            .bundled_proton
+            .as_ref()
+            .map_or(ptr::null_mut(), |p| to_cstring(&p.path.to_string_lossy())),
+        prefix_redirected: g.prefix_redirected as c_int,
+        prefix_created_at: g.prefix_created_at.unwrap_or(-1),
+        family_shared: g.family_shared as c_int,
+        app_type: match g.app_type {
+            AppType::Game => 0,
+            AppType::Tool => 1,
+            AppType::Application => 2,
+            AppType::Demo => 3,
+        },
+        install_state: match g.install_state {
+            nak_rust::game_finder::InstallState::FullyInstalled => 0,
+            nak_rust::game_finder::InstallState::UpdateRequired => 1,
+            nak_rust::game_finder::InstallState::Downloading => 2,
+            nak_rust::game_finder::InstallState::NotInstalled => 3,
+        },
+    }
+}
+
 /// Free a NakGameList returned by nak_detect_all_games
+///
+/// # Safety
+/// `list` must be exactly as returned by NaK and not freed before.
 #[no_mangle]
 pub unsafe extern "C" fn nak_game_list_free(list: NakGameList) {
     if list.games.is_null() {
@@ -173,17 +471,149 @@ pub unsafe extern "C" fn nak_game_list_free(list: NakGameList) {
     }
     let games = unsafe { Vec::from_raw_parts(list.games, list.count, list.count) };
     for g in games {
-        free_if_nonnull(g.name);
-        free_if_nonnull(g.app_id);
-        free_if_nonnull(g.install_path);
-        free_if_nonnull(g.prefix_path);
-        free_if_nonnull(g.launcher);
-        free_if_nonnull(g.my_games_folder);
-        free_if_nonnull(g.appdata_local_folder);
-        free_if_nonnull(g.appdata_roaming_folder);
-        free_if_nonnull(g.registry_path);
-        free_if_nonnull(g.registry_value);
+        unsafe { free_game_fields(g) };
+    }
+}
+
+/// Look up one installed Steam game by App ID without a full detection scan.
+///
+/// Returns null if the game isn't installed. Free with nak_game_free.
+///
+/// # Safety
+/// `app_id` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_find_game_by_app_id(app_id: *const c_char) -> *mut NakGame {
+    let app_id = unsafe { from_cstr(app_id) };
+    match nak_rust::game_finder::find_game_by_app_id(app_id) {
+        Some(game) => Box::into_raw(Box::new(nak_game(&cached_game(&game)))),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Free a NakGame returned by nak_find_game_by_app_id
+///
+/// # Safety
+/// `game` must be null or a game returned by `nak_find_game_by_app_id`, not freed
+/// before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_game_free(game: *mut NakGame) {
+    if game.is_null() {
+        return;
     }
+    let game = unsafe { Box::from_raw(game) };
+    unsafe { free_game_fields(*game) };
+}
+
+unsafe fn free_game_fields(g: NakGame) {
+    free_if_nonnull(g.name);
+    free_if_nonnull(g.app_id);
+    free_if_nonnull(g.install_path);
+    free_if_nonnull(g.prefix_path);
+    free_if_nonnull(g.launcher);
+    free_if_nonnull(g.my_games_folder);
+    free_if_nonnull(g.appdata_local_folder);
+    free_if_nonnull(g.appdata_roaming_folder);
+    free_if_nonnull(g.registry_path);
+    free_if_nonnull(g.registry_value);
+    free_if_nonnull(g.my_games_path);
+    free_if_nonnull(g.appdata_local_path);
+    free_if_nonnull(g.appdata_roaming_path);
+    free_if_nonnull(g.bundled_runtime_path);
+}
+
+/// Rebuild the parts of a `Game` its prefix path helpers read from a
+/// NakGame. None for a null game or one without a prefix.
+unsafe fn game_from_nak(game: *const NakGame) -> Option<nak_rust::game_finder::Game> {
+    use nak_rust::game_finder::{AppType, Game, HeroicStore, Launcher};
+
+    let g = unsafe { game.as_ref() }?;
+    let opt = |p: *mut c_char| Some(unsafe { from_cstr(p) }.to_string()).filter(|s| !s.is_empty());
+    let launcher_name = unsafe { from_cstr(g.launcher) };
+    let launcher = [
+        Launcher::Steam { is_flatpak: true, is_snap: false },
+        Launcher::Steam { is_flatpak: false, is_snap: true },
+        Launcher::Heroic { store: HeroicStore::GOG },
+        Launcher::Heroic { store: HeroicStore::Epic },
+        Launcher::Bottles,
+        Launcher::Lutris,
+    ]
+    .into_iter()
+    .find(|l| l.display_name() == launcher_name)
+    .unwrap_or(Launcher::Steam { is_flatpak: false, is_snap: false });
+
+    Some(Game {
+        name: unsafe { from_cstr(g.name) }.to_string(),
+        app_id: unsafe { from_cstr(g.app_id) }.to_string(),
+        install_path: PathBuf::from(unsafe { from_cstr(g.install_path) }),
+        prefix_path: Some(PathBuf::from(opt(g.prefix_path)?)),
+        launcher,
+        my_games_folder: opt(g.my_games_folder),
+        appdata_local_folder: opt(g.appdata_local_folder),
+        appdata_roaming_folder: opt(g.appdata_roaming_folder),
+        registry_path: opt(g.registry_path),
+        registry_value: opt(g.registry_value),
+        prefix_shared: g.prefix_shared != 0,
+        prefix_redirected: g.prefix_redirected != 0,
+        bundled_runtime: opt(g.bundled_runtime_path).map(PathBuf::from),
+        family_shared: g.family_shared != 0,
+        install_size: None,
+        app_type: AppType::Game,
+        install_state: match g.install_state {
+            1 => nak_rust::game_finder::InstallState::UpdateRequired,
+            2 => nak_rust::game_finder::InstallState::Downloading,
+            3 => nak_rust::game_finder::InstallState::NotInstalled,
+            _ => nak_rust::game_finder::InstallState::FullyInstalled,
+        },
+    })
+}
+
+fn path_to_cstring(path: Option<PathBuf>) -> *mut c_char {
+    path.map_or(ptr::null_mut(), |p| to_cstring(&p.to_string_lossy()))
+}
+
+/// The game's "My Games" folder inside its prefix, resolved now (following
+/// a redirected Documents folder). Null if the prefix or folder is unknown;
+/// otherwise free with nak_string_free.
+///
+/// # Safety
+/// `game` must be null or a game returned by `nak_find_game_by_app_id` that has not
+/// been freed.
+#[no_mangle]
+pub unsafe extern "C" fn nak_game_prefix_my_games_path(game: *const NakGame) -> *mut c_char {
+    path_to_cstring(unsafe { game_from_nak(game) }.and_then(|g| g.get_prefix_my_games_path()))
+}
+
+/// The game's AppData/Local folder inside its prefix. Null if the prefix or
+/// folder is unknown; otherwise free with nak_string_free.
+///
+/// # Safety
+/// `game` must be null or a game returned by `nak_find_game_by_app_id` that has not
+/// been freed.
+#[no_mangle]
+pub unsafe extern "C" fn nak_game_prefix_appdata_local_path(game: *const NakGame) -> *mut c_char {
+    path_to_cstring(unsafe { game_from_nak(game) }.and_then(|g| g.get_prefix_appdata_local_path()))
+}
+
+/// The game's AppData/Roaming folder inside its prefix. Null if the prefix
+/// or folder is unknown; otherwise free with nak_string_free.
+///
+/// # Safety
+/// `game` must be null or a game returned by `nak_find_game_by_app_id` that has not
+/// been freed.
+#[no_mangle]
+pub unsafe extern "C" fn nak_game_prefix_appdata_roaming_path(game: *const NakGame) -> *mut c_char {
+    path_to_cstring(unsafe { game_from_nak(game) }.and_then(|g| g.get_prefix_appdata_roaming_path()))
+}
+
+/// The Documents folder of the game's prefix user. Null if the game has no
+/// prefix; otherwise free with nak_string_free.
+///
+/// # Safety
+/// `game` must be null or a game returned by `nak_find_game_by_app_id` that has not
+/// been freed.
+#[no_mangle]
+pub unsafe extern "C" fn nak_game_prefix_documents_path(game: *const NakGame) -> *mut c_char {
+    path_to_cstring(unsafe { game_from_nak(game) }.and_then(|g| g.get_prefix_documents_path()))
 }
 
 unsafe fn free_if_nonnull(p: *mut c_char) {
@@ -204,6 +634,7 @@ pub struct NakKnownGame {
     pub registry_path: *const c_char,
     pub registry_value: *const c_char,
     pub steam_folder: *const c_char,
+    pub needs_vcredist: *const c_char, // comma-separated winetricks verbs, null if none
 }
 
 // We need to leak CStrings for the static known games list since the Rust statics
@@ -228,6 +659,11 @@ static KNOWN_GAMES_FFI: std::sync::LazyLock<KnownGamesVec> = std::sync::LazyLock
                 registry_path: leak_str(kg.registry_path),
                 registry_value: leak_str(kg.registry_value),
                 steam_folder: leak_str(kg.steam_folder),
+                needs_vcredist: if kg.needs_vcredist.is_empty() {
+                    ptr::null()
+                } else {
+                    leak_str(&kg.needs_vcredist.join(","))
+                },
             })
             .collect(),
     )
@@ -247,6 +683,9 @@ fn leak_str_opt(s: Option<&str>) -> *const c_char {
 /// Get the list of all known games (static data, do NOT free)
 ///
 /// Returns a pointer to the first element and writes the count to `out_count`.
+///
+/// # Safety
+/// `out_count` must be null or valid for writes.
 #[no_mangle]
 pub unsafe extern "C" fn nak_get_known_games(out_count: *mut usize) -> *const NakKnownGame {
     let games = &KNOWN_GAMES_FFI.0;
@@ -256,6 +695,257 @@ pub unsafe extern "C" fn nak_get_known_games(out_count: *mut usize) -> *const Na
     games.as_ptr()
 }
 
+/// Look up a known game by name (static data, do NOT free).
+///
+/// Case, punctuation and common abbreviations are ignored, so "skyrim se"
+/// finds "Skyrim Special Edition". Returns a pointer into the
+/// nak_get_known_games array, or null if no game matches.
+///
+/// # Safety
+/// `name` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_find_known_game_by_name(name: *const c_char) -> *const NakKnownGame {
+    let name = unsafe { from_cstr(name) };
+    nak_rust::game_finder::find_by_name(name)
+        .and_then(|known| {
+            nak_rust::game_finder::KNOWN_GAMES
+                .iter()
+                .position(|kg| ptr::eq(kg, known))
+        })
+        .map_or(ptr::null(), |i| &KNOWN_GAMES_FFI.0[i] as *const NakKnownGame)
+}
+
+/// A detected prefix for a known game (C-compatible)
+#[repr(C)]
+pub struct NakKnownGamePrefix {
+    pub launcher: *mut c_char, // display name string
+    pub prefix_path: *mut c_char,
+}
+
+/// List of prefixes for a known game
+#[repr(C)]
+pub struct NakKnownGamePrefixList {
+    pub prefixes: *mut NakKnownGamePrefix,
+    pub count: usize,
+}
+
+/// Every detected prefix (across launchers) for the known game with this
+/// Steam app id or name. Empty if the game isn't known.
+///
+/// # Safety
+/// `app_id_or_name` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_prefixes_for_known_game(app_id_or_name: *const c_char) -> NakKnownGamePrefixList {
+    let key = unsafe { from_cstr(app_id_or_name) };
+    let known = nak_rust::game_finder::find_by_steam_id(key)
+        .or_else(|| nak_rust::game_finder::find_by_name(key));
+
+    let mut prefixes: Vec<NakKnownGamePrefix> = known
+        .map(nak_rust::game_finder::prefixes_for_known_game)
+        .unwrap_or_default()
+        .iter()
+        .map(|(launcher, prefix)| NakKnownGamePrefix {
+            launcher: to_cstring(launcher.display_name()),
+            prefix_path: to_cstring(&prefix.to_string_lossy()),
+        })
+        .collect();
+
+    let list = NakKnownGamePrefixList {
+        prefixes: prefixes.as_mut_ptr(),
+        count: prefixes.len(),
+    };
+    std::mem::forget(prefixes);
+    list
+}
+
+/// Free a NakKnownGamePrefixList
+///
+/// # Safety
+/// `list` must be exactly as returned by NaK and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_known_game_prefix_list_free(list: NakKnownGamePrefixList) {
+    if list.prefixes.is_null() {
+        return;
+    }
+    let prefixes = unsafe { Vec::from_raw_parts(list.prefixes, list.count, list.count) };
+    for p in prefixes {
+        free_if_nonnull(p.launcher);
+        free_if_nonnull(p.prefix_path);
+    }
+}
+
+/// Detected games on one mount point (C-compatible)
+#[repr(C)]
+pub struct NakMountGroup {
+    pub mount_point: *mut c_char, // null for games whose install path is missing
+    pub app_ids: NakStringList,
+    pub names: NakStringList,
+}
+
+/// Detected games grouped by mount point
+#[repr(C)]
+pub struct NakMountGroupList {
+    pub groups: *mut NakMountGroup,
+    pub count: usize,
+}
+
+/// Group detected games by the mount point of their install directory
+#[no_mangle]
+pub extern "C" fn nak_games_by_mount() -> NakMountGroupList {
+    let result = detect_games_cached();
+    let grouped =
+        nak_rust::utils::group_by_mount(result.games.iter(), |g| Path::new(&g.install_path));
+
+    let mut groups: Box<[NakMountGroup]> = grouped
+        .into_iter()
+        .map(|(mount, games)| NakMountGroup {
+            mount_point: if mount.as_os_str().is_empty() {
+                ptr::null_mut()
+            } else {
+                to_cstring(&mount.to_string_lossy())
+            },
+            app_ids: to_string_list(games.iter().map(|g| g.app_id.clone()).collect()),
+            names: to_string_list(games.iter().map(|g| g.name.clone()).collect()),
+        })
+        .collect();
+
+    let list = NakMountGroupList {
+        groups: groups.as_mut_ptr(),
+        count: groups.len(),
+    };
+    std::mem::forget(groups);
+    list
+}
+
+/// Free a NakMountGroupList
+///
+/// # Safety
+/// `list` must be exactly as returned by NaK and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_mount_group_list_free(list: NakMountGroupList) {
+    if list.groups.is_null() {
+        return;
+    }
+    let groups = unsafe { Vec::from_raw_parts(list.groups, list.count, list.count) };
+    for g in groups {
+        free_if_nonnull(g.mount_point);
+        unsafe {
+            nak_string_list_free(g.app_ids);
+            nak_string_list_free(g.names);
+        }
+    }
+}
+
+/// Detected games sharing one install directory (C-compatible)
+#[repr(C)]
+pub struct NakInstallDirGroup {
+    pub install_dir: *mut c_char, // outermost directory of the group
+    pub app_ids: NakStringList,
+    pub names: NakStringList,
+}
+
+/// Groups of detected games sharing an install directory
+#[repr(C)]
+pub struct NakInstallDirGroupList {
+    pub groups: *mut NakInstallDirGroup,
+    pub count: usize,
+}
+
+/// Group detected games whose install directories are the same or nested
+/// inside one another (bundles, region variants). Only groups of two or more.
+#[no_mangle]
+pub extern "C" fn nak_shared_install_dirs() -> NakInstallDirGroupList {
+    let result = detect_games_cached();
+    let grouped = nak_rust::utils::group_overlapping_paths(result.games.iter(), |g| {
+        Path::new(&g.install_path)
+    });
+
+    let mut groups: Box<[NakInstallDirGroup]> = grouped
+        .into_iter()
+        .map(|(dir, games)| NakInstallDirGroup {
+            install_dir: to_cstring(&dir.to_string_lossy()),
+            app_ids: to_string_list(games.iter().map(|g| g.app_id.clone()).collect()),
+            names: to_string_list(games.iter().map(|g| g.name.clone()).collect()),
+        })
+        .collect();
+
+    let list = NakInstallDirGroupList {
+        groups: groups.as_mut_ptr(),
+        count: groups.len(),
+    };
+    std::mem::forget(groups);
+    list
+}
+
+/// Free a NakInstallDirGroupList
+///
+/// # Safety
+/// `list` must be exactly as returned by NaK and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_install_dir_group_list_free(list: NakInstallDirGroupList) {
+    if list.groups.is_null() {
+        return;
+    }
+    let groups = unsafe { Vec::from_raw_parts(list.groups, list.count, list.count) };
+    for g in groups {
+        free_if_nonnull(g.install_dir);
+        unsafe {
+            nak_string_list_free(g.app_ids);
+            nak_string_list_free(g.names);
+        }
+    }
+}
+
+/// Find a Steam game's Wine prefix, honouring a STEAM_COMPAT_DATA_PATH
+/// override in its launch options.
+///
+/// Writes 1 to `out_redirected` (may be null) if the override was used.
+/// Returns a newly allocated string (free with nak_string_free), or null.
+///
+/// # Safety
+/// `app_id` must be null or a valid NUL-terminated string. `out_redirected` must be
+/// null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn nak_find_game_prefix_path(
+    app_id: *const c_char,
+    out_redirected: *mut c_int,
+) -> *mut c_char {
+    let app_id = unsafe { from_cstr(app_id) };
+    let resolved = nak_rust::game_finder::resolve_game_prefix(app_id);
+    if !out_redirected.is_null() {
+        *out_redirected = resolved.as_ref().is_some_and(|p| p.redirected) as c_int;
+    }
+    to_cstring_opt(resolved.map(|p| p.path.to_string_lossy().into_owned()).as_deref())
+}
+
+/// Locally stored compatibility note for an app id, or null if none.
+///
+/// Never touches the network. Free with nak_string_free.
+///
+/// # Safety
+/// `app_id` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_compat_note_for(app_id: *const c_char) -> *mut c_char {
+    let app_id = unsafe { from_cstr(app_id) };
+    to_cstring_opt(nak_rust::game_finder::compat_note_for_app_id(app_id).as_deref())
+}
+
+/// Store a compatibility note for an app id. A null or empty note removes it.
+///
+/// Returns null on success, or an error message (free with nak_string_free).
+///
+/// # Safety
+/// `app_id` and `note` must each be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_set_compat_note(app_id: *const c_char, note: *const c_char) -> *mut c_char {
+    let app_id = unsafe { from_cstr(app_id) };
+    let note = unsafe { from_cstr(note) };
+    match nak_rust::game_finder::set_compat_note(app_id, Some(note)) {
+        Ok(()) => ptr::null_mut(),
+        Err(e) => error_to_cstring(e),
+    }
+}
+
 // ============================================================================
 // Tier 2: Proton Detection
 // ============================================================================
@@ -280,8 +970,10 @@ pub struct NakProtonList {
 /// Find all installed Proton versions
 #[no_mangle]
 pub extern "C" fn nak_find_steam_protons() -> NakProtonList {
-    let protons = nak_rust::steam::find_steam_protons();
+    proton_list(&nak_rust::steam::find_steam_protons())
+}
 
+fn proton_list(protons: &[nak_rust::steam::SteamProton]) -> NakProtonList {
     let mut ffi_protons: Vec<NakSteamProton> = protons
         .iter()
         .map(|p| NakSteamProton {
@@ -302,6 +994,9 @@ pub extern "C" fn nak_find_steam_protons() -> NakProtonList {
 }
 
 /// Free a NakProtonList
+///
+/// # Safety
+/// `list` must be exactly as returned by NaK and not freed before.
 #[no_mangle]
 pub unsafe extern "C" fn nak_proton_list_free(list: NakProtonList) {
     if list.protons.is_null() {
@@ -315,307 +1010,2814 @@ pub unsafe extern "C" fn nak_proton_list_free(list: NakProtonList) {
     }
 }
 
-// ============================================================================
-// Tier 3: Steam Paths
-// ============================================================================
-
-/// Find the Steam installation path
+/// Check that a Proton install is intact: the `proton` script plus an
+/// executable wine and wineserver under files/bin or dist/bin.
 ///
-/// Returns a newly allocated string (caller must free with nak_string_free),
-/// or null if Steam is not found.
+/// Returns null if it is usable, or an error saying what is missing or
+/// which folder to select instead (free with nak_string_free).
+///
+/// # Safety
+/// `proton_path` must be null or a valid NUL-terminated string.
 #[no_mangle]
-pub extern "C" fn nak_find_steam_path() -> *mut c_char {
-    match nak_rust::steam::find_steam_path() {
-        Some(path) => to_cstring(&path.to_string_lossy()),
-        None => ptr::null_mut(),
+pub unsafe extern "C" fn nak_verify_proton(proton_path: *const c_char) -> *mut c_char {
+    let path = unsafe { from_cstr(proton_path) };
+    if path.is_empty() {
+        return to_cstring("No Proton path given");
     }
-}
-
+    match nak_rust::steam::verify_proton_install(Path::new(path)) {
+        Ok(()) => ptr::null_mut(),
+        Err(e) => to_cstring(&e),
+    }
+}
+
+/// Custom compatibility tools of the same family (C-compatible)
+#[repr(C)]
+pub struct NakProtonGroup {
+    pub protons: NakProtonList, // newest first
+}
+
+/// List of duplicate tool groups
+#[repr(C)]
+pub struct NakProtonGroupList {
+    pub groups: *mut NakProtonGroup,
+    pub count: usize,
+}
+
+/// Group custom compatibility tools that are builds of the same family
+#[no_mangle]
+pub extern "C" fn nak_find_duplicate_tools() -> NakProtonGroupList {
+    let mut groups: Vec<NakProtonGroup> = nak_rust::steam::find_duplicate_tools()
+        .iter()
+        .map(|g| NakProtonGroup {
+            protons: proton_list(g),
+        })
+        .collect();
+
+    let list = NakProtonGroupList {
+        groups: groups.as_mut_ptr(),
+        count: groups.len(),
+    };
+    std::mem::forget(groups);
+    list
+}
+
+/// Free a NakProtonGroupList
+///
+/// # Safety
+/// `list` must be exactly as returned by NaK and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_proton_group_list_free(list: NakProtonGroupList) {
+    if list.groups.is_null() {
+        return;
+    }
+    let groups = unsafe { Vec::from_raw_parts(list.groups, list.count, list.count) };
+    for g in groups {
+        unsafe { nak_proton_list_free(g.protons) };
+    }
+}
+
+/// A Wine/Proton runner downloaded by Heroic (C-compatible)
+#[repr(C)]
+pub struct NakHeroicRunner {
+    pub name: *mut c_char,
+    pub runner_type: *mut c_char, // e.g. "Proton-GE", "Wine-GE"
+    pub path: *mut c_char,
+}
+
+/// List of Heroic runners
+#[repr(C)]
+pub struct NakHeroicRunnerList {
+    pub runners: *mut NakHeroicRunner,
+    pub count: usize,
+}
+
+/// List the Wine/Proton runners managed by Heroic
+#[no_mangle]
+pub extern "C" fn nak_list_heroic_runners() -> NakHeroicRunnerList {
+    let runners = nak_rust::game_finder::list_heroic_runners();
+
+    let mut ffi_runners: Vec<NakHeroicRunner> = runners
+        .iter()
+        .map(|r| NakHeroicRunner {
+            name: to_cstring(&r.name),
+            runner_type: to_cstring(&r.runner_type),
+            path: to_cstring(&r.path.to_string_lossy()),
+        })
+        .collect();
+
+    let list = NakHeroicRunnerList {
+        runners: ffi_runners.as_mut_ptr(),
+        count: ffi_runners.len(),
+    };
+    std::mem::forget(ffi_runners);
+    list
+}
+
+/// Free a NakHeroicRunnerList
+///
+/// # Safety
+/// `list` must be exactly as returned by NaK and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_heroic_runner_list_free(list: NakHeroicRunnerList) {
+    if list.runners.is_null() {
+        return;
+    }
+    let runners = unsafe { Vec::from_raw_parts(list.runners, list.count, list.count) };
+    for r in runners {
+        free_if_nonnull(r.name);
+        free_if_nonnull(r.runner_type);
+        free_if_nonnull(r.path);
+    }
+}
+
+/// An installed Steam Linux Runtime tool (C-compatible)
+#[repr(C)]
+pub struct NakRuntimeInfo {
+    pub name: *mut c_char,
+    pub codename: *mut c_char,
+    pub path: *mut c_char,
+    pub version: *mut c_char,                 // null if unknown
+    pub pressure_vessel_version: *mut c_char, // null if unknown
+}
+
+/// List of Steam Linux Runtime tools
+#[repr(C)]
+pub struct NakRuntimeInfoList {
+    pub runtimes: *mut NakRuntimeInfo,
+    pub count: usize,
+}
+
+/// List installed Steam Linux Runtime (pressure-vessel) tools and their versions
+#[no_mangle]
+pub extern "C" fn nak_runtime_versions() -> NakRuntimeInfoList {
+    let runtimes = nak_rust::steam::runtime_versions();
+
+    let mut ffi_runtimes: Vec<NakRuntimeInfo> = runtimes
+        .iter()
+        .map(|r| NakRuntimeInfo {
+            name: to_cstring(&r.name),
+            codename: to_cstring(&r.codename),
+            path: to_cstring(&r.path.to_string_lossy()),
+            version: to_cstring_opt(r.version.as_deref()),
+            pressure_vessel_version: to_cstring_opt(r.pressure_vessel_version.as_deref()),
+        })
+        .collect();
+
+    let list = NakRuntimeInfoList {
+        runtimes: ffi_runtimes.as_mut_ptr(),
+        count: ffi_runtimes.len(),
+    };
+    std::mem::forget(ffi_runtimes);
+    list
+}
+
+/// Free a NakRuntimeInfoList
+///
+/// # Safety
+/// `list` must be exactly as returned by NaK and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_runtime_info_list_free(list: NakRuntimeInfoList) {
+    if list.runtimes.is_null() {
+        return;
+    }
+    let runtimes = unsafe { Vec::from_raw_parts(list.runtimes, list.count, list.count) };
+    for r in runtimes {
+        free_if_nonnull(r.name);
+        free_if_nonnull(r.codename);
+        free_if_nonnull(r.path);
+        free_if_nonnull(r.version);
+        free_if_nonnull(r.pressure_vessel_version);
+    }
+}
+
+/// Verify a downloaded Proton tarball before extraction: its SHA-256 (hex;
+/// skipped when `expected_sha256` is null or empty) and that it is a
+/// gzip/xz/zstd/plain tar.
+///
+/// Returns null if the archive is usable, or an error message (caller must free with nak_string_free).
+///
+/// # Safety
+/// `archive_path` and `expected_sha256` must each be null or a valid NUL-terminated
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_verify_proton_archive(
+    archive_path: *const c_char,
+    expected_sha256: *const c_char,
+) -> *mut c_char {
+    let archive = unsafe { from_cstr(archive_path) };
+    let expected = unsafe { from_cstr(expected_sha256) };
+    let expected = (!expected.is_empty()).then_some(expected);
+    match nak_rust::steam::verify_archive(Path::new(archive), expected) {
+        Ok(()) => ptr::null_mut(),
+        Err(e) => error_to_cstring(e),
+    }
+}
+
+// ============================================================================
+// Tier 3: Steam Paths
+// ============================================================================
+
+/// Find the Steam installation path
+///
+/// Returns a newly allocated string (caller must free with nak_string_free),
+/// or null if Steam is not found.
+#[no_mangle]
+pub extern "C" fn nak_find_steam_path() -> *mut c_char {
+    match nak_rust::steam::find_steam_path() {
+        Some(path) => to_cstring(&path.to_string_lossy()),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Whether the Steam client is running: 1 yes, 0 no. Config writers refuse
+/// to touch Steam's VDF files while it is.
+#[no_mangle]
+pub extern "C" fn nak_steam_is_running() -> c_int {
+    nak_rust::steam::is_running() as c_int
+}
+
+/// The Steam account (userdata folder name) chosen in NaK's config.
+///
+/// Returns a newly allocated string (caller must free with nak_string_free),
+/// or null when none is selected and the account is auto-detected.
+#[no_mangle]
+pub extern "C" fn nak_get_selected_steam_account() -> *mut c_char {
+    let account = nak_rust::config::AppConfig::load().selected_steam_account;
+    to_cstring_opt(Some(account.as_str()).filter(|a| !a.is_empty()))
+}
+
+/// Select the Steam account whose userdata NaK reads and writes, and save
+/// the config. Null or "" clears the selection (auto-detect again).
+///
+/// Drops the cached game scan, since launch options come from the account.
+///
+/// # Safety
+/// `account_id` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_set_selected_steam_account(account_id: *const c_char) {
+    let account = unsafe { from_cstr(account_id) }.trim().to_string();
+
+    let mut config = nak_rust::config::AppConfig::load();
+    config.selected_steam_account = account;
+    config.save();
+    nak_invalidate_game_cache();
+}
+
+/// A Steam account from loginusers.vdf (C-compatible)
+#[repr(C)]
+pub struct NakSteamAccount {
+    pub account_id: *mut c_char,   // userdata folder name (SteamID3)
+    pub persona_name: *mut c_char,
+    pub most_recent: c_int,        // 1 if it was the last account to log in
+    pub timestamp: u64,            // last login, unix seconds
+}
+
+/// List of Steam accounts
+#[repr(C)]
+pub struct NakSteamAccountList {
+    pub accounts: *mut NakSteamAccount,
+    pub count: usize,
+}
+
+/// List the Steam accounts that have logged in on this machine, most
+/// recently used first. Free with nak_steam_account_list_free.
+#[no_mangle]
+pub extern "C" fn nak_get_steam_accounts() -> NakSteamAccountList {
+    // A boxed slice: the free function rebuilds the Vec with capacity == count
+    let mut accounts: Box<[NakSteamAccount]> = nak_rust::steam::get_steam_accounts()
+        .into_iter()
+        .map(|a| NakSteamAccount {
+            account_id: to_cstring(&a.account_id),
+            persona_name: to_cstring(&a.persona_name),
+            most_recent: a.most_recent as c_int,
+            timestamp: a.timestamp,
+        })
+        .collect();
+
+    let list = NakSteamAccountList {
+        accounts: accounts.as_mut_ptr(),
+        count: accounts.len(),
+    };
+    std::mem::forget(accounts);
+    list
+}
+
+/// Free a NakSteamAccountList
+///
+/// # Safety
+/// `list` must be exactly as returned by NaK and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_steam_account_list_free(list: NakSteamAccountList) {
+    if list.accounts.is_null() {
+        return;
+    }
+    let accounts = unsafe { Vec::from_raw_parts(list.accounts, list.count, list.count) };
+    for a in accounts {
+        unsafe {
+            free_if_nonnull(a.account_id);
+            free_if_nonnull(a.persona_name);
+        }
+    }
+}
+
+/// A candidate Steam root and what was found there (C-compatible)
+#[repr(C)]
+pub struct NakSteamPathCheck {
+    pub path: *mut c_char,
+    pub state: c_int, // 0 = missing, 1 = dangling symlink, 2 = symlink loop, 3 = no steamapps, 4 = valid
+}
+
+/// Explanation of Steam path detection (C-compatible)
+#[repr(C)]
+pub struct NakSteamDiagnosis {
+    pub steam_path: *mut c_char, // null if Steam was not found
+    pub guidance: *mut c_char,
+    pub flatpak_steam_installed: c_int,
+    pub snap_steam_installed: c_int,
+    pub nak_sandboxed: c_int,
+    pub candidates: *mut NakSteamPathCheck,
+    pub candidate_count: usize,
+}
+
+/// Explain why Steam was (or wasn't) found, with actionable guidance
+#[no_mangle]
+pub extern "C" fn nak_diagnose_steam() -> NakSteamDiagnosis {
+    use nak_rust::steam::SteamPathState;
+
+    let diagnosis = nak_rust::steam::diagnose_steam();
+    let mut candidates: Vec<NakSteamPathCheck> = diagnosis
+        .candidates
+        .iter()
+        .map(|(path, state)| NakSteamPathCheck {
+            path: to_cstring(&path.to_string_lossy()),
+            state: match state {
+                SteamPathState::Missing => 0,
+                SteamPathState::DanglingSymlink => 1,
+                SteamPathState::SymlinkLoop => 2,
+                SteamPathState::NoSteamapps => 3,
+                SteamPathState::Valid(_) => 4,
+            },
+        })
+        .collect();
+
+    let result = NakSteamDiagnosis {
+        steam_path: to_cstring_opt(
+            diagnosis
+                .steam_path
+                .as_ref()
+                .map(|p| p.to_string_lossy())
+                .as_deref(),
+        ),
+        guidance: to_cstring(&diagnosis.guidance),
+        flatpak_steam_installed: diagnosis.flatpak_steam_installed as c_int,
+        snap_steam_installed: diagnosis.snap_steam_installed as c_int,
+        nak_sandboxed: diagnosis.nak_sandboxed as c_int,
+        candidates: candidates.as_mut_ptr(),
+        candidate_count: candidates.len(),
+    };
+    std::mem::forget(candidates);
+    result
+}
+
+/// Free a NakSteamDiagnosis
+///
+/// # Safety
+/// `diagnosis` must be exactly as returned by NaK and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_steam_diagnosis_free(diagnosis: NakSteamDiagnosis) {
+    free_if_nonnull(diagnosis.steam_path);
+    free_if_nonnull(diagnosis.guidance);
+    if diagnosis.candidates.is_null() {
+        return;
+    }
+    let candidates = unsafe {
+        Vec::from_raw_parts(diagnosis.candidates, diagnosis.candidate_count, diagnosis.candidate_count)
+    };
+    for c in candidates {
+        free_if_nonnull(c.path);
+    }
+}
+
+/// Persona name of the Steam account NaK is using (configured or auto-detected).
+///
+/// Returns a newly allocated string (caller must free with nak_string_free),
+/// or null if no account could be resolved.
+#[no_mangle]
+pub extern "C" fn nak_selected_account_name() -> *mut c_char {
+    to_cstring_opt(nak_rust::steam::selected_account_name().as_deref())
+}
+
+/// Check whether Steam Play is enabled for all titles.
+///
+/// Returns 1 if enabled, 0 otherwise. If `out_default_tool` is non-null it
+/// receives the default tool's internal name (free with nak_string_free),
+/// or null when disabled.
+///
+/// # Safety
+/// `out_default_tool` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn nak_steam_play_enabled(out_default_tool: *mut *mut c_char) -> c_int {
+    let status = nak_rust::steam::steam_play_enabled();
+    if !out_default_tool.is_null() {
+        *out_default_tool = to_cstring_opt(status.default_tool.as_deref());
+    }
+    status.enabled_for_all as c_int
+}
+
+/// Steam's download region (cell ID) from config.vdf, or 0 if unknown.
+/// Usable as a hint for picking a nearby download mirror.
+#[no_mangle]
+pub extern "C" fn nak_download_region() -> u32 {
+    nak_rust::steam::download_region().unwrap_or(0)
+}
+
+/// Check whether Steam Cloud sync is enabled for a game.
+///
+/// Returns 1 if enabled, 0 if disabled, -1 if it can't be determined.
+#[no_mangle]
+pub extern "C" fn nak_cloud_sync_enabled(app_id: u32) -> c_int {
+    match nak_rust::steam::cloud_sync_enabled(app_id) {
+        Some(true) => 1,
+        Some(false) => 0,
+        None => -1,
+    }
+}
+
+/// A saved Steam Input controller configuration (C-compatible)
+#[repr(C)]
+pub struct NakControllerConfig {
+    pub controller_type: *mut c_char, // e.g. "controller_ps5"
+    pub name: *mut c_char,            // null if the config has no title
+    pub path: *mut c_char,
+}
+
+/// Steam Input setup for a game (C-compatible)
+#[repr(C)]
+pub struct NakInputConfig {
+    pub found: c_int,
+    /// -1 no per-game override, 0 forced off, 1 controller defaults, 2 forced on
+    pub steam_input: c_int,
+    pub configs: *mut NakControllerConfig,
+    pub count: usize,
+}
+
+/// Look up a game's Steam Input override and saved controller configs.
+///
+/// `found` is 0 when Steam has neither for this game.
+/// Free with nak_input_config_free.
+#[no_mangle]
+pub extern "C" fn nak_game_input_config(app_id: u32) -> NakInputConfig {
+    use nak_rust::steam::SteamInputSetting;
+
+    let Some(info) = nak_rust::steam::game_input_config(app_id) else {
+        return NakInputConfig {
+            found: 0,
+            steam_input: -1,
+            configs: ptr::null_mut(),
+            count: 0,
+        };
+    };
+
+    let mut configs: Vec<NakControllerConfig> = info
+        .configs
+        .iter()
+        .map(|c| NakControllerConfig {
+            controller_type: to_cstring(&c.controller_type),
+            name: to_cstring_opt(c.name.as_deref()),
+            path: to_cstring(&c.path.to_string_lossy()),
+        })
+        .collect();
+
+    let result = NakInputConfig {
+        found: 1,
+        steam_input: match info.setting {
+            None => -1,
+            Some(SteamInputSetting::Disabled) => 0,
+            Some(SteamInputSetting::Default) => 1,
+            Some(SteamInputSetting::Enabled) => 2,
+        },
+        configs: configs.as_mut_ptr(),
+        count: configs.len(),
+    };
+    std::mem::forget(configs);
+    result
+}
+
+/// Free a NakInputConfig
+///
+/// # Safety
+/// `info` must be exactly as returned by NaK and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_input_config_free(info: NakInputConfig) {
+    if info.configs.is_null() {
+        return;
+    }
+    let configs = unsafe { Vec::from_raw_parts(info.configs, info.count, info.count) };
+    for c in configs {
+        free_if_nonnull(c.controller_type);
+        free_if_nonnull(c.name);
+        free_if_nonnull(c.path);
+    }
+}
+
+/// Shader cache size for one app (C-compatible)
+#[repr(C)]
+pub struct NakShaderCache {
+    pub app_id: u32,
+    pub size: u64,
+}
+
+/// List of per-app shader cache sizes
+#[repr(C)]
+pub struct NakShaderCacheList {
+    pub caches: *mut NakShaderCache,
+    pub count: usize,
+}
+
+/// List shader cache sizes per app across all Steam libraries (largest first)
+#[no_mangle]
+pub extern "C" fn nak_shadercache_sizes() -> NakShaderCacheList {
+    let mut caches: Box<[NakShaderCache]> = nak_rust::steam::shadercache_sizes()
+        .into_iter()
+        .map(|(app_id, size)| NakShaderCache { app_id, size })
+        .collect();
+
+    let list = NakShaderCacheList {
+        caches: caches.as_mut_ptr(),
+        count: caches.len(),
+    };
+    std::mem::forget(caches);
+    list
+}
+
+/// Free a NakShaderCacheList
+///
+/// # Safety
+/// `list` must be exactly as returned by NaK and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_shadercache_list_free(list: NakShaderCacheList) {
+    if list.caches.is_null() {
+        return;
+    }
+    let _ = unsafe { Vec::from_raw_parts(list.caches, list.count, list.count) };
+}
+
+/// Size of one game's Fossilize/Vulkan pipeline caches across all libraries
+#[no_mangle]
+pub extern "C" fn nak_fossilize_cache_size(app_id: u32) -> u64 {
+    nak_rust::steam::fossilize_cache_size(app_id)
+}
+
+/// Remove one game's Fossilize/Vulkan pipeline caches (never saves or game data).
+///
+/// Writes the number of bytes freed to `out_freed` (if non-null).
+/// Returns null on success, or an error message (caller must free with nak_string_free).
+///
+/// # Safety
+/// `out_freed` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn nak_clear_fossilize_cache(app_id: u32, out_freed: *mut u64) -> *mut c_char {
+    match nak_rust::steam::clear_fossilize_cache(app_id) {
+        Ok(freed) => {
+            if !out_freed.is_null() {
+                *out_freed = freed;
+            }
+            ptr::null_mut()
+        }
+        Err(e) => error_to_cstring(e),
+    }
+}
+
+/// Per-app compatdata (prefix) size (C-compatible)
+#[repr(C)]
+pub struct NakCompatdataSize {
+    pub app_id: u32,
+    pub size: u64,
+}
+
+/// List of per-app compatdata sizes
+#[repr(C)]
+pub struct NakCompatdataSizeList {
+    pub sizes: *mut NakCompatdataSize,
+    pub count: usize,
+}
+
+/// List compatdata sizes per app across all Steam libraries (largest first).
+///
+/// `cancel_flag` may point to an int set non-zero to stop the walk early
+/// (may be null); a cancelled walk returns an empty list.
+///
+/// # Safety
+/// `cancel_flag` must be null or point to an int that stays valid until the call
+/// returns.
+#[no_mangle]
+pub unsafe extern "C" fn nak_compatdata_sizes(cancel_flag: *const c_int) -> NakCompatdataSizeList {
+    let watch = CancelWatch::new(cancel_flag);
+    let mut sizes: Box<[NakCompatdataSize]> =
+        nak_rust::steam::compatdata_sizes_cancellable(&watch.cancel)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(app_id, size)| NakCompatdataSize { app_id, size })
+            .collect();
+    drop(watch);
+
+    let list = NakCompatdataSizeList {
+        sizes: sizes.as_mut_ptr(),
+        count: sizes.len(),
+    };
+    std::mem::forget(sizes);
+    list
+}
+
+/// Free a NakCompatdataSizeList
+///
+/// # Safety
+/// `list` must be exactly as returned by NaK and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_compatdata_size_list_free(list: NakCompatdataSizeList) {
+    if list.sizes.is_null() {
+        return;
+    }
+    let _ = unsafe { Vec::from_raw_parts(list.sizes, list.count, list.count) };
+}
+
+/// Remove one game's shader cache from every Steam library.
+///
+/// Writes the number of bytes freed to `out_freed` (if non-null).
+/// Returns null on success, or an error message (caller must free with nak_string_free).
+///
+/// # Safety
+/// `out_freed` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn nak_clear_shadercache(app_id: u32, out_freed: *mut u64) -> *mut c_char {
+    match nak_rust::steam::clear_shadercache(app_id) {
+        Ok(freed) => {
+            if !out_freed.is_null() {
+                *out_freed = freed;
+            }
+            ptr::null_mut()
+        }
+        Err(e) => error_to_cstring(e),
+    }
+}
+
+/// Write the current STEAM_COMPAT_MOUNTS into each app's launch options.
+///
+/// Other launch options are preserved. Steam should be closed first.
+/// Writes the number of apps changed to `out_updated` (if non-null).
+/// Returns null on success, or an error message (caller must free with nak_string_free).
+///
+/// # Safety
+/// `app_ids` must be null or point to `count` values. `out_updated` must be null or
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn nak_sync_compat_mounts(
+    app_ids: *const u32,
+    count: usize,
+    out_updated: *mut usize,
+) -> *mut c_char {
+    let ids = if app_ids.is_null() || count == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(app_ids, count) }
+    };
+
+    match nak_rust::steam::sync_compat_mounts(ids) {
+        Ok(updated) => {
+            if !out_updated.is_null() {
+                *out_updated = updated;
+            }
+            ptr::null_mut()
+        }
+        Err(e) => error_to_cstring(e),
+    }
+}
+
+/// A Vulkan-capable GPU (C-compatible)
+#[repr(C)]
+pub struct NakVulkanDevice {
+    pub name: *mut c_char,
+    pub vendor_id: u32,
+    pub device_id: u32,
+    pub device_type: c_int, // 0 = discrete, 1 = integrated, 2 = virtual, 3 = other
+}
+
+/// List of Vulkan GPUs
+#[repr(C)]
+pub struct NakVulkanDeviceList {
+    pub devices: *mut NakVulkanDevice,
+    pub count: usize,
+}
+
+/// List Vulkan-capable GPUs (discrete first). Empty if vulkaninfo is missing.
+#[no_mangle]
+pub extern "C" fn nak_vulkan_devices() -> NakVulkanDeviceList {
+    use nak_rust::steam::VulkanDeviceType;
+
+    let mut devices: Vec<NakVulkanDevice> = nak_rust::steam::vulkan_devices()
+        .iter()
+        .map(|d| NakVulkanDevice {
+            name: to_cstring(&d.name),
+            vendor_id: d.vendor_id,
+            device_id: d.device_id,
+            device_type: match d.device_type {
+                VulkanDeviceType::Discrete => 0,
+                VulkanDeviceType::Integrated => 1,
+                VulkanDeviceType::Virtual => 2,
+                VulkanDeviceType::Other => 3,
+            },
+        })
+        .collect();
+
+    let list = NakVulkanDeviceList {
+        devices: devices.as_mut_ptr(),
+        count: devices.len(),
+    };
+    std::mem::forget(devices);
+    list
+}
+
+/// Free a NakVulkanDeviceList
+///
+/// # Safety
+/// `list` must be exactly as returned by NaK and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_vulkan_device_list_free(list: NakVulkanDeviceList) {
+    if list.devices.is_null() {
+        return;
+    }
+    let devices = unsafe { Vec::from_raw_parts(list.devices, list.count, list.count) };
+    for d in devices {
+        free_if_nonnull(d.name);
+    }
+}
+
+/// Generate Steam launch options (DXVK config, STEAM_COMPAT_MOUNTS and,
+/// if `gpu_name` names a Vulkan device, the env vars selecting that GPU).
+///
+/// `dxvk_conf_path` and `gpu_name` may be null.
+/// Returns a newly allocated string (caller must free with nak_string_free).
+///
+/// # Safety
+/// `dxvk_conf_path` and `gpu_name` must each be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_generate_launch_options(
+    dxvk_conf_path: *const c_char,
+    is_electron_app: c_int,
+    gpu_name: *const c_char,
+) -> *mut c_char {
+    unsafe { nak_generate_launch_options_for_app(dxvk_conf_path, is_electron_app, gpu_name, ptr::null()) }
+}
+
+/// nak_generate_launch_options plus the recommended controller env vars for
+/// `app_id` if it is a known game.
+///
+/// `dxvk_conf_path`, `gpu_name` and `app_id` may be null.
+/// Returns a newly allocated string (caller must free with nak_string_free).
+///
+/// # Safety
+/// `dxvk_conf_path`, `gpu_name` and `app_id` must each be null or a valid
+/// NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_generate_launch_options_for_app(
+    dxvk_conf_path: *const c_char,
+    is_electron_app: c_int,
+    gpu_name: *const c_char,
+    app_id: *const c_char,
+) -> *mut c_char {
+    unsafe {
+        nak_generate_launch_options_with_mounts(
+            dxvk_conf_path,
+            is_electron_app,
+            gpu_name,
+            app_id,
+            ptr::null(),
+            0,
+            ptr::null(),
+            0,
+        )
+    }
+}
+
+/// nak_generate_launch_options_for_app with STEAM_COMPAT_MOUNTS adjusted: the
+/// `extra_count` paths in `extra_mounts` are added to the detected
+/// directories and the `excluded_count` paths in `excluded` removed.
+///
+/// Either array may be null. Returns a newly allocated string (caller must
+/// free with nak_string_free).
+///
+/// # Safety
+/// `dxvk_conf_path`, `gpu_name` and `app_id` must each be null or a valid
+/// NUL-terminated string. `extra_mounts` must be null or point to `extra_count`
+/// strings, each null or NUL-terminated. `excluded` must be null or point to
+/// `excluded_count` strings, each null or NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn nak_generate_launch_options_with_mounts(
+    dxvk_conf_path: *const c_char,
+    is_electron_app: c_int,
+    gpu_name: *const c_char,
+    app_id: *const c_char,
+    extra_mounts: *const *const c_char,
+    extra_count: usize,
+    excluded: *const *const c_char,
+    excluded_count: usize,
+) -> *mut c_char {
+    let dxvk_conf = unsafe { from_cstr(dxvk_conf_path) };
+    let gpu_name = unsafe { from_cstr(gpu_name) };
+    let app_id = unsafe { from_cstr(app_id) };
+    let extra_mounts: Vec<String> = unsafe { cstr_array(extra_mounts, extra_count) }
+        .into_iter()
+        .map(String::from)
+        .collect();
+    let excluded: Vec<String> = unsafe { cstr_array(excluded, excluded_count) }
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+    let gpu = if gpu_name.is_empty() {
+        None
+    } else {
+        nak_rust::steam::vulkan_devices()
+            .into_iter()
+            .find(|d| d.name == gpu_name)
+    };
+
+    let options = nak_rust::steam::generate_launch_options_with_mounts(
+        (!dxvk_conf.is_empty()).then(|| Path::new(dxvk_conf)),
+        is_electron_app != 0,
+        gpu.as_ref(),
+        &nak_rust::game_finder::controller_env_for_app_id(app_id),
+        &extra_mounts,
+        &excluded,
+    );
+    to_cstring(&options)
+}
+
+/// Root directories nak_generate_launch_options puts in STEAM_COMPAT_MOUNTS
+/// (e.g. "/mnt"), sorted.
+///
+/// Writes the number of entries to `out_count` (may be null). Returns null
+/// when there are none; otherwise free with nak_string_array_free.
+///
+/// # Safety
+/// `out_count` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn nak_detect_extra_mounts(out_count: *mut usize) -> *mut *mut c_char {
+    let list = to_string_list(nak_rust::steam::detect_extra_mounts());
+    if !out_count.is_null() {
+        unsafe { *out_count = list.count };
+    }
+    if list.count == 0 {
+        unsafe { nak_string_list_free(list) };
+        return ptr::null_mut();
+    }
+    list.items
+}
+
+/// Recommended controller-related env vars for a known game, as "KEY=VALUE"
+/// items. Empty for unknown games. Free with nak_string_list_free.
+///
+/// # Safety
+/// `app_id` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_controller_env_for(app_id: *const c_char) -> NakStringList {
+    let app_id = unsafe { from_cstr(app_id) };
+    to_string_list(
+        nak_rust::game_finder::controller_env_for_app_id(app_id)
+            .into_iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect(),
+    )
+}
+
+// ============================================================================
+// Tier 4: Dependency Installation (callback-based)
+// ============================================================================
+
+/// Callback for status messages: fn(message: *const c_char)
+pub type NakStatusCallback = Option<unsafe extern "C" fn(*const c_char)>;
+
+/// Callback for log messages: fn(message: *const c_char)
+pub type NakLogCallback = Option<unsafe extern "C" fn(*const c_char)>;
+
+/// Callback for progress updates: fn(progress: f32) where 0.0..=1.0
+pub type NakProgressCallback = Option<unsafe extern "C" fn(c_float)>;
+
+/// Cause of an installer error, reported through an optional `out_code`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NakErrorCode {
+    Success = 0,
+    NetworkError = 1,
+    IoError = 2,
+    Cancelled = 3,
+    ProtonNotFound = 4,
+    PrefixInvalid = 5,
+    Unknown = 6,
+}
+
+impl NakErrorCode {
+    fn from_error(e: &(dyn std::error::Error + 'static)) -> Self {
+        use nak_rust::installers::InstallErrorKind;
+        match nak_rust::installers::error_kind(e) {
+            InstallErrorKind::Network => Self::NetworkError,
+            InstallErrorKind::Io => Self::IoError,
+            InstallErrorKind::Cancelled => Self::Cancelled,
+            InstallErrorKind::ProtonNotFound => Self::ProtonNotFound,
+            InstallErrorKind::PrefixInvalid => Self::PrefixInvalid,
+            InstallErrorKind::Other => Self::Unknown,
+        }
+    }
+}
+
+/// Store `code` through an optional out-pointer
+unsafe fn set_error_code(out_code: *mut NakErrorCode, code: NakErrorCode) {
+    if let Some(out) = unsafe { out_code.as_mut() } {
+        *out = code;
+    }
+}
+
+/// Report an installer result through `out_code`; returns the error message
+/// or null
+unsafe fn install_result(
+    result: Result<(), (NakErrorCode, String)>,
+    out_code: *mut NakErrorCode,
+) -> *mut c_char {
+    match result {
+        Ok(()) => {
+            unsafe { set_error_code(out_code, NakErrorCode::Success) };
+            ptr::null_mut()
+        }
+        Err((code, msg)) => {
+            unsafe { set_error_code(out_code, code) };
+            to_cstring(&msg)
+        }
+    }
+}
+
+/// Pair an installer error with its code
+fn coded(e: Box<dyn std::error::Error>) -> (NakErrorCode, String) {
+    (NakErrorCode::from_error(e.as_ref()), e.to_string())
+}
+
+/// Look up a Proton build by install path: an installed one, or a runtime
+/// bundled with a detected game
+fn proton_by_path(proton_path: &str) -> Result<nak_rust::steam::SteamProton, (NakErrorCode, String)> {
+    nak_rust::steam::find_steam_protons()
+        .into_iter()
+        .chain(detect_games_cached().games.into_iter().filter_map(|g| g.bundled_proton))
+        .find(|p| p.path.to_string_lossy() == proton_path)
+        .ok_or_else(|| {
+            (
+                NakErrorCode::ProtonNotFound,
+                format!("Proton not found at path: {}", proton_path),
+            )
+        })
+}
+
+/// Mirrors a C cancel flag into an AtomicBool by polling it on a helper
+/// thread until dropped
+struct CancelWatch {
+    cancel: Arc<AtomicBool>,
+    done: Arc<AtomicBool>,
+    handle: Option<std::thread::JoinHandle<()>>,
+}
+
+impl CancelWatch {
+    fn new(cancel_flag: *const c_int) -> Self {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let done = Arc::new(AtomicBool::new(false));
+        if cancel_flag.is_null() {
+            return Self { cancel, done, handle: None };
+        }
+
+        let (cancel_clone, done_clone) = (cancel.clone(), done.clone());
+        let cancel_flag_ptr = cancel_flag as usize; // safe to send across threads
+        let handle = std::thread::spawn(move || {
+            while !done_clone.load(Ordering::Relaxed) {
+                let flag = unsafe { std::ptr::read_volatile(cancel_flag_ptr as *const c_int) };
+                if flag != 0 {
+                    cancel_clone.store(true, Ordering::Relaxed);
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(100));
+            }
+        });
+        Self { cancel, done, handle: Some(handle) }
+    }
+}
+
+impl Drop for CancelWatch {
+    fn drop(&mut self) {
+        self.done.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Install all Wine prefix dependencies (winetricks, .NET, registry, etc.)
+///
+/// This is a blocking call. Use callbacks for progress updates.
+/// `cancel_flag` should point to an int that can be set to non-zero to cancel.
+///
+/// Returns null on success, or an error message (caller must free with nak_string_free).
+/// If `out_code` is non-null it receives the cause (Success on success).
+///
+/// # Safety
+/// `prefix_path`, `proton_name` and `proton_path` must each be null or a valid
+/// NUL-terminated string. `out_code` must be null or valid for writes. `cancel_flag`
+/// must be null or point to an int that stays valid until the call returns.
+#[no_mangle]
+pub unsafe extern "C" fn nak_install_all_dependencies(
+    prefix_path: *const c_char,
+    proton_name: *const c_char,
+    proton_path: *const c_char,
+    status_cb: NakStatusCallback,
+    log_cb: NakLogCallback,
+    progress_cb: NakProgressCallback,
+    cancel_flag: *const c_int,
+    app_id: u32,
+    out_code: *mut NakErrorCode,
+) -> *mut c_char {
+    let _proton_name = unsafe { from_cstr(proton_name) };
+    let callbacks = InstallCallbacks {
+        status_cb,
+        log_cb,
+        progress_cb,
+    };
+    let result = install_dependencies(
+        unsafe { from_cstr(prefix_path) },
+        unsafe { from_cstr(proton_path) },
+        callbacks,
+        cancel_flag,
+        app_id,
+    );
+    unsafe { install_result(result, out_code) }
+}
+
+/// The C callbacks of an install; plain function pointers, so they can move
+/// to a worker thread
+#[derive(Clone, Copy)]
+struct InstallCallbacks {
+    status_cb: NakStatusCallback,
+    log_cb: NakLogCallback,
+    progress_cb: NakProgressCallback,
+}
+
+/// Shared body of the blocking and background dependency installs
+fn install_dependencies(
+    prefix: &str,
+    proton_path: &str,
+    callbacks: InstallCallbacks,
+    cancel_flag: *const c_int,
+    app_id: u32,
+) -> Result<(), (NakErrorCode, String)> {
+    let proton = proton_by_path(proton_path)?;
+
+    // Mirrors the C cancel flag until the install returns
+    let watch = CancelWatch::new(cancel_flag);
+    let InstallCallbacks {
+        status_cb,
+        log_cb,
+        progress_cb,
+    } = callbacks;
+
+    let ctx = nak_rust::installers::TaskContext::new(
+        move |msg| {
+            if let Some(cb) = status_cb {
+                let c = CString::new(msg).unwrap_or_default();
+                unsafe { cb(c.as_ptr()) };
+            }
+        },
+        move |msg| {
+            if let Some(cb) = log_cb {
+                let c = CString::new(msg).unwrap_or_default();
+                unsafe { cb(c.as_ptr()) };
+            }
+        },
+        move |p| {
+            if let Some(cb) = progress_cb {
+                unsafe { cb(p) };
+            }
+        },
+        watch.cancel.clone(),
+    );
+
+    nak_rust::installers::install_all_dependencies(
+        Path::new(prefix),
+        &proton,
+        &ctx,
+        0.0,
+        1.0,
+        app_id,
+    )
+    .map_err(coded)
+}
+
+/// Install individual winetricks verbs (e.g. "vcrun2022") into a prefix.
+///
+/// `verbs` is an array of `verb_count` names, each of which must be a
+/// supported verb. Blocking; each step is reported through `log_cb`, and the
+/// first failing verb stops the install. `cancel_flag` works as in
+/// nak_install_all_dependencies.
+///
+/// Returns null on success, or an error message naming the failed verb
+/// (caller must free with nak_string_free). If `out_code` is non-null it
+/// receives the cause (Success on success).
+///
+/// # Safety
+/// `prefix_path` and `proton_path` must each be null or a valid NUL-terminated string.
+/// `verbs` must be null or point to `verb_count` strings, each null or NUL-terminated.
+/// `out_code` must be null or valid for writes. `cancel_flag` must be null or point to
+/// an int that stays valid until the call returns.
+#[no_mangle]
+pub unsafe extern "C" fn nak_install_winetricks_verbs(
+    prefix_path: *const c_char,
+    proton_path: *const c_char,
+    verbs: *const *const c_char,
+    verb_count: usize,
+    log_cb: NakLogCallback,
+    cancel_flag: *const c_int,
+    out_code: *mut NakErrorCode,
+) -> *mut c_char {
+    let verbs = unsafe { cstr_array(verbs, verb_count) };
+    let prefix = unsafe { from_cstr(prefix_path) };
+    let proton_path = unsafe { from_cstr(proton_path) };
+
+    let result = proton_by_path(proton_path).and_then(|proton| {
+        let watch = CancelWatch::new(cancel_flag);
+        let ctx = nak_rust::installers::TaskContext::new(
+            |_| {},
+            move |msg| {
+                if let Some(cb) = log_cb {
+                    let c = CString::new(msg).unwrap_or_default();
+                    unsafe { cb(c.as_ptr()) };
+                }
+            },
+            |_| {},
+            watch.cancel.clone(),
+        );
+        nak_rust::installers::install_winetricks_verbs(Path::new(prefix), &proton, &verbs, &ctx)
+            .map_err(coded)
+    });
+    unsafe { install_result(result, out_code) }
+}
+
+/// Where a background install stands
+enum AsyncInstallState {
+    Running,
+    Done,
+    /// The error, until taken with nak_install_handle_take_error
+    Failed(Option<(NakErrorCode, String)>),
+}
+
+/// A dependency install running on a background thread (opaque to C)
+pub struct NakInstallHandle {
+    state: Arc<Mutex<AsyncInstallState>>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+/// Start nak_install_all_dependencies on a background thread and return at once.
+///
+/// Arguments are as for nak_install_all_dependencies and are copied before
+/// returning, except `cancel_flag`, which must stay valid until the handle is
+/// freed. Callbacks run on the worker thread. Poll with
+/// nak_install_handle_poll and release with nak_install_handle_free.
+///
+/// # Safety
+/// `prefix_path`, `proton_name` and `proton_path` must each be null or a valid
+/// NUL-terminated string. `cancel_flag` must be null or point to an int that stays
+/// valid until the install finishes.
+#[no_mangle]
+pub unsafe extern "C" fn nak_install_all_dependencies_async(
+    prefix_path: *const c_char,
+    proton_name: *const c_char,
+    proton_path: *const c_char,
+    status_cb: NakStatusCallback,
+    log_cb: NakLogCallback,
+    progress_cb: NakProgressCallback,
+    cancel_flag: *const c_int,
+    app_id: u32,
+) -> *mut NakInstallHandle {
+    let _proton_name = unsafe { from_cstr(proton_name) };
+    let prefix = unsafe { from_cstr(prefix_path) }.to_string();
+    let proton_path = unsafe { from_cstr(proton_path) }.to_string();
+    let callbacks = InstallCallbacks {
+        status_cb,
+        log_cb,
+        progress_cb,
+    };
+    let cancel_flag_ptr = cancel_flag as usize; // safe to send across threads
+
+    let state = Arc::new(Mutex::new(AsyncInstallState::Running));
+    let worker_state = state.clone();
+    let thread = std::thread::spawn(move || {
+        // A panic must still end the install, or polling reports it running forever
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            install_dependencies(
+                &prefix,
+                &proton_path,
+                callbacks,
+                cancel_flag_ptr as *const c_int,
+                app_id,
+            )
+        }));
+        let state = match result {
+            Ok(Ok(())) => AsyncInstallState::Done,
+            Ok(Err(e)) => AsyncInstallState::Failed(Some(e)),
+            Err(panic) => {
+                let reason = panic
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| panic.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown error".to_string());
+                AsyncInstallState::Failed(Some((NakErrorCode::Unknown, format!("Install crashed: {}", reason))))
+            }
+        };
+        *worker_state.lock().unwrap_or_else(|e| e.into_inner()) = state;
+    });
+
+    Box::into_raw(Box::new(NakInstallHandle {
+        state,
+        thread: Some(thread),
+    }))
+}
+
+/// Check a background install: 0 = running, 1 = done, -1 = error
+/// (including a null handle)
+///
+/// # Safety
+/// `handle` must be null or returned by `nak_install_all_dependencies_async` and not
+/// yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn nak_install_handle_poll(handle: *mut NakInstallHandle) -> c_int {
+    let Some(handle) = (unsafe { handle.as_ref() }) else {
+        return -1;
+    };
+    match *handle.state.lock().unwrap() {
+        AsyncInstallState::Running => 0,
+        AsyncInstallState::Done => 1,
+        AsyncInstallState::Failed(_) => -1,
+    }
+}
+
+/// Take the error of a failed background install.
+///
+/// Returns null while running, on success, or once the error was taken;
+/// otherwise the message (caller must free with nak_string_free). If
+/// `out_code` is non-null it receives the cause, or Success when null is
+/// returned.
+///
+/// # Safety
+/// `out_code` must be null or valid for writes. `handle` must be null or returned by
+/// `nak_install_all_dependencies_async` and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn nak_install_handle_take_error(
+    handle: *mut NakInstallHandle,
+    out_code: *mut NakErrorCode,
+) -> *mut c_char {
+    let error = unsafe { handle.as_ref() }.and_then(|handle| match &mut *handle.state.lock().unwrap() {
+        AsyncInstallState::Failed(error) => error.take(),
+        _ => None,
+    });
+    unsafe { install_result(error.map_or(Ok(()), Err), out_code) }
+}
+
+/// Wait for a background install to finish and release its handle.
+///
+/// Blocks while the install runs; set the cancel flag first to stop it early.
+///
+/// # Safety
+/// `handle` must be null or returned by `nak_install_all_dependencies_async`, not freed
+/// before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_install_handle_free(handle: *mut NakInstallHandle) {
+    if handle.is_null() {
+        return;
+    }
+    let mut handle = unsafe { Box::from_raw(handle) };
+    if let Some(thread) = handle.thread.take() {
+        let _ = thread.join();
+    }
+}
+
+/// Apply Wine registry settings to a prefix
+///
+/// Returns null on success, or an error message (caller must free with nak_string_free).
+/// If `out_code` is non-null it receives the cause (Success on success).
+///
+/// # Safety
+/// `prefix_path`, `proton_name` and `proton_path` must each be null or a valid
+/// NUL-terminated string. `out_code` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn nak_apply_wine_registry_settings(
+    prefix_path: *const c_char,
+    proton_name: *const c_char,
+    proton_path: *const c_char,
+    log_cb: NakLogCallback,
+    app_id: u32,
+    out_code: *mut NakErrorCode,
+) -> *mut c_char {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    let _proton_name = unsafe { from_cstr(proton_name) };
+
+    let proton = match proton_by_path(unsafe { from_cstr(proton_path) }) {
+        Ok(p) => p,
+        Err(e) => return unsafe { install_result(Err(e), out_code) },
+    };
+
+    let log_fn = move |msg: String| {
+        if let Some(cb) = log_cb {
+            let c = CString::new(msg).unwrap_or_default();
+            unsafe { cb(c.as_ptr()) };
+        }
+    };
+
+    let app_id_opt = if app_id == 0 { None } else { Some(app_id) };
+
+    let result = nak_rust::installers::apply_wine_registry_settings(
+        Path::new(prefix),
+        &proton,
+        &log_fn,
+        app_id_opt,
+    );
+    unsafe { install_result(result.map_err(coded), out_code) }
+}
+
+/// Apply a game's registry entry with a custom install path.
+///
+/// Looks up the game by name in KNOWN_GAMES, writes the registry entry
+/// pointing to `install_path`. Returns null on success, or an error message.
+/// If `out_code` is non-null it receives the cause (Success on success).
+///
+/// # Safety
+/// `prefix_path`, `proton_name`, `proton_path`, `game_name` and `install_path` must
+/// each be null or a valid NUL-terminated string. `out_code` must be null or valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn nak_apply_registry_for_game_path(
+    prefix_path: *const c_char,
+    proton_name: *const c_char,
+    proton_path: *const c_char,
+    game_name: *const c_char,
+    install_path: *const c_char,
+    log_cb: NakLogCallback,
+    out_code: *mut NakErrorCode,
+) -> *mut c_char {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    let _proton_name = unsafe { from_cstr(proton_name) };
+    let game = unsafe { from_cstr(game_name) };
+    let install = unsafe { from_cstr(install_path) };
+
+    let proton = match proton_by_path(unsafe { from_cstr(proton_path) }) {
+        Ok(p) => p,
+        Err(e) => return unsafe { install_result(Err(e), out_code) },
+    };
+
+    let log_fn = move |msg: String| {
+        if let Some(cb) = log_cb {
+            let c = CString::new(msg).unwrap_or_default();
+            unsafe { cb(c.as_ptr()) };
+        }
+    };
+
+    let result = nak_rust::installers::apply_registry_for_game_path(
+        Path::new(prefix),
+        &proton,
+        game,
+        Path::new(install),
+        &log_fn,
+    );
+    unsafe { install_result(result.map_err(coded), out_code) }
+}
+
+/// Flush pending registry changes in a prefix to user.reg/system.reg by
+/// waiting for its wineserver to exit. Fails at once, without waiting, if
+/// programs are still running in the prefix, and gives up if the wineserver
+/// has not exited after 15 seconds.
+///
+/// Returns null on success, or an error message (free with nak_string_free).
+/// If `out_code` is non-null it receives the cause (Success on success).
+///
+/// # Safety
+/// `prefix_path` and `proton_path` must each be null or a valid NUL-terminated string.
+/// `out_code` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn nak_flush_prefix_registry(
+    prefix_path: *const c_char,
+    proton_path: *const c_char,
+    out_code: *mut NakErrorCode,
+) -> *mut c_char {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    let result = proton_by_path(unsafe { from_cstr(proton_path) }).and_then(|proton| {
+        nak_rust::installers::flush_prefix_registry(Path::new(prefix), &proton).map_err(coded)
+    });
+    unsafe { install_result(result, out_code) }
+}
+
+/// Read a value from a prefix's system.reg, falling back to user.reg.
+///
+/// `registry_path` is a key such as `Software\Bethesda Softworks\Skyrim`
+/// (the form KNOWN_GAMES uses); Wow6432Node is checked too in 64-bit
+/// prefixes. Reads the files on disk, so call nak_flush_prefix_registry first
+/// if Wine may still be running. Returns a newly allocated string (free with
+/// nak_string_free), or null if the value isn't there.
+///
+/// # Safety
+/// `prefix_path`, `registry_path` and `registry_value` must each be null or a valid
+/// NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_read_registry_value(
+    prefix_path: *const c_char,
+    registry_path: *const c_char,
+    registry_value: *const c_char,
+) -> *mut c_char {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    let key = unsafe { from_cstr(registry_path) };
+    let value = unsafe { from_cstr(registry_value) };
+    if prefix.is_empty() || key.is_empty() {
+        return ptr::null_mut();
+    }
+    to_cstring_opt(nak_rust::game_finder::read_registry_value(Path::new(prefix), key, value).as_deref())
+}
+
+/// Validate a download mirror URL (http, https, file, or an absolute path).
+///
+/// Returns null if the mirror is usable, or an error message explaining what
+/// is wrong (free with nak_string_free).
+///
+/// # Safety
+/// `url` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_validate_mirror(url: *const c_char) -> *mut c_char {
+    let url = unsafe { from_cstr(url) };
+    match nak_rust::installers::validate_mirror(url) {
+        Ok(_) => ptr::null_mut(),
+        Err(e) => error_to_cstring(e),
+    }
+}
+
+/// Estimated disk space of a dependency install, in bytes
+#[repr(C)]
+pub struct NakSetupFootprint {
+    /// Downloaded into the NaK cache
+    pub download: u64,
+    /// Unpacked into the prefix
+    pub extracted: u64,
+    /// download + extracted + prefix overhead
+    pub total_required: u64,
+}
+
+/// Estimate the disk space nak_install_all_dependencies needs for a prefix
+/// and game, leaving out verbs already installed and downloads already cached.
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_estimated_setup_footprint(
+    prefix_path: *const c_char,
+    app_id: u32,
+) -> NakSetupFootprint {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    let footprint = nak_rust::installers::estimated_setup_footprint(Path::new(prefix), app_id);
+    NakSetupFootprint {
+        download: footprint.download,
+        extracted: footprint.extracted,
+        total_required: footprint.total_required,
+    }
+}
+
+// ============================================================================
+// Tier 5: Prefix Symlinks
+// ============================================================================
+
+/// Ensure the Temp directory exists in the Wine prefix's AppData/Local.
+///
+/// MO2 and other tools require AppData/Local/Temp to exist.
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_ensure_temp_directory(prefix_path: *const c_char) {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    nak_rust::installers::symlinks::ensure_temp_directory(Path::new(prefix));
+}
+
+/// Ensure both AppData/Local/Temp and the Windows Temp directory exist.
+///
+/// Returns the directories that were missing and got created; empty if Wine
+/// hasn't initialized the prefix yet. Free with nak_string_list_free.
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_ensure_all_temp_dirs(prefix_path: *const c_char) -> NakStringList {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    let created = nak_rust::installers::symlinks::ensure_all_temp_dirs(Path::new(prefix));
+    to_string_list(created.iter().map(|p| p.display().to_string()).collect())
+}
+
+/// Detect installed games and create symlinks from the prefix to game prefixes.
+///
+/// This is a convenience wrapper that detects games and creates symlinks in one call.
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_create_game_symlinks_auto(prefix_path: *const c_char) {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    nak_rust::installers::symlinks::create_game_symlinks_auto(Path::new(prefix));
+}
+
+/// A game symlink nak_create_game_symlinks_auto would create (C-compatible)
+#[repr(C)]
+pub struct NakPlannedSymlink {
+    pub link: *mut c_char,      // path inside the prefix
+    pub target: *mut c_char,    // folder in the game's prefix
+    pub game_name: *mut c_char,
+    pub status: c_int,          // 0 = will be created, 1 = already linked, 2 = broken link, 3 = path taken
+}
+
+/// Planned game symlinks
+#[repr(C)]
+pub struct NakSymlinkPlan {
+    pub links: *mut NakPlannedSymlink,
+    pub count: usize,
+}
+
+/// Preview nak_create_game_symlinks_auto without creating anything.
+///
+/// Links that already exist, dangling links and paths taken by something
+/// else are listed with their status and would be left alone.
+/// Free with nak_symlink_plan_free.
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_plan_game_symlinks(prefix_path: *const c_char) -> NakSymlinkPlan {
+    use nak_rust::installers::symlinks::SymlinkStatus;
+
+    let prefix = unsafe { from_cstr(prefix_path) };
+    let mut links: Box<[NakPlannedSymlink]> = nak_rust::installers::symlinks::plan_game_symlinks(Path::new(prefix))
+        .into_iter()
+        .map(|p| NakPlannedSymlink {
+            link: to_cstring(&p.link.to_string_lossy()),
+            target: to_cstring(&p.target.to_string_lossy()),
+            game_name: to_cstring(&p.game_name),
+            status: match p.status {
+                SymlinkStatus::Create => 0,
+                SymlinkStatus::Linked => 1,
+                SymlinkStatus::Broken => 2,
+                SymlinkStatus::Occupied => 3,
+            },
+        })
+        .collect();
+
+    let plan = NakSymlinkPlan {
+        links: links.as_mut_ptr(),
+        count: links.len(),
+    };
+    std::mem::forget(links);
+    plan
+}
+
+/// Free a symlink plan returned by nak_plan_game_symlinks
+///
+/// # Safety
+/// `plan` must be exactly as returned by NaK and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_symlink_plan_free(plan: NakSymlinkPlan) {
+    if plan.links.is_null() {
+        return;
+    }
+    let links = unsafe { Vec::from_raw_parts(plan.links, plan.count, plan.count) };
+    for link in links {
+        unsafe {
+            free_if_nonnull(link.link);
+            free_if_nonnull(link.target);
+            free_if_nonnull(link.game_name);
+        }
+    }
+}
+
+/// Make both `steamuser` and the prefix's real user directory name resolve.
+///
+/// Writes 1 to `out_created` if a link was created (may be null).
+/// Returns null on success, or an error message (free with nak_string_free).
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string. `out_created` must be
+/// null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn nak_ensure_steamuser_symlink(
+    prefix_path: *const c_char,
+    out_created: *mut c_int,
+) -> *mut c_char {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    match nak_rust::installers::ensure_steamuser_symlink(Path::new(prefix)) {
+        Ok(link) => {
+            if !out_created.is_null() {
+                *out_created = link.is_some() as c_int;
+            }
+            ptr::null_mut()
+        }
+        Err(e) => error_to_cstring(e),
+    }
+}
+
+// ============================================================================
+// Tier 6: Logging
+// ============================================================================
+
+/// Callback for NaK log messages: fn(level: *const c_char, message: *const c_char)
+///
+/// Levels: "info", "warning", "error", "install", "action", "download"
+pub type NakLogLevelCallback = Option<unsafe extern "C" fn(*const c_char, *const c_char)>;
+
+/// Initialize NaK logging with a callback.
+///
+/// The callback receives (level, message) for all NaK internal log messages.
+/// Call once at startup before any other nak_* functions.
+///
+/// # Safety
+/// `cb` may be called from any thread for the rest of the process.
+#[no_mangle]
+pub unsafe extern "C" fn nak_init_logging(cb: NakLogLevelCallback) {
+    if let Some(callback) = cb {
+        nak_rust::logging::set_log_callback(move |level: &str, message: &str| {
+            let c_level = CString::new(level).unwrap_or_default();
+            let c_msg = CString::new(message).unwrap_or_default();
+            unsafe { callback(c_level.as_ptr(), c_msg.as_ptr()) };
+        });
+    }
+}
+
+// ============================================================================
+// Tier 7: DXVK Configuration
+// ============================================================================
+
+/// Ensure the DXVK config file exists, downloading if necessary.
+///
+/// Returns null on success, or an error message (caller must free with nak_string_free).
+#[no_mangle]
+pub extern "C" fn nak_ensure_dxvk_conf() -> *mut c_char {
+    match nak_rust::dxvk::ensure_dxvk_conf() {
+        Ok(_) => ptr::null_mut(),
+        Err(e) => error_to_cstring(e),
+    }
+}
+
+/// DXVK version the stored dxvk.conf was taken from (e.g. "2.5.3").
+///
+/// Returns a newly allocated string (caller must free with nak_string_free),
+/// or null if there is no config yet or it carries no version.
+#[no_mangle]
+pub extern "C" fn nak_get_dxvk_version() -> *mut c_char {
+    to_cstring_opt(nak_rust::dxvk::dxvk_conf_version().as_deref())
+}
+
+/// Re-download the DXVK config if `force` is non-zero or the stored copy is
+/// older than the version NaK bundles.
+///
+/// A failed download keeps the existing file. Returns null on success
+/// (including when nothing needed updating), or an error message (caller
+/// must free with nak_string_free).
+#[no_mangle]
+pub extern "C" fn nak_update_dxvk_conf(force: c_int) -> *mut c_char {
+    match nak_rust::dxvk::update_dxvk_conf(force != 0) {
+        Ok(_) => ptr::null_mut(),
+        Err(e) => error_to_cstring(e),
+    }
+}
+
+/// Get the path to the DXVK config file.
+///
+/// Returns a newly allocated string (caller must free with nak_string_free).
+#[no_mangle]
+pub extern "C" fn nak_get_dxvk_conf_path() -> *mut c_char {
+    let path = nak_rust::dxvk::get_dxvk_conf_path();
+    to_cstring(&path.to_string_lossy())
+}
+
+/// Remove the DXVK state cache files for a detected game.
+///
+/// Writes the number of bytes freed to `out_freed` (if non-null).
+/// Returns null on success, or an error message (caller must free with nak_string_free).
+///
+/// # Safety
+/// `app_id` must be null or a valid NUL-terminated string. `out_freed` must be null or
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn nak_clear_dxvk_state_cache(
+    app_id: *const c_char,
+    out_freed: *mut u64,
+) -> *mut c_char {
+    let app_id = unsafe { from_cstr(app_id) };
+
+    let Some(game) = find_detected_game(app_id) else {
+        return to_cstring(&format!("Game not found: {}", app_id));
+    };
+
+    match nak_rust::dxvk::clear_state_cache(&game) {
+        Ok(freed) => {
+            if !out_freed.is_null() {
+                *out_freed = freed;
+            }
+            ptr::null_mut()
+        }
+        Err(e) => error_to_cstring(e),
+    }
+}
+
+/// Outcome of applying a DXVK config to one game (C-compatible)
+#[repr(C)]
+pub struct NakDxvkApplyResult {
+    pub app_id: *mut c_char,
+    /// 0 = DXVK_CONFIG_FILE in launch options, 1 = copied to install dir, -1 = failed
+    pub method: c_int,
+    pub error: *mut c_char, // null on success
+}
+
+/// Per-game results of nak_apply_dxvk_conf
+#[repr(C)]
+pub struct NakDxvkApplyResultList {
+    pub results: *mut NakDxvkApplyResult,
+    pub count: usize,
+}
+
+/// Point several detected games at the same DXVK config.
+///
+/// `app_ids` is an array of `count` app id strings. Steam must be closed,
+/// since Steam games are configured through their launch options.
+/// Free with nak_dxvk_apply_result_list_free.
+///
+/// # Safety
+/// `conf_path` must be null or a valid NUL-terminated string. `app_ids` must be null or
+/// point to `count` strings, each null or NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn nak_apply_dxvk_conf(
+    conf_path: *const c_char,
+    app_ids: *const *const c_char,
+    count: usize,
+) -> NakDxvkApplyResultList {
+    use nak_rust::dxvk::ConfApplyMethod;
+
+    let conf = unsafe { from_cstr(conf_path) };
+    let wanted = unsafe { cstr_array(app_ids, count) };
+
+    let detected = nak_rust::game_finder::detect_all_games().games;
+    let games: Vec<nak_rust::game_finder::Game> = detected
+        .into_iter()
+        .filter(|g| wanted.contains(&g.app_id.as_str()))
+        .collect();
+    let applied = nak_rust::dxvk::apply_conf_to_prefixes(Path::new(conf), &games);
+
+    let mut results: Vec<NakDxvkApplyResult> = wanted
+        .iter()
+        .map(|&app_id| match applied.iter().find(|r| r.app_id == app_id) {
+            Some(r) => match &r.result {
+                Ok(method) => NakDxvkApplyResult {
+                    app_id: to_cstring(app_id),
+                    method: match method {
+                        ConfApplyMethod::LaunchOptions => 0,
+                        ConfApplyMethod::InstallDir => 1,
+                    },
+                    error: ptr::null_mut(),
+                },
+                Err(e) => NakDxvkApplyResult {
+                    app_id: to_cstring(app_id),
+                    method: -1,
+                    error: to_cstring(e),
+                },
+            },
+            None => NakDxvkApplyResult {
+                app_id: to_cstring(app_id),
+                method: -1,
+                error: to_cstring(&format!("Game not found: {}", app_id)),
+            },
+        })
+        .collect();
+
+    let list = NakDxvkApplyResultList {
+        results: results.as_mut_ptr(),
+        count: results.len(),
+    };
+    std::mem::forget(results);
+    list
+}
+
+/// Free a NakDxvkApplyResultList
+///
+/// # Safety
+/// `list` must be exactly as returned by NaK and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_dxvk_apply_result_list_free(list: NakDxvkApplyResultList) {
+    if list.results.is_null() {
+        return;
+    }
+    let results = unsafe { Vec::from_raw_parts(list.results, list.count, list.count) };
+    for r in results {
+        free_if_nonnull(r.app_id);
+        free_if_nonnull(r.error);
+    }
+}
+
 // ============================================================================
-// Tier 4: Dependency Installation (callback-based)
+// Tier 8: Prefix Diagnostics
 // ============================================================================
 
-/// Callback for status messages: fn(message: *const c_char)
-pub type NakStatusCallback = Option<unsafe extern "C" fn(*const c_char)>;
+/// List the VC++ redistributables installed in a prefix.
+///
+/// Items are winetricks verb names (e.g. "vcrun2022"), newest first.
+/// Free with nak_string_list_free.
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_installed_vcredist(prefix_path: *const c_char) -> NakStringList {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    to_string_list(nak_rust::installers::installed_vcredist(Path::new(prefix)))
+}
+
+/// List the .NET Framework versions and desktop runtimes installed in a prefix.
+///
+/// Items are winetricks verb names: Framework first (e.g. "dotnet48"), then
+/// desktop runtimes (e.g. "dotnetdesktop8"). Free with nak_string_list_free.
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_installed_dotnet(prefix_path: *const c_char) -> NakStringList {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    to_string_list(nak_rust::installers::installed_dotnet(Path::new(prefix)))
+}
+
+/// Which kind of .NET a winetricks verb installs:
+/// 0 = .NET Framework, 1 = .NET desktop runtime, -1 = neither
+///
+/// # Safety
+/// `verb` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_dotnet_kind(verb: *const c_char) -> c_int {
+    use nak_rust::installers::DotnetKind;
+
+    let verb = unsafe { from_cstr(verb) };
+    match nak_rust::installers::dotnet_kind(verb) {
+        Some(DotnetKind::Framework) => 0,
+        Some(DotnetKind::Desktop) => 1,
+        None => -1,
+    }
+}
+
+/// Whether a prefix has native Media Foundation installed (1 yes, 0 no)
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_has_media_foundation(prefix_path: *const c_char) -> c_int {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    nak_rust::installers::has_media_foundation(Path::new(prefix)) as c_int
+}
+
+/// Whether a prefix already has NaK's current dependency set (1 yes, 0 no)
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_dependencies_installed(prefix_path: *const c_char) -> c_int {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    nak_rust::installers::dependencies_installed(Path::new(prefix)) as c_int
+}
+
+/// The marker the last finished dependency install left in a prefix, as a
+/// JSON object: `nak_version`, `deps_version` (0 until an install completes
+/// without failures), `installed_at` (Unix seconds) and `verbs`.
+///
+/// Returns a newly allocated string (caller must free with nak_string_free),
+/// or null if the prefix has no readable marker.
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_read_deps_marker(prefix_path: *const c_char) -> *mut c_char {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    let json = nak_rust::installers::read_deps_marker(Path::new(prefix)).and_then(|m| m.to_json().ok());
+    to_cstring_opt(json.as_deref())
+}
+
+/// Whether Wine Gecko and Wine Mono are installed in a prefix.
+///
+/// Writes 1/0 to `out_gecko` and `out_mono` (each may be null).
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string. `out_gecko` and
+/// `out_mono` must each be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn nak_has_gecko_mono(
+    prefix_path: *const c_char,
+    out_gecko: *mut c_int,
+    out_mono: *mut c_int,
+) {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    let (gecko, mono) = nak_rust::installers::has_gecko_mono(Path::new(prefix));
+    if !out_gecko.is_null() {
+        *out_gecko = gecko as c_int;
+    }
+    if !out_mono.is_null() {
+        *out_mono = mono as c_int;
+    }
+}
+
+/// Install whichever of Wine Gecko / Wine Mono a prefix lacks from the
+/// packages shipped with the Proton at `proton_path` (blocking call).
+///
+/// Returns null on success, or an error message (caller must free with
+/// nak_string_free). If `out_code` is non-null it receives the cause.
+///
+/// # Safety
+/// `prefix_path` and `proton_path` must each be null or a valid NUL-terminated string.
+/// `out_code` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn nak_install_gecko_mono(
+    prefix_path: *const c_char,
+    proton_path: *const c_char,
+    log_cb: NakLogCallback,
+    out_code: *mut NakErrorCode,
+) -> *mut c_char {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    let result = proton_by_path(unsafe { from_cstr(proton_path) }).and_then(|proton| {
+        let ctx = nak_rust::installers::TaskContext::new(
+            |_| {},
+            move |msg| {
+                if let Some(cb) = log_cb {
+                    let c = CString::new(msg).unwrap_or_default();
+                    unsafe { cb(c.as_ptr()) };
+                }
+            },
+            |_| {},
+            Arc::new(AtomicBool::new(false)),
+        );
+        nak_rust::installers::install_gecko_mono(Path::new(prefix), &proton, &ctx).map_err(coded)
+    });
+    unsafe { install_result(result, out_code) }
+}
+
+/// Which tool created a prefix: 0 = Proton, 1 = Wine, 2 = Bottles, 3 = unknown
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_prefix_kind(prefix_path: *const c_char) -> c_int {
+    use nak_rust::installers::PrefixKind;
+
+    let prefix = unsafe { from_cstr(prefix_path) };
+    match nak_rust::installers::prefix_kind(Path::new(prefix)) {
+        PrefixKind::Proton => 0,
+        PrefixKind::Wine => 1,
+        PrefixKind::Bottles => 2,
+        PrefixKind::Unknown => 3,
+    }
+}
+
+/// Files Proton manages in a prefix, from compatdata's `tracked_files`.
+///
+/// Paths are relative to `pfx/`; empty if the file is absent.
+/// Free with nak_string_list_free.
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_prefix_tracked_files(prefix_path: *const c_char) -> NakStringList {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    to_string_list(nak_rust::installers::prefix_tracked_files(Path::new(prefix)))
+}
+
+/// The directory holding a prefix's drive_c, given the prefix root, a
+/// compatdata-style parent (`<path>/pfx/drive_c`) or drive_c itself.
+/// Returns null if no drive_c is found; free with nak_string_free.
+///
+/// # Safety
+/// `path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_resolve_prefix_root(path: *const c_char) -> *mut c_char {
+    let path = unsafe { from_cstr(path) };
+    match nak_rust::game_finder::resolve_prefix_root(Path::new(path)) {
+        Some(root) => to_cstring(&root.to_string_lossy()),
+        None => ptr::null_mut(),
+    }
+}
+
+/// A prefix's Documents folder, following shell-folder redirections that
+/// resolve inside the prefix. OneDrive redirections are ignored (with a logged
+/// warning) in favour of the local Documents folder.
+/// Caller must free with nak_string_free.
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_prefix_documents_path(prefix_path: *const c_char) -> *mut c_char {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    let documents = nak_rust::game_finder::prefix_documents_path(Path::new(prefix));
+    to_cstring(&documents.to_string_lossy())
+}
+
+/// Whether NaK's download cache is on the same filesystem as a prefix: 1 yes
+/// (or unknown), 0 no. Different filesystems make installs copy across drives.
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_cache_prefix_same_fs(prefix_path: *const c_char) -> c_int {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    nak_rust::installers::cache_prefix_same_fs(Path::new(prefix)) as c_int
+}
+
+/// Pick a writable temp directory with at least `required_bytes` free: NaK's
+/// cache tmp, then the prefix's filesystem (if Wine has initialized it),
+/// then $TMPDIR.
+///
+/// On success writes the path to `out_path` (free with nak_string_free).
+/// Returns null on success, or an error message (caller must free with nak_string_free).
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string. `out_path` must be null
+/// or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn nak_select_temp_dir(
+    prefix_path: *const c_char,
+    required_bytes: u64,
+    out_path: *mut *mut c_char,
+) -> *mut c_char {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    match nak_rust::installers::select_temp_dir(Path::new(prefix), required_bytes) {
+        Ok(dir) => {
+            if !out_path.is_null() {
+                *out_path = to_cstring(&dir.to_string_lossy());
+            }
+            ptr::null_mut()
+        }
+        Err(e) => error_to_cstring(e),
+    }
+}
+
+/// Delete NaK's downloads and install scratch files not modified for
+/// `older_than_secs` (partial downloads, cached installers, the tmp dir).
+/// Other cache contents, files still being written and the config directory
+/// are left alone.
+///
+/// Writes the number of bytes freed to `out_bytes_freed` (if non-null).
+/// Returns null on success, or an error message (caller must free with nak_string_free).
+///
+/// # Safety
+/// `out_bytes_freed` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn nak_clean_cache(older_than_secs: u64, out_bytes_freed: *mut u64) -> *mut c_char {
+    match nak_rust::installers::clean_cache(Duration::from_secs(older_than_secs)) {
+        Ok(freed) => {
+            if !out_bytes_freed.is_null() {
+                *out_bytes_freed = freed;
+            }
+            ptr::null_mut()
+        }
+        Err(e) => error_to_cstring(e.into()),
+    }
+}
+
+/// DPI (LogPixels) configured in a prefix, or 0 if unset (Wine uses 96)
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_get_prefix_dpi(prefix_path: *const c_char) -> u32 {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    nak_rust::installers::get_prefix_dpi(Path::new(prefix)).unwrap_or(0)
+}
+
+/// Set a prefix's DPI (72-480) by editing user.reg; the old file is kept as
+/// user.reg.nak-bak. Nothing may be running in the prefix.
+///
+/// Returns null on success, or an error message (free with nak_string_free).
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_set_prefix_dpi(prefix_path: *const c_char, dpi: u32) -> *mut c_char {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    match nak_rust::installers::set_prefix_dpi(Path::new(prefix), dpi) {
+        Ok(()) => ptr::null_mut(),
+        Err(e) => error_to_cstring(e),
+    }
+}
+
+/// Audio driver pinned in a prefix ("pulse", "alsa", "oss", "coreaudio" or
+/// "none"), or null when Wine picks one itself. Free with nak_string_free.
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_get_audio_driver(prefix_path: *const c_char) -> *mut c_char {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    to_cstring_opt(nak_rust::installers::get_audio_driver(Path::new(prefix)).as_deref())
+}
+
+/// Pin a prefix's audio driver ("pulse", "alsa", "oss", "coreaudio", "none"),
+/// or pass "" / null to let Wine choose. Edits user.reg, keeping the old file
+/// as user.reg.nak-bak; nothing may be running in the prefix.
+///
+/// Returns null on success, or an error message (free with nak_string_free).
+///
+/// # Safety
+/// `prefix_path` and `driver` must each be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_set_audio_driver(
+    prefix_path: *const c_char,
+    driver: *const c_char,
+) -> *mut c_char {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    let driver = unsafe { from_cstr(driver) };
+    match nak_rust::installers::set_audio_driver(Path::new(prefix), driver) {
+        Ok(()) => ptr::null_mut(),
+        Err(e) => error_to_cstring(e),
+    }
+}
+
+/// Whether a prefix has PendingFileRenameOperations left over from an
+/// interrupted installer: 1 yes, 0 no
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_has_pending_renames(prefix_path: *const c_char) -> c_int {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    nak_rust::installers::has_pending_renames(Path::new(prefix)) as c_int
+}
+
+/// Clear leftover PendingFileRenameOperations by editing system.reg; the old
+/// file is kept as system.reg.nak-bak. Nothing may be running in the prefix.
+///
+/// Writes 1 to `out_cleared` (may be null) if anything was removed.
+/// Returns null on success, or an error message (free with nak_string_free).
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string. `out_cleared` must be
+/// null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn nak_clear_pending_renames(
+    prefix_path: *const c_char,
+    out_cleared: *mut c_int,
+) -> *mut c_char {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    match nak_rust::installers::clear_pending_renames(Path::new(prefix)) {
+        Ok(cleared) => {
+            if !out_cleared.is_null() {
+                *out_cleared = cleared as c_int;
+            }
+            ptr::null_mut()
+        }
+        Err(e) => error_to_cstring(e),
+    }
+}
+
+/// Architecture of a prefix: 64, 32, or 0 if it has no registry yet.
+///
+/// 32-bit prefixes cannot be used with Proton; NaK refuses to install
+/// dependencies into them.
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_prefix_arch(prefix_path: *const c_char) -> c_int {
+    use nak_rust::game_finder::PrefixArch;
+
+    let prefix = unsafe { from_cstr(prefix_path) };
+    match nak_rust::game_finder::prefix_arch(Path::new(prefix)) {
+        Some(PrefixArch::Win64) => 64,
+        Some(PrefixArch::Win32) => 32,
+        None => 0,
+    }
+}
+
+/// A Start Menu shortcut inside a prefix (C-compatible)
+#[repr(C)]
+pub struct NakShortcut {
+    pub name: *mut c_char,
+    pub lnk_path: *mut c_char,
+    pub target_path: *mut c_char,       // null if unknown
+    pub target_linux_path: *mut c_char, // null if not on C: or Z:
+    pub arguments: *mut c_char,         // null if none
+    pub working_dir: *mut c_char,       // null if none
+}
+
+/// List of Start Menu shortcuts
+#[repr(C)]
+pub struct NakShortcutList {
+    pub shortcuts: *mut NakShortcut,
+    pub count: usize,
+}
+
+/// List the Start Menu shortcuts (.lnk) installed in a prefix
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_list_start_menu_shortcuts(prefix_path: *const c_char) -> NakShortcutList {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    let shortcuts = nak_rust::installers::list_start_menu_shortcuts(Path::new(prefix));
+
+    let mut ffi_shortcuts: Vec<NakShortcut> = shortcuts
+        .iter()
+        .map(|s| NakShortcut {
+            name: to_cstring(&s.name),
+            lnk_path: to_cstring(&s.lnk_path.to_string_lossy()),
+            target_path: to_cstring_opt(s.target_path.as_deref()),
+            target_linux_path: to_cstring_opt(
+                s.target_linux_path
+                    .as_ref()
+                    .map(|p| p.to_string_lossy())
+                    .as_deref(),
+            ),
+            arguments: to_cstring_opt(s.arguments.as_deref()),
+            working_dir: to_cstring_opt(s.working_dir.as_deref()),
+        })
+        .collect();
+
+    let list = NakShortcutList {
+        shortcuts: ffi_shortcuts.as_mut_ptr(),
+        count: ffi_shortcuts.len(),
+    };
+    std::mem::forget(ffi_shortcuts);
+    list
+}
+
+/// Free a NakShortcutList
+///
+/// # Safety
+/// `list` must be exactly as returned by NaK and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_shortcut_list_free(list: NakShortcutList) {
+    if list.shortcuts.is_null() {
+        return;
+    }
+    let shortcuts = unsafe { Vec::from_raw_parts(list.shortcuts, list.count, list.count) };
+    for s in shortcuts {
+        free_if_nonnull(s.name);
+        free_if_nonnull(s.lnk_path);
+        free_if_nonnull(s.target_path);
+        free_if_nonnull(s.target_linux_path);
+        free_if_nonnull(s.arguments);
+        free_if_nonnull(s.working_dir);
+    }
+}
+
+/// A permission problem on a prefix or cache path (C-compatible)
+#[repr(C)]
+pub struct NakPermissionIssue {
+    pub path: *mut c_char,
+    pub problem: *mut c_char, // human-readable description
+}
+
+/// List of permission problems
+#[repr(C)]
+pub struct NakPermissionIssueList {
+    pub issues: *mut NakPermissionIssue,
+    pub count: usize,
+}
+
+/// Check that the current user can read/write the prefix, compatdata and cache.
+///
+/// An empty list means no problems were found.
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_check_permissions(prefix_path: *const c_char) -> NakPermissionIssueList {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    let issues = nak_rust::installers::check_permissions(Path::new(prefix));
+
+    let mut ffi_issues: Vec<NakPermissionIssue> = issues
+        .iter()
+        .map(|i| NakPermissionIssue {
+            path: to_cstring(&i.path.to_string_lossy()),
+            problem: to_cstring(&i.problem.description()),
+        })
+        .collect();
+
+    let list = NakPermissionIssueList {
+        issues: ffi_issues.as_mut_ptr(),
+        count: ffi_issues.len(),
+    };
+    std::mem::forget(ffi_issues);
+    list
+}
+
+/// Free a NakPermissionIssueList
+///
+/// # Safety
+/// `list` must be exactly as returned by NaK and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_permission_issue_list_free(list: NakPermissionIssueList) {
+    if list.issues.is_null() {
+        return;
+    }
+    let issues = unsafe { Vec::from_raw_parts(list.issues, list.count, list.count) };
+    for i in issues {
+        free_if_nonnull(i.path);
+        free_if_nonnull(i.problem);
+    }
+}
+
+/// A mod manager installation inside a prefix (C-compatible)
+///
+/// All fields are null when no mod manager was found.
+#[repr(C)]
+pub struct NakModManagerInfo {
+    pub name: *mut c_char, // "Mod Organizer 2" or "Vortex"
+    pub exe_path: *mut c_char,
+    pub install_dir: *mut c_char,
+    pub version: *mut c_char, // null if unknown
+}
+
+/// Detect an installed MO2 or Vortex inside a prefix (MO2 preferred).
+///
+/// Free with nak_mod_manager_info_free.
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_detect_mod_manager(prefix_path: *const c_char) -> NakModManagerInfo {
+    let prefix = unsafe { from_cstr(prefix_path) };
+
+    match nak_rust::installers::detect_mod_manager(Path::new(prefix)) {
+        Some(info) => NakModManagerInfo {
+            name: to_cstring(info.kind.display_name()),
+            exe_path: to_cstring(&info.exe_path.to_string_lossy()),
+            install_dir: to_cstring(&info.install_dir.to_string_lossy()),
+            version: to_cstring_opt(info.version.as_deref()),
+        },
+        None => NakModManagerInfo {
+            name: ptr::null_mut(),
+            exe_path: ptr::null_mut(),
+            install_dir: ptr::null_mut(),
+            version: ptr::null_mut(),
+        },
+    }
+}
+
+/// Free a NakModManagerInfo
+///
+/// # Safety
+/// `info` must be exactly as returned by NaK and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_mod_manager_info_free(info: NakModManagerInfo) {
+    free_if_nonnull(info.name);
+    free_if_nonnull(info.exe_path);
+    free_if_nonnull(info.install_dir);
+    free_if_nonnull(info.version);
+}
+
+/// A game whose prefix was created by a much older Proton (C-compatible)
+#[repr(C)]
+pub struct NakPrefixUpgrade {
+    pub name: *mut c_char,
+    pub app_id: *mut c_char,
+    pub prefix_path: *mut c_char,
+    pub prefix_version: *mut c_char,
+    pub prefix_major: u32,
+    pub current_major: u32,
+}
+
+/// List of prefixes needing an upgrade
+#[repr(C)]
+pub struct NakPrefixUpgradeList {
+    pub prefixes: *mut NakPrefixUpgrade,
+    pub count: usize,
+}
+
+/// Find games whose prefix is two or more Proton major versions behind `current_proton`
+///
+/// # Safety
+/// `current_proton` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nak_prefixes_needing_upgrade(current_proton: *const c_char) -> NakPrefixUpgradeList {
+    let current = unsafe { from_cstr(current_proton) };
+    let upgrades = nak_rust::game_finder::prefixes_needing_upgrade(current);
 
-/// Callback for log messages: fn(message: *const c_char)
-pub type NakLogCallback = Option<unsafe extern "C" fn(*const c_char)>;
+    let mut ffi_upgrades: Vec<NakPrefixUpgrade> = upgrades
+        .iter()
+        .map(|u| NakPrefixUpgrade {
+            name: to_cstring(&u.game_name),
+            app_id: to_cstring(&u.app_id),
+            prefix_path: to_cstring(&u.prefix_path.to_string_lossy()),
+            prefix_version: to_cstring(&u.prefix_version),
+            prefix_major: u.prefix_major,
+            current_major: u.current_major,
+        })
+        .collect();
 
-/// Callback for progress updates: fn(progress: f32) where 0.0..=1.0
-pub type NakProgressCallback = Option<unsafe extern "C" fn(c_float)>;
+    let list = NakPrefixUpgradeList {
+        prefixes: ffi_upgrades.as_mut_ptr(),
+        count: ffi_upgrades.len(),
+    };
+    std::mem::forget(ffi_upgrades);
+    list
+}
 
-/// Install all Wine prefix dependencies (winetricks, .NET, registry, etc.)
+/// Free a NakPrefixUpgradeList
 ///
-/// This is a blocking call. Use callbacks for progress updates.
-/// `cancel_flag` should point to an int that can be set to non-zero to cancel.
+/// # Safety
+/// `list` must be exactly as returned by NaK and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_prefix_upgrade_list_free(list: NakPrefixUpgradeList) {
+    if list.prefixes.is_null() {
+        return;
+    }
+    let prefixes = unsafe { Vec::from_raw_parts(list.prefixes, list.count, list.count) };
+    for p in prefixes {
+        free_if_nonnull(p.name);
+        free_if_nonnull(p.app_id);
+        free_if_nonnull(p.prefix_path);
+        free_if_nonnull(p.prefix_version);
+    }
+}
+
+/// One DXVK DLL vs. its DllOverrides entry (C-compatible)
+#[repr(C)]
+pub struct NakDxvkDllCheck {
+    pub dll: *mut c_char,
+    pub dll_present: c_int,
+    pub override_value: *mut c_char, // null if no override
+    pub mismatch: *mut c_char,       // description, null if consistent
+}
+
+/// DXVK setup check for a prefix
+#[repr(C)]
+pub struct NakDxvkSetupStatus {
+    pub proton_managed: c_int, // 1 if Proton sets overrides at launch
+    pub dlls: *mut NakDxvkDllCheck,
+    pub count: usize,
+}
+
+/// Cross-check DXVK DLLs in a prefix against its d3d/dxgi DllOverrides
 ///
-/// Returns null on success, or an error message (caller must free with nak_string_free).
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
 #[no_mangle]
-pub unsafe extern "C" fn nak_install_all_dependencies(
-    prefix_path: *const c_char,
-    proton_name: *const c_char,
-    proton_path: *const c_char,
-    status_cb: NakStatusCallback,
-    log_cb: NakLogCallback,
-    progress_cb: NakProgressCallback,
-    cancel_flag: *const c_int,
-    app_id: u32,
-) -> *mut c_char {
+pub unsafe extern "C" fn nak_verify_dxvk_setup(prefix_path: *const c_char) -> NakDxvkSetupStatus {
     let prefix = unsafe { from_cstr(prefix_path) };
-    let _proton_name = unsafe { from_cstr(proton_name) };
-    let proton_path_str = unsafe { from_cstr(proton_path) };
+    let status = nak_rust::installers::verify_dxvk_setup(Path::new(prefix));
 
-    // Find the matching SteamProton by path
-    let protons = nak_rust::steam::find_steam_protons();
-    let proton = match protons
+    let mut dlls: Vec<NakDxvkDllCheck> = status
+        .dlls
         .iter()
-        .find(|p| p.path.to_string_lossy() == proton_path_str)
-    {
-        Some(p) => p.clone(),
-        None => {
-            return to_cstring(&format!(
-                "Proton not found at path: {}",
-                proton_path_str
-            ));
-        }
+        .map(|d| NakDxvkDllCheck {
+            dll: to_cstring(d.dll),
+            dll_present: d.dll_present as c_int,
+            override_value: to_cstring_opt(d.override_value.as_deref()),
+            mismatch: to_cstring_opt(d.mismatch.map(|m| m.description())),
+        })
+        .collect();
+
+    let result = NakDxvkSetupStatus {
+        proton_managed: status.proton_managed as c_int,
+        dlls: dlls.as_mut_ptr(),
+        count: dlls.len(),
     };
+    std::mem::forget(dlls);
+    result
+}
 
-    // Build cancel flag from raw pointer
-    let cancel = Arc::new(AtomicBool::new(false));
-    let cancel_clone = cancel.clone();
+/// Free a NakDxvkSetupStatus
+///
+/// # Safety
+/// `status` must be exactly as returned by NaK and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_dxvk_setup_status_free(status: NakDxvkSetupStatus) {
+    if status.dlls.is_null() {
+        return;
+    }
+    let dlls = unsafe { Vec::from_raw_parts(status.dlls, status.count, status.count) };
+    for d in dlls {
+        free_if_nonnull(d.dll);
+        free_if_nonnull(d.override_value);
+        free_if_nonnull(d.mismatch);
+    }
+}
 
-    // Spawn a thread to poll the C cancel flag
-    let cancel_flag_ptr = cancel_flag as usize; // safe to send across threads
-    let poll_handle = std::thread::spawn(move || {
-        while !cancel_clone.load(Ordering::Relaxed) {
-            std::thread::sleep(std::time::Duration::from_millis(100));
-            if cancel_flag_ptr != 0 {
-                let flag = unsafe { *(cancel_flag_ptr as *const c_int) };
-                if flag != 0 {
-                    cancel_clone.store(true, Ordering::Relaxed);
-                    break;
-                }
+/// Reconcile DllOverrides with the installed DXVK DLLs.
+///
+/// Fails while anything runs in the prefix; the previous user.reg is kept
+/// as `user.reg.nak-bak`. Writes the number of overrides changed to `out_changed` (if non-null).
+/// Returns null on success, or an error message (caller must free with nak_string_free).
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string. `out_changed` must be
+/// null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn nak_fix_dxvk_setup(prefix_path: *const c_char, out_changed: *mut usize) -> *mut c_char {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    match nak_rust::installers::fix_dxvk_setup(Path::new(prefix)) {
+        Ok(changed) => {
+            if !out_changed.is_null() {
+                *out_changed = changed;
             }
+            ptr::null_mut()
         }
-    });
-
-    let ctx = nak_rust::installers::TaskContext::new(
-        move |msg| {
-            if let Some(cb) = status_cb {
-                let c = CString::new(msg).unwrap_or_default();
-                unsafe { cb(c.as_ptr()) };
-            }
-        },
-        move |msg| {
-            if let Some(cb) = log_cb {
-                let c = CString::new(msg).unwrap_or_default();
-                unsafe { cb(c.as_ptr()) };
-            }
-        },
-        move |p| {
-            if let Some(cb) = progress_cb {
-                unsafe { cb(p) };
-            }
-        },
-        cancel.clone(),
-    );
-
-    let result = nak_rust::installers::install_all_dependencies(
-        Path::new(prefix),
-        &proton,
-        &ctx,
-        0.0,
-        1.0,
-        app_id,
-    );
-
-    // Stop the cancel polling thread
-    cancel.store(true, Ordering::Relaxed);
-    let _ = poll_handle.join();
-
-    match result {
-        Ok(()) => ptr::null_mut(),
         Err(e) => error_to_cstring(e),
     }
 }
 
-/// Apply Wine registry settings to a prefix
+/// DllOverrides health of one winetricks verb (C-compatible)
+#[repr(C)]
+pub struct NakVerbOverride {
+    pub verb: *mut c_char,
+    pub ok: c_int, // 1 if every DLL the verb installs is overridden to native
+}
+
+/// Per-verb results of nak_verify_verb_overrides
+#[repr(C)]
+pub struct NakVerbOverrideList {
+    pub verbs: *mut NakVerbOverride,
+    pub count: usize,
+}
+
+/// Check the DllOverrides of installed winetricks verbs.
 ///
-/// Returns null on success, or an error message (caller must free with nak_string_free).
+/// `verbs` is an array of `count` verb names; null checks the standard
+/// dependency verbs. Verbs not installed or without overrides are left out.
+/// Free with nak_verb_override_list_free.
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string. `verbs` must be null or
+/// point to `count` strings, each null or NUL-terminated.
 #[no_mangle]
-pub unsafe extern "C" fn nak_apply_wine_registry_settings(
+pub unsafe extern "C" fn nak_verify_verb_overrides(
     prefix_path: *const c_char,
-    proton_name: *const c_char,
-    proton_path: *const c_char,
-    log_cb: NakLogCallback,
-    app_id: u32,
-) -> *mut c_char {
+    verbs: *const *const c_char,
+    count: usize,
+) -> NakVerbOverrideList {
     let prefix = unsafe { from_cstr(prefix_path) };
-    let _proton_name = unsafe { from_cstr(proton_name) };
-    let proton_path_str = unsafe { from_cstr(proton_path) };
-
-    let protons = nak_rust::steam::find_steam_protons();
-    let proton = match protons
-        .iter()
-        .find(|p| p.path.to_string_lossy() == proton_path_str)
-    {
-        Some(p) => p.clone(),
-        None => {
-            return to_cstring(&format!(
-                "Proton not found at path: {}",
-                proton_path_str
-            ));
-        }
+    let verbs = if verbs.is_null() {
+        nak_rust::deps::STANDARD_VERBS.to_vec()
+    } else {
+        unsafe { cstr_array(verbs, count) }
     };
+    let mut results: Vec<NakVerbOverride> =
+        nak_rust::installers::verify_verb_overrides(Path::new(prefix), &verbs)
+            .iter()
+            .map(|(verb, ok)| NakVerbOverride {
+                verb: to_cstring(verb),
+                ok: *ok as c_int,
+            })
+            .collect();
 
-    let log_fn = move |msg: String| {
-        if let Some(cb) = log_cb {
-            let c = CString::new(msg).unwrap_or_default();
-            unsafe { cb(c.as_ptr()) };
-        }
+    let list = NakVerbOverrideList {
+        verbs: results.as_mut_ptr(),
+        count: results.len(),
     };
+    std::mem::forget(results);
+    list
+}
 
-    let app_id_opt = if app_id == 0 { None } else { Some(app_id) };
-
-    match nak_rust::installers::apply_wine_registry_settings(
-        Path::new(prefix),
-        &proton,
-        &log_fn,
-        app_id_opt,
-    ) {
-        Ok(()) => ptr::null_mut(),
-        Err(e) => error_to_cstring(e),
+/// Free a NakVerbOverrideList
+///
+/// # Safety
+/// `list` must be exactly as returned by NaK and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_verb_override_list_free(list: NakVerbOverrideList) {
+    if list.verbs.is_null() {
+        return;
+    }
+    let verbs = unsafe { Vec::from_raw_parts(list.verbs, list.count, list.count) };
+    for v in verbs {
+        free_if_nonnull(v.verb);
     }
 }
 
-/// Apply a game's registry entry with a custom install path.
+/// Restore missing DllOverrides of installed winetricks verbs (nothing may be
+/// running in the prefix). `verbs`/`count` as for nak_verify_verb_overrides.
 ///
-/// Looks up the game by name in KNOWN_GAMES, writes the registry entry
-/// pointing to `install_path`. Returns null on success, or an error message.
+/// Writes the number of overrides set to `out_fixed` (if non-null).
+/// Returns null on success, or an error message (caller must free with nak_string_free).
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string. `verbs` must be null or
+/// point to `count` strings, each null or NUL-terminated. `out_fixed` must be null or
+/// valid for writes.
 #[no_mangle]
-pub unsafe extern "C" fn nak_apply_registry_for_game_path(
+pub unsafe extern "C" fn nak_fix_verb_overrides(
     prefix_path: *const c_char,
-    proton_name: *const c_char,
-    proton_path: *const c_char,
-    game_name: *const c_char,
-    install_path: *const c_char,
-    log_cb: NakLogCallback,
+    verbs: *const *const c_char,
+    count: usize,
+    out_fixed: *mut usize,
 ) -> *mut c_char {
     let prefix = unsafe { from_cstr(prefix_path) };
-    let _proton_name = unsafe { from_cstr(proton_name) };
-    let proton_path_str = unsafe { from_cstr(proton_path) };
-    let game = unsafe { from_cstr(game_name) };
-    let install = unsafe { from_cstr(install_path) };
-
-    let protons = nak_rust::steam::find_steam_protons();
-    let proton = match protons
-        .iter()
-        .find(|p| p.path.to_string_lossy() == proton_path_str)
-    {
-        Some(p) => p.clone(),
-        None => {
-            return to_cstring(&format!(
-                "Proton not found at path: {}",
-                proton_path_str
-            ));
-        }
+    let verbs = if verbs.is_null() {
+        nak_rust::deps::STANDARD_VERBS.to_vec()
+    } else {
+        unsafe { cstr_array(verbs, count) }
     };
-
-    let log_fn = move |msg: String| {
-        if let Some(cb) = log_cb {
-            let c = CString::new(msg).unwrap_or_default();
-            unsafe { cb(c.as_ptr()) };
+    match nak_rust::installers::fix_verb_overrides(Path::new(prefix), &verbs) {
+        Ok(fixed) => {
+            if !out_fixed.is_null() {
+                *out_fixed = fixed;
+            }
+            ptr::null_mut()
         }
-    };
-
-    match nak_rust::installers::apply_registry_for_game_path(
-        Path::new(prefix),
-        &proton,
-        game,
-        Path::new(install),
-        &log_fn,
-    ) {
-        Ok(()) => ptr::null_mut(),
-        Err(e) => to_cstring(&e),
+        Err(e) => error_to_cstring(e),
     }
 }
 
 // ============================================================================
-// Tier 5: Prefix Symlinks
+// Tier 9: System Diagnostics
 // ============================================================================
 
-/// Ensure the Temp directory exists in the Wine prefix's AppData/Local.
-///
-/// MO2 and other tools require AppData/Local/Temp to exist.
+/// 32-bit library availability (C-compatible)
+#[repr(C)]
+pub struct NakMultilibStatus {
+    pub detected: c_int, // 0 if detection wasn't possible (other fields unreliable)
+    pub vulkan: c_int,
+    pub gl: c_int,
+    pub missing: NakStringList,
+}
+
+/// Check for the 32-bit Vulkan/GL libraries Proton needs
 #[no_mangle]
-pub unsafe extern "C" fn nak_ensure_temp_directory(prefix_path: *const c_char) {
-    let prefix = unsafe { from_cstr(prefix_path) };
-    nak_rust::installers::symlinks::ensure_temp_directory(Path::new(prefix));
+pub extern "C" fn nak_check_multilib() -> NakMultilibStatus {
+    let status = nak_rust::installers::check_multilib();
+    NakMultilibStatus {
+        detected: status.detected as c_int,
+        vulkan: status.vulkan as c_int,
+        gl: status.gl as c_int,
+        missing: to_string_list(status.missing),
+    }
 }
 
-/// Detect installed games and create symlinks from the prefix to game prefixes.
+/// Free a NakMultilibStatus
 ///
-/// This is a convenience wrapper that detects games and creates symlinks in one call.
+/// # Safety
+/// `status` must be exactly as returned by NaK and not freed before.
 #[no_mangle]
-pub unsafe extern "C" fn nak_create_game_symlinks_auto(prefix_path: *const c_char) {
-    let prefix = unsafe { from_cstr(prefix_path) };
-    nak_rust::installers::symlinks::create_game_symlinks_auto(Path::new(prefix));
+pub unsafe extern "C" fn nak_multilib_status_free(status: NakMultilibStatus) {
+    unsafe { nak_string_list_free(status.missing) };
 }
 
-// ============================================================================
-// Tier 6: Logging
-// ============================================================================
-
-/// Callback for NaK log messages: fn(level: *const c_char, message: *const c_char)
-///
-/// Levels: "info", "warning", "error", "install", "action", "download"
-pub type NakLogLevelCallback = Option<unsafe extern "C" fn(*const c_char, *const c_char)>;
+/// Vulkan device extensions supported by any GPU (empty if vulkaninfo is
+/// unavailable). Free with nak_string_list_free.
+#[no_mangle]
+pub extern "C" fn nak_vulkan_extensions() -> NakStringList {
+    to_string_list(nak_rust::installers::vulkan_extensions())
+}
 
-/// Initialize NaK logging with a callback.
+/// Vulkan extensions the Proton at `proton_path` needs but the driver lacks
+/// (alternatives joined with " or "). Empty when nothing is missing or the
+/// driver can't be queried. Free with nak_string_list_free.
 ///
-/// The callback receives (level, message) for all NaK internal log messages.
-/// Call once at startup before any other nak_* functions.
+/// # Safety
+/// `proton_path` must be null or a valid NUL-terminated string.
 #[no_mangle]
-pub unsafe extern "C" fn nak_init_logging(cb: NakLogLevelCallback) {
-    if let Some(callback) = cb {
-        nak_rust::logging::set_log_callback(move |level: &str, message: &str| {
-            let c_level = CString::new(level).unwrap_or_default();
-            let c_msg = CString::new(message).unwrap_or_default();
-            unsafe { callback(c_level.as_ptr(), c_msg.as_ptr()) };
+pub unsafe extern "C" fn nak_proton_vulkan_missing(proton_path: *const c_char) -> NakStringList {
+    let proton_path = unsafe { from_cstr(proton_path) };
+    let proton = nak_rust::steam::find_steam_protons()
+        .into_iter()
+        .find(|p| p.path.to_string_lossy() == proton_path)
+        .unwrap_or_else(|| {
+            let path = PathBuf::from(proton_path);
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            nak_rust::steam::SteamProton {
+                config_name: name.clone(),
+                name,
+                path,
+                is_steam_proton: false,
+                is_experimental: false,
+            }
         });
+
+    match nak_rust::installers::proton_vulkan_ok(&proton) {
+        Ok(()) => to_string_list(Vec::new()),
+        Err(missing) => to_string_list(missing),
     }
 }
 
+/// Generate a JSON report of detected games, Protons, platform and warnings
+/// for pasting into a bug report. Non-zero `redact` strips the home
+/// directory and username from paths.
+///
+/// Returns a newly allocated string (caller must free with nak_string_free).
+#[no_mangle]
+pub extern "C" fn nak_generate_report(redact: c_int) -> *mut c_char {
+    to_cstring(&nak_rust::report::generate_report(redact != 0))
+}
+
 // ============================================================================
-// Tier 7: DXVK Configuration
+// Tier 10: Instance Locking
 // ============================================================================
 
-/// Ensure the DXVK config file exists, downloading if necessary.
+static APP_LOCK: LazyLock<Mutex<Option<nak_rust::config::NakLock>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+/// Take the app-wide NaK lock, failing fast if another instance holds it.
 ///
+/// Calling this again while already holding the lock succeeds.
 /// Returns null on success, or an error message (caller must free with nak_string_free).
 #[no_mangle]
-pub extern "C" fn nak_ensure_dxvk_conf() -> *mut c_char {
-    match nak_rust::dxvk::ensure_dxvk_conf() {
-        Ok(_) => ptr::null_mut(),
+pub extern "C" fn nak_acquire_lock() -> *mut c_char {
+    let mut held = APP_LOCK.lock().unwrap();
+    if held.is_some() {
+        return ptr::null_mut();
+    }
+    match nak_rust::config::acquire_lock() {
+        Ok(lock) => {
+            *held = Some(lock);
+            ptr::null_mut()
+        }
         Err(e) => error_to_cstring(e),
     }
 }
 
-/// Get the path to the DXVK config file.
+/// Release the app-wide NaK lock (no-op if not held)
+#[no_mangle]
+pub extern "C" fn nak_release_lock() {
+    APP_LOCK.lock().unwrap().take();
+}
+
+/// Whether any process is running with this Wine prefix: 1 yes, 0 no
 ///
-/// Returns a newly allocated string (caller must free with nak_string_free).
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string.
 #[no_mangle]
-pub extern "C" fn nak_get_dxvk_conf_path() -> *mut c_char {
-    let path = nak_rust::dxvk::get_dxvk_conf_path();
-    to_cstring(&path.to_string_lossy())
+pub unsafe extern "C" fn nak_prefix_in_use(prefix_path: *const c_char) -> c_int {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    nak_rust::installers::prefix_in_use(Path::new(prefix)) as c_int
+}
+
+/// Find a mod manager running against a prefix.
+///
+/// Returns its display name (e.g. "Mod Organizer 2"; free with
+/// nak_string_free) and writes its pid to `out_pid` (may be null),
+/// or returns null if none is running.
+///
+/// # Safety
+/// `prefix_path` must be null or a valid NUL-terminated string. `out_pid` must be null
+/// or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn nak_running_mod_manager(
+    prefix_path: *const c_char,
+    out_pid: *mut u32,
+) -> *mut c_char {
+    let prefix = unsafe { from_cstr(prefix_path) };
+    let Some(running) = nak_rust::installers::running_mod_manager(Path::new(prefix)) else {
+        return ptr::null_mut();
+    };
+    if !out_pid.is_null() {
+        *out_pid = running.pid;
+    }
+    to_cstring(running.kind.display_name())
 }
 
 // ============================================================================
 // General: String free
 // ============================================================================
 
+/// A list of owned strings
+#[repr(C)]
+pub struct NakStringList {
+    pub items: *mut *mut c_char,
+    pub count: usize,
+}
+
 /// Free a string returned by any nak_* function
+///
+/// # Safety
+/// `s` must be null or a string returned by a nak_* function, not freed before.
 #[no_mangle]
 pub unsafe extern "C" fn nak_string_free(s: *mut c_char) {
     free_if_nonnull(s);
 }
+
+/// Free a NakStringList returned by any nak_* function
+///
+/// # Safety
+/// `list` must be exactly as returned by NaK and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_string_list_free(list: NakStringList) {
+    if list.items.is_null() {
+        return;
+    }
+    let items = unsafe { Vec::from_raw_parts(list.items, list.count, list.count) };
+    for item in items {
+        free_if_nonnull(item);
+    }
+}
+
+/// Free a bare string array of `count` items returned by a nak_* function
+///
+/// # Safety
+/// `arr` and `count` must be exactly as returned by a nak_* function, not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn nak_string_array_free(arr: *mut *mut c_char, count: usize) {
+    unsafe { nak_string_list_free(NakStringList { items: arr, count }) };
+}
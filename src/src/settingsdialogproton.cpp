@@ -562,7 +562,7 @@ void ProtonSettingsTab::showGameRegistryDialog()
         char* error = nak_apply_registry_for_game_path(
             prefixUtf8.constData(), protonNmUtf8.constData(),
             protonPthUtf8.constData(), gameUtf8.constData(),
-            pathUtf8.constData(), &ProtonSettingsTab::logCallback);
+            pathUtf8.constData(), &ProtonSettingsTab::logCallback, nullptr);
 
         InstallResult r;
         if (error != nullptr) {
@@ -656,7 +656,7 @@ void ProtonSettingsTab::startInstallTask(uint32_t appId, const QString& prefixPa
         prefixPathUtf8.constData(), protonNameUtf8.constData(),
         protonPathUtf8.constData(), &ProtonSettingsTab::statusCallback,
         &ProtonSettingsTab::logCallback, &ProtonSettingsTab::progressCallback,
-        &cancelFlag, appId);
+        &cancelFlag, appId, nullptr);
 
     InstallResult r;
     if (error != nullptr) {